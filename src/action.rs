@@ -0,0 +1,362 @@
+use crate::tree::fuzzy_match;
+
+/// User-invokable operations, shared by keybindings and menus so every
+/// entry point dispatches through the same handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveDown,
+    MoveUp,
+    Expand,
+    Collapse,
+    Top,
+    Bottom,
+    PageDown,
+    PageUp,
+    SwitchPane,
+    Search,
+    SetPattern,
+    ScopePattern,
+    CycleTypeFilter,
+    FilterKeys,
+    GoToKey,
+    ExpandAll,
+    CollapseAll,
+    ShrinkTree,
+    GrowTree,
+    Rescan,
+    OpenMenu,
+    CommandPalette,
+    SelectDb,
+    Subscribe,
+    ServerInfo,
+    CreateKey,
+    Edit,
+    Import,
+    Delete,
+    DeleteFolder,
+    RemoveElement,
+    Refresh,
+    TogglePin,
+    ToggleBase64,
+    ToggleLineNumbers,
+    ToggleWrap,
+    ToggleSort,
+    LoadFull,
+    CopyPath,
+    CopyValue,
+    SetTtl,
+    Rename,
+    CopyKey,
+    Help,
+    Back,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: &'static [Action] = &[
+        Action::MoveDown,
+        Action::MoveUp,
+        Action::Expand,
+        Action::Collapse,
+        Action::Top,
+        Action::Bottom,
+        Action::PageDown,
+        Action::PageUp,
+        Action::SwitchPane,
+        Action::Search,
+        Action::SetPattern,
+        Action::ScopePattern,
+        Action::CycleTypeFilter,
+        Action::FilterKeys,
+        Action::GoToKey,
+        Action::ExpandAll,
+        Action::CollapseAll,
+        Action::ShrinkTree,
+        Action::GrowTree,
+        Action::Rescan,
+        Action::OpenMenu,
+        Action::CommandPalette,
+        Action::SelectDb,
+        Action::Subscribe,
+        Action::ServerInfo,
+        Action::CreateKey,
+        Action::Edit,
+        Action::Import,
+        Action::Delete,
+        Action::DeleteFolder,
+        Action::RemoveElement,
+        Action::Refresh,
+        Action::TogglePin,
+        Action::ToggleBase64,
+        Action::ToggleLineNumbers,
+        Action::ToggleWrap,
+        Action::ToggleSort,
+        Action::LoadFull,
+        Action::CopyPath,
+        Action::CopyValue,
+        Action::SetTtl,
+        Action::Rename,
+        Action::CopyKey,
+        Action::Help,
+        Action::Back,
+        Action::Quit,
+    ];
+
+    /// Actions offered by the context menu, in menu order.
+    pub const MENU: &'static [Action] = &[
+        Action::Edit,
+        Action::Import,
+        Action::Delete,
+        Action::DeleteFolder,
+        Action::Refresh,
+        Action::TogglePin,
+        Action::ScopePattern,
+        Action::CopyPath,
+        Action::CopyValue,
+        Action::SetTtl,
+        Action::Rename,
+        Action::CopyKey,
+        Action::CreateKey,
+    ];
+
+    /// Name used for the action in the `[keybindings]` config table.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MoveDown => "move_down",
+            Action::MoveUp => "move_up",
+            Action::Expand => "expand",
+            Action::Collapse => "collapse",
+            Action::Top => "top",
+            Action::Bottom => "bottom",
+            Action::PageDown => "page_down",
+            Action::PageUp => "page_up",
+            Action::SwitchPane => "switch_pane",
+            Action::Search => "search",
+            Action::SetPattern => "set_pattern",
+            Action::ScopePattern => "scope_pattern",
+            Action::CycleTypeFilter => "type_filter",
+            Action::FilterKeys => "filter_keys",
+            Action::GoToKey => "go_to_key",
+            Action::ExpandAll => "expand_all",
+            Action::CollapseAll => "collapse_all",
+            Action::ShrinkTree => "shrink_tree",
+            Action::GrowTree => "grow_tree",
+            Action::Rescan => "rescan",
+            Action::OpenMenu => "menu",
+            Action::CommandPalette => "command_palette",
+            Action::SelectDb => "select_db",
+            Action::Subscribe => "subscribe",
+            Action::ServerInfo => "server_info",
+            Action::CreateKey => "create_key",
+            Action::Edit => "edit",
+            Action::Import => "import",
+            Action::Delete => "delete",
+            Action::DeleteFolder => "delete_folder",
+            Action::RemoveElement => "remove_element",
+            Action::Refresh => "refresh",
+            Action::TogglePin => "toggle_pin",
+            Action::ToggleBase64 => "toggle_base64",
+            Action::ToggleLineNumbers => "toggle_line_numbers",
+            Action::ToggleWrap => "toggle_wrap",
+            Action::ToggleSort => "toggle_sort",
+            Action::LoadFull => "load_full",
+            Action::CopyPath => "copy_path",
+            Action::CopyValue => "copy_value",
+            Action::SetTtl => "set_ttl",
+            Action::Rename => "rename",
+            Action::CopyKey => "copy_key",
+            Action::Help => "help",
+            Action::Back => "back",
+            Action::Quit => "quit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Self::ALL.iter().copied().find(|a| a.name() == name)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveDown => "Move down",
+            Action::MoveUp => "Move up",
+            Action::Expand => "Expand folder / select key",
+            Action::Collapse => "Collapse folder",
+            Action::Top => "Go to top",
+            Action::Bottom => "Go to bottom",
+            Action::PageDown => "Page down",
+            Action::PageUp => "Page up",
+            Action::SwitchPane => "Switch pane",
+            Action::Search => "Search tree",
+            Action::SetPattern => "Set SCAN pattern",
+            Action::ScopePattern => "Scan from here",
+            Action::CycleTypeFilter => "Cycle type filter",
+            Action::FilterKeys => "Filter by type or glob",
+            Action::GoToKey => "Go to key by name",
+            Action::ExpandAll => "Expand all",
+            Action::CollapseAll => "Collapse all",
+            Action::ShrinkTree => "Narrow the tree pane",
+            Action::GrowTree => "Widen the tree pane",
+            Action::Rescan => "Rescan keys",
+            Action::OpenMenu => "Action menu",
+            Action::CommandPalette => "Command palette",
+            Action::SelectDb => "Switch database",
+            Action::Subscribe => "Monitor Pub/Sub channel",
+            Action::ServerInfo => "Server info",
+            Action::CreateKey => "Create key here",
+            Action::Edit => "Edit value",
+            Action::Import => "Import value from file",
+            Action::Delete => "Delete key or folder",
+            Action::DeleteFolder => "Delete folder and all keys under it",
+            Action::RemoveElement => "Remove element",
+            Action::Refresh => "Refresh value",
+            Action::TogglePin => "Pin/unpin value pane",
+            Action::ToggleBase64 => "Toggle base64 decoding",
+            Action::ToggleLineNumbers => "Toggle line numbers",
+            Action::ToggleWrap => "Toggle line wrapping",
+            Action::ToggleSort => "Sort members by name",
+            Action::LoadFull => "Load truncated value in full",
+            Action::CopyPath => "Copy key/prefix",
+            Action::CopyValue => "Copy value",
+            Action::SetTtl => "Set TTL",
+            Action::Rename => "Rename key",
+            Action::CopyKey => "Copy key to a new name",
+            Action::Help => "Help",
+            Action::Back => "Stop monitor / clear search / quit",
+            Action::Quit => "Quit",
+        }
+    }
+
+    pub fn key_hint(self) -> &'static str {
+        match self {
+            Action::MoveDown => "j",
+            Action::MoveUp => "k",
+            Action::Expand => "l",
+            Action::Collapse => "h",
+            Action::Top => "g",
+            Action::Bottom => "G",
+            Action::PageDown => "^d",
+            Action::PageUp => "^u",
+            Action::SwitchPane => "Tab",
+            Action::Search => "/",
+            Action::SetPattern => "f",
+            Action::ScopePattern => "*",
+            Action::CycleTypeFilter => "F",
+            Action::FilterKeys => "^f",
+            Action::GoToKey => "o",
+            Action::ExpandAll => "E",
+            Action::CollapseAll => "C",
+            Action::ShrinkTree => "<",
+            Action::GrowTree => ">",
+            Action::Rescan => "R",
+            Action::OpenMenu => "m",
+            Action::CommandPalette => ":",
+            Action::SelectDb => "s",
+            Action::Subscribe => "P",
+            Action::ServerInfo => "I",
+            Action::CreateKey => "a",
+            Action::Edit => "e",
+            Action::Import => "i",
+            Action::Delete => "d",
+            Action::DeleteFolder => "D",
+            Action::RemoveElement => "d",
+            Action::Refresh => "r",
+            Action::TogglePin => "p",
+            Action::ToggleBase64 => "b",
+            Action::ToggleLineNumbers => "L",
+            Action::ToggleWrap => "w",
+            Action::ToggleSort => "S",
+            Action::LoadFull => "X",
+            Action::CopyPath => "y",
+            Action::CopyValue => "Y",
+            Action::SetTtl => "t",
+            Action::Rename => "n",
+            Action::CopyKey => "c",
+            Action::Help => "?",
+            Action::Back => "Esc",
+            Action::Quit => "q",
+        }
+    }
+
+    /// Whether the action modifies data and is unavailable in read-only mode.
+    pub fn is_write(self) -> bool {
+        matches!(
+            self,
+            Action::Edit
+                | Action::Import
+                | Action::Delete
+                | Action::DeleteFolder
+                | Action::RemoveElement
+                | Action::SetTtl
+                | Action::Rename
+                | Action::CopyKey
+                | Action::CreateKey
+        )
+    }
+
+    /// Whether the action needs a key (rather than a folder) selected.
+    pub fn needs_key(self) -> bool {
+        matches!(
+            self,
+            Action::Edit
+                | Action::Import
+                | Action::RemoveElement
+                | Action::Refresh
+                | Action::TogglePin
+                | Action::ToggleBase64
+                | Action::LoadFull
+                | Action::CopyValue
+                | Action::SetTtl
+                | Action::Rename
+                | Action::CopyKey
+        )
+    }
+
+    /// Whether the command palette offers the action: everything except
+    /// moving the cursor and opening menus.
+    fn in_palette(self) -> bool {
+        !matches!(
+            self,
+            Action::MoveDown
+                | Action::MoveUp
+                | Action::Expand
+                | Action::Collapse
+                | Action::Top
+                | Action::Bottom
+                | Action::PageDown
+                | Action::PageUp
+                | Action::OpenMenu
+                | Action::CommandPalette
+                | Action::Back
+        )
+    }
+
+    /// Command palette entries applicable to the current selection.
+    pub fn palette(is_key: bool, readonly: bool) -> Vec<Action> {
+        Self::ALL
+            .iter()
+            .copied()
+            .filter(|a| a.in_palette())
+            .filter(|a| is_key || !a.needs_key())
+            .filter(|a| !readonly || !a.is_write())
+            .collect()
+    }
+
+    /// `actions` whose label or config name fuzzy-matches `query`.
+    pub fn filter(actions: &[Action], query: &str) -> Vec<Action> {
+        actions
+            .iter()
+            .copied()
+            .filter(|a| fuzzy_match(a.label(), query) || fuzzy_match(a.name(), query))
+            .collect()
+    }
+
+    /// Menu actions applicable to the selected node.
+    pub fn for_node(is_key: bool, readonly: bool) -> Vec<Action> {
+        Self::MENU
+            .iter()
+            .copied()
+            .filter(|a| is_key || !a.needs_key())
+            .filter(|a| !readonly || !a.is_write())
+            .collect()
+    }
+}
@@ -1,33 +1,171 @@
-use crate::config::{AppConfig, ProtectedNamespace, ProtectionLevel};
-use crate::editor::ExternalEditor;
-use crate::redis_client::{RedisClient, RedisType, RedisValue};
-use crate::tree::{TreeBuilder, TreeNode};
-use crate::ui::dialogs::Dialog;
-use crate::ui::layout::AppLayout;
+use crate::action::Action;
+use crate::clipboard::{Clipboard, CopyMethod};
+use crate::config::{
+    clamp_tree_percent, AppConfig, ConnectionConfig, MatchMode, ProtectedNamespace, ProtectionLevel,
+};
+use crate::editor::{EditOutcome, ExternalEditor};
+use crate::format::decode_base64;
+use crate::keymap::{motion_target, parse_motion, Motion, MOTION_TIMEOUT};
+use crate::redis_client::key::{display_key, escape_key, parse_key};
+use crate::redis_client::{
+    error_message, glob_escape, is_busy_key, GetExExpiry, RedisClient, RedisType, RedisValue,
+    ServerInfo, ServerRole, ValuePage, ValueSize, VALUE_PAGE_SIZE,
+};
+use crate::session::{load_session, save_session, session_key, SessionState};
+use crate::tree::{
+    expand_to_key, node_at_path, node_at_path_mut, path_names, FolderStats, KeyFilter, TreeBuilder,
+    TreeNode,
+};
+use crate::tunnel::SshTunnel;
+use crate::ui::breadcrumb::Breadcrumb;
+use crate::ui::dialogs::{
+    diff_preview_height, parse_members, server_info_height, server_info_lines, CreateKeyField,
+    Dialog, ValueElement, CREATE_KEY_TYPES,
+};
+use crate::ui::folder_view::FolderView;
+use crate::ui::info_bar::{remaining_ttl, TTL_MISSING};
+use crate::ui::layout::{clamp_scroll, visible_offset, AppLayout, TREE_PERCENT_STEP};
+use crate::ui::monitor_view::MonitorView;
 use crate::ui::theme::Theme;
-use crate::ui::tree_view::{TreeView, TreeViewState};
+use crate::ui::tree_view::{TreeSnapshot, TreeView, TreeViewState};
+use crate::ui::value_view::{render_string, StringLines, ValueSearch, ValueView};
 
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use futures_util::StreamExt;
+use ratatui::layout::{Constraint, Layout, Position, Rect};
 use ratatui::DefaultTerminal;
-use std::time::Duration;
-use tokio::sync::mpsc;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::MissedTickBehavior;
 
 pub struct App {
     config: AppConfig,
     tree_nodes: Vec<TreeNode>,
+    loaded_keys: Vec<(String, RedisType)>,
+    /// Index into `loaded_keys` of each key whose type is still being
+    /// fetched
+    pending_types: HashMap<String, usize>,
+    /// Batches were merged since the tree was last sorted and flattened
+    tree_dirty: bool,
+    /// Prefixes of lazily loaded folders not scanned yet
+    unloaded_prefixes: BTreeSet<String>,
+    /// Tree state from before a rescan, applied while the scan fills the
+    /// tree; its selection is dropped once the user moves
+    rescan_snapshot: Option<TreeSnapshot>,
+    /// `rescan_snapshot` comes from the saved session; show its selection
+    /// once restored
+    restoring_session: bool,
+    type_filter: Option<RedisType>,
     tree_state: TreeViewState,
+    /// Keys of a vim-style motion typed so far in the tree pane (`5`, `g`)
+    pending_motion: String,
+    /// When the first key of `pending_motion` was typed
+    motion_started: Option<Instant>,
     selected_value: Option<RedisValue>,
+    /// `selected_value` rendered once if it is a string, so frames don't
+    /// decode it again
+    string_lines: Option<StringLines>,
     selected_type: Option<RedisType>,
     selected_ttl: Option<i64>,
+    /// When `selected_ttl` was read, so the info bar can count it down
+    ttl_loaded_at: Option<Instant>,
+    selected_size: Option<ValueSize>,
+    selected_memory: Option<i64>,
+    selected_encoding: Option<String>,
+    /// Full byte length of the displayed string when only a prefix was
+    /// loaded because of the size guard
+    value_truncated: Option<usize>,
+    pinned_key: Option<String>,
+    clipboard: Clipboard,
+    base64_decoded: bool,
+    /// Highlighted element of a collection value, relative to `value_page`
+    element_index: usize,
+    /// Loaded window of a paged collection value
+    value_page: Option<ValuePage>,
+    /// Absolute element to highlight once a requested page arrives
+    pending_element: Option<usize>,
+    /// Command run once the open `Dialog::Confirm` or `Dialog::Protection`
+    /// is confirmed
+    pending_action: Option<RedisCommand>,
+    /// Protected key the user just acknowledged; lets the resumed action
+    /// past `allow_write` once
+    protection_ack: Option<String>,
     theme: Theme,
     current_dialog: Option<Dialog>,
+    prompt: Option<Prompt>,
+    scan_pattern: String,
+    scanning: bool,
+    /// Whether the Redis task currently holds a working connection
+    connected: bool,
+    /// The server is a replica; writes are refused up front
+    replica: bool,
+    /// Read-only mode as configured, before replica detection forced it on
+    requested_readonly: bool,
+    /// Server-side key count from the last full scan
+    db_size: Option<u64>,
+    /// Round-trip time of the last `PING`, cleared while disconnected
+    latency: Option<Duration>,
+    /// Incremented per scan so batches from a superseded scan are dropped
+    scan_id: u64,
+    /// `scan_id` as seen by the type lookup worker, which skips batches of
+    /// a superseded scan
+    current_scan: Arc<AtomicU64>,
+    /// Incremented per value load so a slow response for a key the
+    /// selection has since left is dropped
+    value_request_id: u64,
+    tick: usize,
+    /// Pane areas from the last draw, for mapping mouse events
+    layout: AppLayout,
+    /// Wrap long value lines; when off, the value pane scrolls sideways
+    wrap_enabled: bool,
+    /// Show set, hash and sorted set members sorted by name
+    sort_members: bool,
+    /// Columns scrolled past in the value pane while wrapping is off
+    value_hscroll: u16,
+    /// Width of the tree pane, in percent of the window
+    tree_percent: u16,
     value_scroll: u16,
+    /// Search within the value pane; kept across keys so `n`/`N` carry on
+    value_search: Option<ValueSearch>,
     focus: Focus,
     should_quit: bool,
     status_message: String,
     redis_tx: mpsc::Sender<RedisCommand>,
+    ui_tx: mpsc::Sender<UiMessage>,
     ui_rx: mpsc::Receiver<UiMessage>,
+    monitor: Option<PubSubMonitor>,
+    /// Prefix and counts of the selected folder, shown in the value pane
+    /// when the folder has no key of its own
+    folder_stats: Option<(String, FolderStats)>,
+    /// SSH port forward the connection goes through, closed on drop
+    tunnel: Option<SshTunnel>,
+    tunnel_up: bool,
+    /// Notes about the startup settings, added to the status on connecting
+    startup_warnings: Vec<String>,
+    /// When the last full scan finished, shown in the header with
+    /// auto-refresh on
+    last_refresh: Option<Instant>,
+    /// When the next auto-refresh scan is due
+    next_refresh: Option<Instant>,
+    /// When the selected value is due to load, once movement stops
+    pending_load: Option<Instant>,
+    /// Keys written by commands sent to the Redis task and not yet
+    /// finished, once per command
+    pending_writes: Vec<String>,
+    /// State changed since the last draw
+    dirty: bool,
+    last_draw: Instant,
+    /// TTL countdown value on screen, redrawn as soon as it ticks
+    drawn_ttl: Option<i64>,
+    /// Last key, mouse or Redis message, which keeps polling fast
+    last_activity: Instant,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -36,26 +174,311 @@ pub enum Focus {
     Value,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKind {
+    Pattern,
+    Search,
+    /// Text search within the displayed value
+    ValueSearch,
+    /// Pub/Sub channel, or pattern when it contains glob characters
+    Subscribe,
+    /// `type:` and glob terms filtering the tree
+    KeyFilter,
+    /// Full name of a key to select
+    GoToKey,
+}
+
+/// Most Pub/Sub messages kept in the monitor buffer.
+const MONITOR_BUFFER: usize = 1000;
+
+/// Pub/Sub messages waiting for the UI; ones arriving while it is full are
+/// dropped rather than holding up the subscription.
+const MONITOR_QUEUE: usize = 256;
+
+/// Most queued messages handled between two frames, so a burst cannot
+/// starve drawing and input.
+const MESSAGES_PER_FRAME: usize = 256;
+
+/// How often the Redis task pings the server to measure latency.
+const PING_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Frames of the spinner shown while scanning or writing.
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+/// How long the loop waits for input when nothing is happening. Replies
+/// to recent input are still picked up at the full frame rate.
+const IDLE_POLL: Duration = Duration::from_millis(250);
+
+/// How long after input or a Redis message the loop keeps polling at the
+/// full frame rate, so replies show promptly.
+const ACTIVE_WINDOW: Duration = Duration::from_secs(1);
+
+/// How long the tree selection must stay put before its value is loaded.
+const LOAD_DEBOUNCE: Duration = Duration::from_millis(80);
+
+/// Scan batches waiting for their types before the scan blocks on the
+/// lookup worker
+const TYPE_LOOKUP_QUEUE: usize = 16;
+
+/// A live Pub/Sub subscription, shown in place of the value pane.
+struct PubSubMonitor {
+    target: String,
+    messages: VecDeque<(String, String)>,
+    /// Channel and payload of messages not yet moved into `messages`
+    incoming: mpsc::Receiver<(String, String)>,
+    /// Dropping or firing this unsubscribes and ends the monitor task
+    stop: oneshot::Sender<()>,
+}
+
+/// Single-line input shown in the status bar.
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub input: String,
+}
+
 #[derive(Debug)]
 pub enum RedisCommand {
-    ScanKeys { pattern: String },
-    GetValue { key: String },
-    SetValue { key: String, value: Vec<u8> },
-    DeleteKey { key: String },
+    ScanKeys {
+        scan_id: u64,
+        pattern: String,
+    },
+    /// Scan the keys under a lazily loaded folder
+    ScanPrefix {
+        scan_id: u64,
+        prefix: String,
+    },
+    GetValue {
+        request_id: u64,
+        key: String,
+    },
+    /// Load a string value in full, ignoring the size guard
+    GetFullValue {
+        request_id: u64,
+        key: String,
+    },
+    /// Load `limit` elements of a collection value starting at `offset`
+    GetValuePage {
+        request_id: u64,
+        key: String,
+        offset: usize,
+        limit: usize,
+    },
+    SetValue {
+        key: String,
+        value: Vec<u8>,
+    },
+    /// Create a string key, failing if `key` already exists
+    CreateString {
+        key: String,
+        value: Vec<u8>,
+    },
+    SetHashField {
+        key: String,
+        field: String,
+        value: String,
+    },
+    DeleteKey {
+        key: String,
+    },
+    DeleteKeys {
+        keys: Vec<String>,
+    },
+    /// Delete every key under `prefix`, including keys not loaded in the
+    /// tree, except keys matching one of `keep`
+    DeletePrefix {
+        prefix: String,
+        keep: Vec<ProtectedNamespace>,
+    },
+    /// Copy `source` to `dest` with DUMP/RESTORE, replacing an existing
+    /// `dest` only with `replace`
+    CopyKey {
+        source: String,
+        dest: String,
+        replace: bool,
+    },
+    /// Rename `old` to `new`, replacing an existing `new` only with
+    /// `replace`
+    RenameKey {
+        old: String,
+        new: String,
+        replace: bool,
+    },
+    /// Set the TTL in seconds (at least 1), or remove it with `None`
+    SetTtl {
+        key: String,
+        seconds: Option<i64>,
+    },
+    SelectDb {
+        db: u8,
+    },
+    /// Read `INFO` for the server info dialog
+    GetInfo,
+    /// Create a list, set, hash or sorted set holding `value`
+    CreateCollection {
+        key: String,
+        value: RedisValue,
+    },
+    /// Check whether a key that was not scanned exists, for go-to-key
+    LocateKey {
+        key: String,
+    },
+    SetListElement {
+        key: String,
+        index: usize,
+        value: String,
+    },
+    RemoveListElement {
+        key: String,
+        index: usize,
+    },
+    RemoveSetMember {
+        key: String,
+        member: String,
+    },
+    SetZSetScore {
+        key: String,
+        member: String,
+        score: f64,
+    },
+}
+
+impl RedisCommand {
+    /// Keys this command writes to; empty for reads.
+    fn written_keys(&self) -> Vec<String> {
+        match self {
+            RedisCommand::SetValue { key, .. }
+            | RedisCommand::CreateString { key, .. }
+            | RedisCommand::SetHashField { key, .. }
+            | RedisCommand::DeleteKey { key }
+            | RedisCommand::SetTtl { key, .. }
+            | RedisCommand::CreateCollection { key, .. }
+            | RedisCommand::SetListElement { key, .. }
+            | RedisCommand::RemoveListElement { key, .. }
+            | RedisCommand::RemoveSetMember { key, .. }
+            | RedisCommand::SetZSetScore { key, .. } => vec![key.clone()],
+            RedisCommand::DeleteKeys { keys } => keys.clone(),
+            RedisCommand::DeletePrefix { prefix, .. } => vec![prefix.clone()],
+            RedisCommand::CopyKey { dest, .. } => vec![dest.clone()],
+            RedisCommand::RenameKey { old, new, .. } => vec![old.clone(), new.clone()],
+            RedisCommand::ScanKeys { .. }
+            | RedisCommand::ScanPrefix { .. }
+            | RedisCommand::GetValue { .. }
+            | RedisCommand::GetFullValue { .. }
+            | RedisCommand::GetValuePage { .. }
+            | RedisCommand::SelectDb { .. }
+            | RedisCommand::GetInfo
+            | RedisCommand::LocateKey { .. } => Vec::new(),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum UiMessage {
-    KeysLoaded(Vec<(String, RedisType)>),
+    /// Keys from one SCAN iteration of scan `scan_id`, typed
+    /// `RedisType::Unknown` until a `TypesResolved` follows
+    KeysBatch {
+        scan_id: u64,
+        batch: Vec<(String, RedisType)>,
+    },
+    ScanComplete {
+        scan_id: u64,
+        total: usize,
+    },
+    /// Types of keys from an earlier batch of scan `scan_id`
+    TypesResolved {
+        scan_id: u64,
+        types: Vec<(String, RedisType)>,
+    },
+    /// The type lookup for `keys` of scan `scan_id` failed
+    TypesFailed {
+        scan_id: u64,
+        keys: Vec<String>,
+        error: String,
+    },
+    ScanFailed(String),
+    /// `DBSIZE` after a full scan
+    DbSize(u64),
+    /// Keys under `prefix` from a lazy folder load
+    PrefixBatch {
+        scan_id: u64,
+        prefix: String,
+        batch: Vec<(String, RedisType)>,
+    },
+    PrefixScanComplete {
+        scan_id: u64,
+        prefix: String,
+        total: usize,
+    },
     ValueLoaded {
+        request_id: u64,
         key: String,
         value: RedisValue,
         ttl: i64,
         redis_type: RedisType,
+        size: Option<ValueSize>,
+        page: Option<ValuePage>,
+        /// Server-side memory usage; `None` where MEMORY USAGE is unsupported
+        memory: Option<i64>,
+        /// OBJECT ENCODING, if the server reported one
+        encoding: Option<String>,
+        /// Full byte length of a string of which only a prefix was loaded
+        truncated: Option<usize>,
+    },
+    /// Loading `key` for request `request_id` failed
+    ValueFailed {
+        request_id: u64,
+        key: String,
+        error: String,
     },
+    /// The key was gone by the time its value was read
+    KeyVanished(String),
+    /// Type of a key looked up by go-to-key; `None` if there is no such key
+    KeyLocated {
+        key: String,
+        redis_type: Option<RedisType>,
+    },
+    /// Round-trip time of the last `PING`
+    Latency(Duration),
+    ServerInfo(ServerInfo),
     Error(String),
     WriteSuccess(String),
     DeleteSuccess(String),
+    /// A bulk delete failed, possibly after removing some of its keys
+    DeleteFailed(String),
+    /// A write to these keys finished, whether or not it succeeded; sent
+    /// after its result
+    WriteFinished(Vec<String>),
+    RenameSuccess {
+        old: String,
+        new: String,
+    },
+    KeyCopied {
+        source: String,
+        dest: String,
+    },
+    /// A copy stopped because `dest` exists; asks whether to replace it
+    CopyTargetExists {
+        source: String,
+        dest: String,
+    },
+    /// A rename stopped because `new` exists; asks whether to replace it
+    RenameTargetExists {
+        old: String,
+        new: String,
+    },
+    DbSelected(u8),
+    /// About to try connecting, `attempt` of `max`
+    Connecting {
+        attempt: u32,
+        max: u32,
+    },
+    /// Connected to a server with this role
+    Connected(ServerRole),
+    /// A command failed because the connection dropped; reconnecting
+    ConnectionLost,
+    /// Every connection attempt failed
+    ConnectionFailed(String),
 }
 
 impl App {
@@ -63,200 +486,903 @@ impl App {
         let (redis_tx, mut redis_rx) = mpsc::channel::<RedisCommand>(100);
         let (ui_tx, ui_rx) = mpsc::channel::<UiMessage>(100);
 
-        // Connect to Redis
-        let mut client = RedisClient::connect(&config.connection.url).await?;
+        // The Redis task connects (with retries) when the first command
+        // arrives, so the UI comes up even while the server is starting
+        let mut connection = config.connection.clone();
+
+        // Pub/Sub monitors run on their own tasks and report errors here
+        let monitor_tx = ui_tx.clone();
+
+        // Scans run on their own tasks, and the types of scanned keys are
+        // fetched off the command loop one batch at a time
+        let current_scan = Arc::new(AtomicU64::new(0));
+        let (type_tx, type_rx) = mpsc::channel::<TypeLookup>(TYPE_LOOKUP_QUEUE);
+        tokio::spawn(run_type_lookups(
+            type_rx,
+            current_scan.clone(),
+            ui_tx.clone(),
+        ));
+        let scans = ScanContext {
+            ui_tx: ui_tx.clone(),
+            type_tx,
+            current_scan: current_scan.clone(),
+        };
 
         // Spawn Redis task
-        let _delimiters = config.ui.delimiters.clone();
         tokio::spawn(async move {
-            while let Some(cmd) = redis_rx.recv().await {
-                match cmd {
-                    RedisCommand::ScanKeys { pattern } => {
-                        match client.scan_keys(&pattern, 1000).await {
-                            Ok(keys) => {
-                                // Get types for all keys
-                                let mut typed_keys = Vec::new();
-                                for key in keys {
-                                    let key_type =
-                                        client.get_type(&key).await.unwrap_or(RedisType::Unknown);
-                                    typed_keys.push((key, key_type));
-                                }
-                                let _ = ui_tx.send(UiMessage::KeysLoaded(typed_keys)).await;
+            let mut client: Option<RedisClient> = None;
+            let mut ping_timer = tokio::time::interval(PING_INTERVAL);
+            ping_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                let cmd = tokio::select! {
+                    cmd = redis_rx.recv() => match cmd {
+                        Some(cmd) => cmd,
+                        None => break,
+                    },
+                    _ = ping_timer.tick() => {
+                        // Only once connected; commands drive reconnects
+                        if let Some(conn) = client.as_mut() {
+                            if let Ok(latency) = conn.ping().await {
+                                let _ = ui_tx.send(UiMessage::Latency(latency)).await;
                             }
-                            Err(e) => {
-                                let _ = ui_tx.send(UiMessage::Error(e.to_string())).await;
+                            if conn.connection_lost() {
+                                let _ = ui_tx.send(UiMessage::ConnectionLost).await;
+                                client = connect_reporting(&mut connection, &ui_tx).await;
                             }
                         }
+                        continue;
                     }
-                    RedisCommand::GetValue { key } => {
-                        let value_result = client.get_value(&key).await;
-                        let ttl_result = client.get_ttl(&key).await;
-                        let type_result = client.get_type(&key).await;
-
-                        match (value_result, ttl_result, type_result) {
-                            (Ok(value), Ok(ttl), Ok(redis_type)) => {
-                                let _ = ui_tx
-                                    .send(UiMessage::ValueLoaded {
-                                        key,
-                                        value,
-                                        ttl,
-                                        redis_type,
-                                    })
-                                    .await;
-                            }
-                            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
-                                let _ = ui_tx.send(UiMessage::Error(e.to_string())).await;
-                            }
+                };
+                if client.is_none() {
+                    client = connect_reporting(&mut connection, &ui_tx).await;
+                }
+                let written = cmd.written_keys();
+                match client.as_mut() {
+                    Some(conn) => {
+                        run_command(conn, cmd, &ui_tx, &scans, &mut connection).await;
+                        if conn.connection_lost() {
+                            let _ = ui_tx.send(UiMessage::ConnectionLost).await;
+                            client = connect_reporting(&mut connection, &ui_tx).await;
                         }
                     }
-                    RedisCommand::SetValue { key, value } => {
-                        let value_str = String::from_utf8_lossy(&value);
-                        match client.set_string(&key, &value_str).await {
-                            Ok(_) => {
-                                let _ = ui_tx.send(UiMessage::WriteSuccess(key)).await;
-                            }
-                            Err(e) => {
-                                let _ = ui_tx.send(UiMessage::Error(e.to_string())).await;
-                            }
-                        }
+                    None => {
+                        let _ = ui_tx
+                            .send(UiMessage::Error("Not connected to Redis".to_string()))
+                            .await;
                     }
-                    RedisCommand::DeleteKey { key } => match client.delete(&key).await {
-                        Ok(_) => {
-                            let _ = ui_tx.send(UiMessage::DeleteSuccess(key)).await;
-                        }
-                        Err(e) => {
-                            let _ = ui_tx.send(UiMessage::Error(e.to_string())).await;
-                        }
-                    },
+                }
+                if !written.is_empty() {
+                    let _ = ui_tx.send(UiMessage::WriteFinished(written)).await;
                 }
             }
         });
 
-        // Request initial scan
-        redis_tx
-            .send(RedisCommand::ScanKeys {
-                pattern: "*".to_string(),
-            })
-            .await?;
-
-        Ok(Self {
+        let theme = Theme::from_config(&config.ui.theme);
+        let requested_readonly = config.connection.readonly;
+        let tree_percent = config.ui.tree_width_percent;
+        let mut app = Self {
             config,
             tree_nodes: Vec::new(),
+            loaded_keys: Vec::new(),
+            pending_types: HashMap::new(),
+            tree_dirty: false,
+            unloaded_prefixes: BTreeSet::new(),
+            rescan_snapshot: None,
+            restoring_session: false,
+            type_filter: None,
             tree_state: TreeViewState::new(),
+            pending_motion: String::new(),
+            motion_started: None,
             selected_value: None,
+            string_lines: None,
             selected_type: None,
             selected_ttl: None,
-            theme: Theme::default(),
+            ttl_loaded_at: None,
+            selected_size: None,
+            selected_memory: None,
+            selected_encoding: None,
+            value_truncated: None,
+            pinned_key: None,
+            clipboard: Clipboard::new(),
+            base64_decoded: false,
+            element_index: 0,
+            value_page: None,
+            pending_element: None,
+            pending_action: None,
+            protection_ack: None,
+            theme,
             current_dialog: None,
+            prompt: None,
+            scan_pattern: "*".to_string(),
+            scanning: false,
+            connected: false,
+            replica: false,
+            requested_readonly,
+            db_size: None,
+            latency: None,
+            scan_id: 0,
+            current_scan,
+            value_request_id: 0,
+            tick: 0,
+            layout: AppLayout::new(Rect::default(), tree_percent),
+            tree_percent,
+            wrap_enabled: true,
+            sort_members: false,
+            value_hscroll: 0,
             value_scroll: 0,
+            value_search: None,
             focus: Focus::Tree,
             should_quit: false,
             status_message: "Loading keys...".to_string(),
             redis_tx,
+            ui_tx: monitor_tx,
             ui_rx,
+            monitor: None,
+            folder_stats: None,
+            tunnel: None,
+            startup_warnings: Vec::new(),
+            tunnel_up: true,
+            last_refresh: None,
+            next_refresh: None,
+            pending_load: None,
+            pending_writes: Vec::new(),
+            dirty: true,
+            last_draw: Instant::now(),
+            drawn_ttl: None,
+            last_activity: Instant::now(),
+        };
+
+        // Request initial scan
+        app.start_scan();
+        if let Some(session) = app.load_session() {
+            app.rescan_snapshot = Some(TreeSnapshot::from_session(
+                session.expanded_paths,
+                session.selected_key,
+            ));
+            app.restoring_session = true;
+        }
+
+        Ok(app)
+    }
+
+    /// Keep `tunnel`, the SSH port forward the connection goes through,
+    /// open for the life of the app and show its state in the header.
+    pub fn tunnel(mut self, tunnel: Option<SshTunnel>) -> Self {
+        self.tunnel = tunnel;
+        self
+    }
+
+    /// Show `warnings` about the startup settings in the status line once
+    /// connected, as the terminal is cleared before they could be read on
+    /// stderr.
+    pub fn warnings(mut self, warnings: Vec<String>) -> Self {
+        self.startup_warnings = warnings;
+        self
+    }
+
+    /// The displayed key's TTL, counted down from when it was read.
+    fn current_ttl(&self) -> Option<i64> {
+        let ttl = self.selected_ttl?;
+        Some(match self.ttl_loaded_at {
+            Some(loaded_at) => remaining_ttl(ttl, loaded_at.elapsed()),
+            None => ttl,
         })
     }
 
+    /// Wall-clock time the displayed key expires, fixed from when its TTL
+    /// was read so it does not drift as the countdown ticks.
+    fn expires_at(&self) -> Option<SystemTime> {
+        let ttl = u64::try_from(self.selected_ttl?).ok().filter(|&t| t > 0)?;
+        let read_at = SystemTime::now().checked_sub(self.ttl_loaded_at?.elapsed())?;
+        read_at.checked_add(Duration::from_secs(ttl))
+    }
+
+    /// Once the displayed key's TTL runs out, freeze it as expired and
+    /// reload the key to confirm it is gone. A TTL read as 0 still has up
+    /// to a second left, so the reload waits until the read is a second
+    /// old; otherwise a key about to expire reloads on every frame.
+    fn check_expiry(&mut self) {
+        let Some(loaded_at) = self.ttl_loaded_at else {
+            return;
+        };
+        if self.current_ttl() != Some(0) || loaded_at.elapsed() < Duration::from_secs(1) {
+            return;
+        }
+        self.dirty = true;
+        self.selected_ttl = Some(0);
+        self.ttl_loaded_at = None;
+        if let Some(key) = self.displayed_key().map(str::to_string) {
+            self.request_value(key);
+        }
+    }
+
+    /// Report an SSH tunnel whose ssh process has exited.
+    fn check_tunnel(&mut self) {
+        let Some(tunnel) = self.tunnel.as_mut() else {
+            return;
+        };
+        if self.tunnel_up && !tunnel.is_running() {
+            self.dirty = true;
+            self.tunnel_up = false;
+            self.status_message = format!("SSH tunnel via {} closed", tunnel.label());
+        }
+    }
+
+    /// Note a change that needs drawing and keep polling fast for a while.
+    fn touch(&mut self) {
+        self.dirty = true;
+        self.last_activity = Instant::now();
+    }
+
+    /// Push the next auto-refresh a full interval past now.
+    fn schedule_refresh(&mut self) {
+        self.next_refresh = self
+            .config
+            .ui
+            .refresh_interval
+            .and_then(|interval| Instant::now().checked_add(interval));
+    }
+
+    /// Rescan once the refresh interval has passed, unless a scan is
+    /// already running or a dialog or prompt is waiting on the user. The
+    /// scan runs in the Redis task, so input keeps being handled meanwhile.
+    fn check_auto_refresh(&mut self) {
+        let Some(due) = self.next_refresh else {
+            return;
+        };
+        if Instant::now() < due
+            || self.scanning
+            || self.current_dialog.is_some()
+            || self.prompt.is_some()
+        {
+            return;
+        }
+        self.next_refresh = None;
+        self.dirty = true;
+        self.status_message = "Refreshing...".to_string();
+        self.start_scan();
+    }
+
+    /// The session saved for this connection, if restoring is enabled.
+    fn load_session(&self) -> Option<SessionState> {
+        let path = self.config.ui.session_file.as_ref()?;
+        let key = session_key(
+            self.config.connection.server_url(),
+            self.config.connection.db,
+        );
+        load_session(path, &key)
+    }
+
+    /// Save the expanded folders and selection for the next start. Best
+    /// effort: failing to write it should not fail the quit.
+    fn save_session(&self) {
+        let Some(path) = self.config.ui.session_file.as_ref() else {
+            return;
+        };
+        // Mid-scan the tree is partial; keep the state the scan will restore
+        let current = self.tree_state.snapshot(&self.tree_nodes);
+        let snapshot = match &self.rescan_snapshot {
+            Some(snapshot) if self.scanning => snapshot,
+            _ => &current,
+        };
+        let state = SessionState {
+            expanded_paths: snapshot.expanded(),
+            selected_key: snapshot
+                .selected()
+                .or(current.selected())
+                .map(str::to_string),
+        };
+        let key = session_key(
+            self.config.connection.server_url(),
+            self.config.connection.db,
+        );
+        let _ = save_session(path, &key, state);
+    }
+
+    /// Quit, or ask first with `confirm_quit` set.
+    fn request_quit(&mut self) {
+        if self.config.ui.confirm_quit {
+            self.current_dialog = Some(Dialog::ConfirmQuit);
+        } else {
+            self.should_quit = true;
+        }
+    }
+
     pub async fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        let frame_interval = Duration::from_secs(1) / self.config.ui.max_fps;
         while !self.should_quit {
             // Process Redis messages
-            while let Ok(msg) = self.ui_rx.try_recv() {
+            for _ in 0..MESSAGES_PER_FRAME {
+                let Ok(msg) = self.ui_rx.try_recv() else {
+                    break;
+                };
                 self.handle_message(msg);
+                self.touch();
             }
+            self.flush_tree();
+            self.drain_monitor();
+            self.expire_motion().await?;
+            self.check_tunnel();
+            self.check_expiry();
+            self.check_auto_refresh();
+            self.check_pending_load().await?;
 
-            // Draw
-            terminal.draw(|frame| self.render(frame))?;
+            // Spinners animate every frame, the TTL countdown when it
+            // ticks, and the refresh age and latency once a second
+            let animating = self.scanning || !self.pending_writes.is_empty();
+            if animating
+                || self.current_ttl() != self.drawn_ttl
+                || self.last_draw.elapsed() >= Duration::from_secs(1)
+            {
+                self.dirty = true;
+            }
+            if std::mem::take(&mut self.dirty) {
+                self.tick = self.tick.wrapping_add(1);
+                terminal.draw(|frame| self.render(frame))?;
+                self.last_draw = Instant::now();
+                self.drawn_ttl = self.current_ttl();
+            }
 
-            // Handle input
-            if event::poll(Duration::from_millis(33))? {
-                if let Event::Key(key) = event::read()? {
-                    self.handle_key(key).await?;
+            // Handle input, or wait longer when there is nothing to show
+            let busy = animating
+                || self.pending_load.is_some()
+                || self.motion_started.is_some()
+                || self.last_activity.elapsed() < ACTIVE_WINDOW;
+            let timeout = if busy { frame_interval } else { IDLE_POLL };
+            if event::poll(timeout)? {
+                match event::read()? {
+                    Event::Key(key) => self.handle_key(key).await?,
+                    Event::Mouse(mouse) => self.handle_mouse(mouse).await?,
+                    Event::Resize(width, height) => self.handle_resize(terminal, width, height)?,
+                    // Focus changes just need a redraw
+                    _ => {}
                 }
+                self.touch();
             }
         }
 
+        self.save_session();
         Ok(())
     }
 
     fn handle_message(&mut self, msg: UiMessage) {
         match msg {
-            UiMessage::KeysLoaded(keys) => {
-                let builder = TreeBuilder::new(self.config.ui.delimiters.clone());
-                self.tree_nodes = builder.build(&keys);
-                self.tree_state.flatten(&self.tree_nodes);
-                self.status_message = format!("Loaded {} keys", keys.len());
+            UiMessage::KeysBatch { scan_id, batch } => {
+                if scan_id != self.scan_id {
+                    return;
+                }
+                self.merge_batch("", batch);
+                self.status_message = format!("Scanning... {} keys", self.loaded_keys.len());
+            }
+            UiMessage::PrefixBatch {
+                scan_id,
+                prefix,
+                batch,
+            } => {
+                if scan_id != self.scan_id {
+                    return;
+                }
+                self.merge_batch(&prefix, batch);
+                self.status_message = format!("Loading {}...", prefix);
+            }
+            UiMessage::PrefixScanComplete {
+                scan_id,
+                prefix,
+                total,
+            } => {
+                if scan_id != self.scan_id {
+                    return;
+                }
+                self.flush_tree();
+                self.status_message = format!("Loaded {} keys under {}", total, prefix);
+                self.refresh_folder_stats();
+            }
+            UiMessage::ScanComplete { scan_id, total } => {
+                if scan_id != self.scan_id {
+                    return;
+                }
+                self.scanning = false;
+                self.flush_tree();
+                // Expansion was applied as the batches merged; a selected
+                // row that never came back falls back to its old index
+                let restored = self
+                    .rescan_snapshot
+                    .take()
+                    .filter(|snapshot| snapshot.selected().is_some());
+                if let Some(ref snapshot) = restored {
+                    self.tree_state.restore_selection(snapshot, true);
+                }
+                if std::mem::take(&mut self.restoring_session) && restored.is_some() {
+                    self.folder_stats = self.selected_folder_stats();
+                    if let Some(key) = self.tree_state.selected_key() {
+                        self.request_value(key.to_string());
+                    }
+                }
+                self.status_message = format!("Loaded {} keys", total);
+                self.refresh_folder_stats();
+                self.last_refresh = Some(Instant::now());
+                self.schedule_refresh();
+            }
+            UiMessage::TypesResolved { scan_id, types } => {
+                if scan_id != self.scan_id {
+                    return;
+                }
+                self.resolve_types(types);
             }
+            UiMessage::TypesFailed {
+                scan_id,
+                keys,
+                error,
+            } => {
+                if scan_id != self.scan_id {
+                    return;
+                }
+                for key in &keys {
+                    self.pending_types.remove(key);
+                }
+                self.status_message = format!("Could not read key types: {}", error);
+            }
+            UiMessage::ScanFailed(e) => {
+                self.scanning = false;
+                self.status_message = format!("Error: {}", e);
+                self.schedule_refresh();
+            }
+            UiMessage::DbSize(size) => self.db_size = Some(size),
             UiMessage::ValueLoaded {
+                request_id,
                 key,
-                value,
+                mut value,
                 ttl,
                 redis_type,
+                size,
+                page,
+                memory,
+                encoding,
+                truncated,
             } => {
+                // Drop responses superseded by a later load, and late loads
+                // for other keys while the pane is pinned
+                if request_id != self.value_request_id
+                    || self.pinned_key.as_ref().is_some_and(|p| *p != key)
+                {
+                    return;
+                }
+                if self.sort_members {
+                    value.sort_members();
+                }
+                let count = value.element_count().unwrap_or(0);
+                let offset = page.map_or(0, |p| p.offset);
+                if let Some(target) = self.pending_element.take() {
+                    self.element_index = target.saturating_sub(offset);
+                }
+                self.element_index = self.element_index.min(count.saturating_sub(1));
+                self.value_page = page;
                 self.selected_value = Some(value);
+                self.render_string_value();
                 self.selected_ttl = Some(ttl);
+                self.ttl_loaded_at = Some(Instant::now());
                 self.selected_type = Some(redis_type);
+                self.selected_size = size;
+                self.selected_memory = memory;
+                self.selected_encoding = encoding;
+                self.value_truncated = truncated;
                 self.value_scroll = 0;
-                self.status_message = format!("Loaded {}", key);
+                self.value_hscroll = 0;
+                self.status_message = if ttl == TTL_MISSING {
+                    // Deleted after its value was read; a refresh drops it
+                    format!("{} no longer exists (r to refresh)", display_key(&key))
+                } else {
+                    format!("Loaded {}", display_key(&key))
+                };
+                self.update_key_type(&key, redis_type);
+            }
+            UiMessage::KeyVanished(key) => self.forget_key(&key),
+            UiMessage::KeyLocated { key, redis_type } => {
+                let Some(redis_type) = redis_type else {
+                    self.status_message = format!("No key named {}", display_key(&key));
+                    return;
+                };
+                if !self.loaded_keys.iter().any(|(k, _)| *k == key) {
+                    let builder = self.tree_builder();
+                    if self.type_filter.is_none_or(|t| t == redis_type) {
+                        builder.insert_key(&mut self.tree_nodes, &key, redis_type);
+                        builder.sort_nodes(&mut self.tree_nodes);
+                    }
+                    self.loaded_keys.push((key.clone(), redis_type));
+                }
+                self.forget_rescan_selection();
+                if !self.reveal_key(&key) {
+                    self.status_message = format!("{} is hidden by a filter", display_key(&key));
+                } else if let Some(key) = self.reset_for_selection() {
+                    self.request_value(key);
+                }
+            }
+            UiMessage::ValueFailed {
+                request_id,
+                key,
+                error,
+            } => {
+                // Like `ValueLoaded`, a failure of a superseded load is stale
+                if request_id != self.value_request_id
+                    || self.pinned_key.as_ref().is_some_and(|p| *p != key)
+                {
+                    return;
+                }
+                self.status_message = format!("Error loading {}: {}", display_key(&key), error);
             }
             UiMessage::Error(e) => {
                 self.status_message = format!("Error: {}", e);
             }
             UiMessage::WriteSuccess(key) => {
-                self.status_message = format!("Saved {}", key);
+                self.status_message = format!("Saved {}", display_key(&key));
+                if !self.loaded_keys.iter().any(|(k, _)| *k == key) {
+                    // A newly created key; rescan so it shows up in the tree
+                    self.start_scan();
+                } else if self.displayed_key() == Some(key.as_str()) {
+                    // Show the written value
+                    self.request_value(key);
+                }
             }
-            UiMessage::DeleteSuccess(key) => {
-                self.status_message = format!("Deleted {}", key);
-                // Trigger rescan
-                let _ = self.redis_tx.try_send(RedisCommand::ScanKeys {
-                    pattern: "*".to_string(),
+            UiMessage::RenameSuccess { old, new } => {
+                self.status_message =
+                    format!("Renamed {} to {}", display_key(&old), display_key(&new));
+                if self.pinned_key.as_deref() == Some(old.as_str()) {
+                    self.pinned_key = Some(new);
+                }
+                self.start_scan();
+            }
+            UiMessage::KeyCopied { source, dest } => {
+                self.status_message =
+                    format!("Copied {} to {}", display_key(&source), display_key(&dest));
+                if !self.loaded_keys.iter().any(|(k, _)| *k == dest) {
+                    self.start_scan();
+                } else if self.displayed_key() == Some(dest.as_str()) {
+                    self.request_value(dest);
+                }
+            }
+            UiMessage::CopyTargetExists { source, dest } => {
+                self.current_dialog = Some(Dialog::Confirm {
+                    title: "Replace Key".to_string(),
+                    message: format!(
+                        "'{}' already exists. Replace it with a copy of '{}'?",
+                        display_key(&dest),
+                        display_key(&source)
+                    ),
+                    confirm_text: "yes".to_string(),
+                    input: String::new(),
+                });
+                self.pending_action = Some(RedisCommand::CopyKey {
+                    source,
+                    dest,
+                    replace: true,
+                });
+            }
+            UiMessage::RenameTargetExists { old, new } => {
+                self.current_dialog = Some(Dialog::Confirm {
+                    title: "Replace Key".to_string(),
+                    message: format!(
+                        "'{}' already exists. Replace it with '{}'?",
+                        display_key(&new),
+                        display_key(&old)
+                    ),
+                    confirm_text: "yes".to_string(),
+                    input: String::new(),
+                });
+                self.pending_action = Some(RedisCommand::RenameKey {
+                    old,
+                    new,
+                    replace: true,
                 });
             }
+            UiMessage::WriteFinished(keys) => {
+                for key in keys {
+                    if let Some(i) = self.pending_writes.iter().position(|k| *k == key) {
+                        self.pending_writes.swap_remove(i);
+                    }
+                }
+            }
+            UiMessage::DeleteSuccess(key) => {
+                self.status_message = format!("Deleted {}", display_key(&key));
+                self.start_scan();
+            }
+            UiMessage::DeleteFailed(e) => {
+                // Some keys may already be gone, so the tree is rescanned
+                self.status_message = format!("Error: {}", e);
+                self.start_scan();
+            }
+            UiMessage::Connecting { attempt, max } => {
+                self.status_message = format!("Connecting… attempt {}/{}", attempt, max);
+            }
+            UiMessage::Connected(role) => {
+                self.connected = true;
+                self.replica = role == ServerRole::Replica;
+                self.config.connection.readonly = self.requested_readonly || self.replica;
+                self.status_message = if self.replica {
+                    "Connected to a replica (read-only)".to_string()
+                } else {
+                    "Connected".to_string()
+                };
+                if !self.startup_warnings.is_empty() {
+                    let warnings = self.startup_warnings.join("; ");
+                    self.status_message =
+                        format!("{} (warning: {})", self.status_message, warnings);
+                }
+            }
+            UiMessage::Latency(latency) => self.latency = Some(latency),
+            UiMessage::ServerInfo(info) => {
+                self.status_message.clear();
+                self.current_dialog = Some(Dialog::ServerInfo { info, scroll: 0 });
+            }
+            UiMessage::ConnectionLost => {
+                self.connected = false;
+                self.latency = None;
+                self.status_message = "Connection lost, reconnecting…".to_string();
+            }
+            UiMessage::ConnectionFailed(e) => {
+                self.connected = false;
+                self.latency = None;
+                self.scanning = false;
+                self.status_message = format!("Connection failed: {} (R to retry)", e);
+            }
+            UiMessage::DbSelected(db) => {
+                self.config.connection.db = db;
+                self.db_size = None;
+                self.pinned_key = None;
+                self.clear_value();
+                self.status_message = format!("Switched to db {}", db);
+                self.start_scan();
+            }
+        }
+    }
+
+    /// Empty the value pane.
+    fn clear_value(&mut self) {
+        self.selected_value = None;
+        self.selected_type = None;
+        self.selected_ttl = None;
+        self.ttl_loaded_at = None;
+        self.selected_size = None;
+        self.selected_memory = None;
+        self.selected_encoding = None;
+        self.value_truncated = None;
+        self.base64_decoded = false;
+        self.element_index = 0;
+        self.value_page = None;
+        self.string_lines = None;
+    }
+
+    /// Render the displayed string value for its format, once per load or
+    /// base64 toggle rather than on every frame.
+    fn render_string_value(&mut self) {
+        self.string_lines = match &self.selected_value {
+            Some(RedisValue::String(s)) => Some(render_string(s.as_bytes(), self.base64_decoded)),
+            Some(RedisValue::Bytes(b)) => Some(render_string(b, self.base64_decoded)),
+            _ => None,
+        };
+    }
+
+    /// Drop a key that no longer exists from the tree, moving the selection
+    /// to a neighbouring row and loading that instead.
+    fn forget_key(&mut self, key: &str) {
+        self.status_message = format!("{} no longer exists", display_key(key));
+        if self.pinned_key.as_deref() == Some(key) {
+            self.pinned_key = None;
+        }
+        if self.displayed_key().is_none_or(|k| k == key) {
+            self.clear_value();
+        }
+        let before = self.loaded_keys.len();
+        self.loaded_keys.retain(|(k, _)| k != key);
+        if self.loaded_keys.len() == before {
+            return;
+        }
+        // Indices into `loaded_keys` have shifted
+        self.pending_types = self
+            .loaded_keys
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, t))| *t == RedisType::Unknown)
+            .map(|(i, (k, _))| (k.clone(), i))
+            .collect();
+        self.rebuild_tree();
+        if self.selected_value.is_none() {
+            if let Some(next) = self.tree_state.selected_key() {
+                self.request_value(next.to_string());
+            }
+        }
+    }
+
+    /// Record the type a key was found to have when its value was loaded,
+    /// in case it changed since the scan.
+    fn update_key_type(&mut self, key: &str, redis_type: RedisType) {
+        let Some(entry) = self.loaded_keys.iter_mut().find(|(k, _)| k == key) else {
+            return;
+        };
+        if entry.1 == redis_type || redis_type == RedisType::Unknown {
+            return;
         }
+        entry.1 = redis_type;
+        self.pending_types.remove(key);
+        self.rebuild_tree();
     }
 
     fn render(&mut self, frame: &mut ratatui::Frame) {
-        use crate::ui::info_bar::InfoBar;
-        use crate::ui::value_view::ValueView;
+        use crate::ui::header::Header;
+        use crate::ui::info_bar::{format_latency, latency_color, InfoBar};
         use ratatui::style::Style;
+        use ratatui::text::{Line, Span};
         use ratatui::widgets::Paragraph;
 
-        let layout = AppLayout::new(frame.area());
+        let layout = AppLayout::new(frame.area(), self.tree_percent);
+        self.layout = layout;
+
+        // Header
+        let header = Header::new(
+            &self.config.connection.url,
+            self.config.connection.db,
+            self.config.connection.readonly,
+            self.config.connection.profile.as_deref(),
+            &self.theme,
+        )
+        .replica(self.replica)
+        .config_file(self.config.config_file.as_deref())
+        .tunnel(self.tunnel.as_ref().map(|t| (t.label(), self.tunnel_up)))
+        .refreshed(
+            self.config
+                .ui
+                .refresh_interval
+                .and(self.last_refresh)
+                .map(|t| t.elapsed()),
+        );
+        header.render(frame, layout.header_area);
 
         // Tree view
-        let mut tree_view = TreeView::new(&self.tree_nodes, &mut self.tree_state, &self.theme);
+        let placeholder = self.tree_placeholder();
+        let mut tree_view = TreeView::new(
+            &self.tree_nodes,
+            &mut self.tree_state,
+            &self.theme,
+            placeholder,
+        )
+        .icons(self.config.ui.icons)
+        .pending(&self.pending_types);
         tree_view.render(frame, layout.tree_area);
 
-        // Value view
-        let selected_key = self.tree_state.selected_key();
+        let path = self.selected_node_path().unwrap_or_default();
+        let segments = path_names(&self.tree_nodes, &path);
+        Breadcrumb::new(&segments, &self.theme).render(frame, layout.breadcrumb_area);
+
+        // Value view; keep the highlighted element on screen
+        let selected_element = self.selected_element_index();
+        if let Some(index) = selected_element {
+            let height = layout.value_area.height.saturating_sub(2).max(1) as usize;
+            let scroll = self.value_scroll as usize;
+            if index < scroll {
+                self.value_scroll = index as u16;
+            } else if index >= scroll + height {
+                self.value_scroll = (index + 1 - height) as u16;
+            }
+        }
         let value_view = ValueView::new(
             self.selected_value.as_ref(),
-            selected_key,
+            self.displayed_key(),
             &self.theme,
             self.value_scroll,
-        );
-        value_view.render(frame, layout.value_area);
+            self.pinned_key.is_some(),
+            self.base64_decoded,
+            selected_element,
+        )
+        .score_precision(self.config.ui.zset_score_precision)
+        .rendered(self.string_lines.as_ref())
+        .offset(self.page_offset())
+        .search(self.value_search.as_ref())
+        .line_numbers(self.config.ui.line_numbers)
+        .truncated(self.value_truncated)
+        .members(self.value_page.map(|p| p.total), self.sort_members)
+        .wrap(self.wrap_enabled, self.value_hscroll);
+        match (&self.monitor, &self.folder_stats) {
+            (Some(monitor), _) => MonitorView::new(&monitor.target, &monitor.messages, &self.theme)
+                .render(frame, layout.value_area),
+            (None, Some((prefix, stats))) => {
+                FolderView::new(prefix, stats, &self.theme).render(frame, layout.value_area)
+            }
+            (None, None) => value_view.render(frame, layout.value_area),
+        }
 
         // Info bar
-        let size = match &self.selected_value {
-            Some(RedisValue::String(s)) => Some(s.len()),
-            _ => None,
-        };
         let info_bar = InfoBar::new(
             self.selected_type,
-            self.selected_ttl,
-            size,
+            self.current_ttl(),
+            self.selected_size,
+            self.value_page,
+            self.config.ui.show_char_count,
             &self.theme,
             self.config.connection.readonly,
-        );
+        )
+        .icons(self.config.ui.icons)
+        .memory(self.selected_memory)
+        .encoding(self.selected_encoding.as_deref())
+        .expires_at(self.expires_at());
         info_bar.render(frame, layout.info_area);
 
         // Status bar
-        let status = Paragraph::new(format!(
-            " {} | {} | ? for help",
-            self.config.connection.url, self.status_message
-        ))
-        .style(Style::default());
-        frame.render_widget(status, layout.status_area);
+        let status_text = match &self.prompt {
+            Some(prompt) => {
+                let label = match prompt.kind {
+                    PromptKind::Pattern => "Pattern",
+                    PromptKind::Search => "/",
+                    PromptKind::ValueSearch => "Find in value",
+                    PromptKind::Subscribe => "Subscribe",
+                    PromptKind::KeyFilter => "Filter",
+                    PromptKind::GoToKey => "Go to key",
+                };
+                let matches = match prompt.kind {
+                    PromptKind::Search if !prompt.input.is_empty() => {
+                        format!("  ({} matches)", self.tree_state.match_count)
+                    }
+                    PromptKind::KeyFilter => match KeyFilter::parse(&prompt.input) {
+                        Ok(Some(_)) => format!("  ({} matches)", self.tree_state.match_count),
+                        Ok(None) => "  (type:<type> or a glob; terms combine)".to_string(),
+                        Err(e) => format!("  ({})", e),
+                    },
+                    PromptKind::ValueSearch => {
+                        let case = match &self.value_search {
+                            Some(s) if s.case_sensitive => "case-sensitive",
+                            _ => "ignoring case",
+                        };
+                        format!("  ({}, Tab toggles)", case)
+                    }
+                    _ => String::new(),
+                };
+                format!(" {}: {}█{}", label, prompt.input, matches)
+            }
+            None => {
+                let mut filter = match self.type_filter {
+                    Some(t) => format!(" | type: {}", t.as_str()),
+                    None => String::new(),
+                };
+                if let Some(ref key_filter) = self.tree_state.key_filter {
+                    filter.push_str(&format!(" | filter: {}", key_filter.query));
+                }
+                if let Some(ref query) = self.tree_state.filter {
+                    filter.push_str(&format!(
+                        " | /{} ({} matches, Esc clears)",
+                        query, self.tree_state.match_count
+                    ));
+                }
+                let mut warning = if self.config.connection.tls.insecure {
+                    " ⚠ TLS verification disabled |"
+                } else {
+                    ""
+                }
+                .to_string();
+                if !self.connected {
+                    warning.push_str(" ⚠ disconnected |");
+                }
+                if !self.pending_writes.is_empty() {
+                    warning.push_str(&format!(" {} writing |", self.spinner()));
+                }
+                format!("{} {}{} | ? for help", warning, self.status_message, filter)
+            }
+        };
+        let mut summary = vec![Span::styled(self.key_summary(), self.theme.border)];
+        // Latency last, colored by how the server is keeping up
+        if let Some(latency) = self.latency {
+            summary.push(Span::styled("| ping ", self.theme.border));
+            summary.push(Span::styled(
+                format!("{} ", format_latency(latency)),
+                Style::default().fg(latency_color(latency)),
+            ));
+        }
+        let summary = Line::from(summary);
+        let [status_area, summary_area] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(summary.width() as u16),
+        ])
+        .areas(layout.status_area);
+        let status = Paragraph::new(status_text).style(Style::default());
+        frame.render_widget(status, status_area);
+        frame.render_widget(Paragraph::new(summary), summary_area);
 
         // Dialog
         if let Some(ref dialog) = self.current_dialog {
@@ -264,51 +1390,243 @@ impl App {
         }
     }
 
-    async fn handle_key(&mut self, key: KeyEvent) -> Result<()> {
-        // Handle dialog first
-        if self.current_dialog.is_some() {
-            return self.handle_dialog_key(key).await;
+    /// Persistent status bar segment: keys loaded into the tree, rows
+    /// currently visible, and the server's key count once known.
+    fn key_summary(&self) -> String {
+        let mut summary = format!(
+            " {} keys | {} visible",
+            self.loaded_keys.len(),
+            self.tree_state.flattened.len()
+        );
+        if let Some(size) = self.db_size {
+            summary.push_str(&format!(" | dbsize {}", size));
         }
+        summary.push(' ');
+        summary
+    }
 
-        match key.code {
-            KeyCode::Char('q') | KeyCode::Esc => {
-                self.should_quit = true;
-            }
-            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.should_quit = true;
-            }
-            KeyCode::Char('?') => {
-                self.current_dialog = Some(Dialog::Help);
-            }
-            KeyCode::Tab => {
-                self.focus = match self.focus {
-                    Focus::Tree => Focus::Value,
-                    Focus::Value => Focus::Tree,
+    /// Current frame of the busy spinner, advancing every few draws.
+    fn spinner(&self) -> char {
+        SPINNER[(self.tick / 3) % SPINNER.len()]
+    }
+
+    /// Message shown in place of an empty tree, distinguishing an in-flight
+    /// scan from a completed scan that found nothing.
+    fn tree_placeholder(&self) -> Option<Vec<String>> {
+        if !self.tree_state.flattened.is_empty() {
+            return None;
+        }
+        if self.scanning {
+            return Some(vec![format!(
+                "{} Scanning {}...",
+                self.spinner(),
+                self.scan_pattern
+            )]);
+        }
+
+        if let Some(ref key_filter) = self.tree_state.key_filter {
+            if !self.tree_nodes.is_empty() {
+                return Some(vec![
+                    format!("No keys match '{}'", key_filter.query),
+                    String::new(),
+                    "Press Esc to clear the filter".to_string(),
+                ]);
+            }
+        }
+        let (message, hint) = match self.type_filter {
+            // Keys were found but the type filter hides all of them
+            Some(t) if !self.loaded_keys.is_empty() => (
+                format!("No {} keys match '{}'", t.as_str(), self.scan_pattern),
+                "Press F to change the type filter",
+            ),
+            _ if self.scan_pattern == "*" => ("Database is empty".to_string(), "Press R to rescan"),
+            _ => (
+                format!("No keys match '{}'", self.scan_pattern),
+                "Press f to change the pattern",
+            ),
+        };
+        Some(vec![message, String::new(), hint.to_string()])
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent) -> Result<()> {
+        // Handle dialog first
+        if self.current_dialog.is_some() {
+            return self.handle_dialog_key(key).await;
+        }
+        if self.prompt.is_some() {
+            return self.handle_prompt_key(key).await;
+        }
+
+        // n/N step through value search matches, shadowing their bindings
+        if self.focus == Focus::Value
+            && self.value_search.is_some()
+            && !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            if let KeyCode::Char(c @ ('n' | 'N')) = key.code {
+                self.step_value_match(c == 'n');
+                return Ok(());
+            }
+        }
+
+        if self.focus == Focus::Tree && self.handle_motion_key(&key).await? {
+            return Ok(());
+        }
+
+        let Some(action) = self.config.ui.keymap.action_for(&key) else {
+            return Ok(());
+        };
+        match self.focus {
+            Focus::Tree => self.handle_tree_action(action).await?,
+            Focus::Value => self.handle_value_action(action).await?,
+        }
+
+        Ok(())
+    }
+
+    /// Feed `key` to the tree pane's motion buffer. Returns false if the key
+    /// is not part of a motion and should be handled as usual. Keys count
+    /// by the action they are bound to, so rebinding `move_down` also
+    /// changes what `10j` is typed as. Counts only start on unbound digits,
+    /// and motions on the keys of `top`/`delete`.
+    async fn handle_motion_key(&mut self, key: &KeyEvent) -> Result<bool> {
+        let Some(c) = self.config.ui.keymap.motion_char(key) else {
+            self.clear_motion();
+            return Ok(false);
+        };
+        if self.pending_motion.is_empty() {
+            if !matches!(c, '1'..='9' | 'g' | 'd') {
+                return Ok(false);
+            }
+            self.motion_started = Some(Instant::now());
+        }
+
+        self.pending_motion.push(c);
+        match parse_motion(&self.pending_motion) {
+            Motion::Pending { .. } => {
+                self.status_message = self.pending_motion.clone();
+            }
+            Motion::Run { action, count } => {
+                self.clear_motion();
+                self.run_motion(action, count).await?;
+            }
+            Motion::Invalid => {
+                self.clear_motion();
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Drop a motion left incomplete for too long, running the single-key
+    /// action of a lone `g` or `d`.
+    async fn expire_motion(&mut self) -> Result<()> {
+        if self
+            .motion_started
+            .is_none_or(|started| started.elapsed() < MOTION_TIMEOUT)
+        {
+            return Ok(());
+        }
+        self.dirty = true;
+        let motion = parse_motion(&self.pending_motion);
+        self.clear_motion();
+        if let Motion::Pending {
+            fallback: Some(action),
+        } = motion
+        {
+            self.handle_tree_action(action).await?;
+        }
+        Ok(())
+    }
+
+    fn clear_motion(&mut self) {
+        if !self.pending_motion.is_empty() && self.status_message == self.pending_motion {
+            self.status_message.clear();
+        }
+        self.pending_motion.clear();
+        self.motion_started = None;
+    }
+
+    async fn run_motion(&mut self, action: Action, count: Option<usize>) -> Result<()> {
+        let Some(count) = count else {
+            return self.handle_tree_action(action).await;
+        };
+        if action == Action::Delete {
+            return self.handle_delete_rows(count).await;
+        }
+        self.forget_rescan_selection();
+        let target = motion_target(
+            action,
+            count,
+            self.tree_state.list_state.selected(),
+            self.tree_state.flattened.len(),
+        );
+        if target.is_some() {
+            self.tree_state.list_state.select(target);
+            self.schedule_load();
+        }
+        Ok(())
+    }
+
+    /// Clicks select tree rows (toggling folders like Enter) or focus the
+    /// value pane; the wheel moves through the focused pane.
+    async fn handle_mouse(&mut self, mouse: MouseEvent) -> Result<()> {
+        if self.current_dialog.is_some() || self.prompt.is_some() {
+            return Ok(());
+        }
+
+        let in_area = |area: Rect| area.contains(Position::new(mouse.column, mouse.row));
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if in_area(self.layout.tree_area) => {
+                self.focus = Focus::Tree;
+                if let Some(idx) = self.tree_state.index_at(self.layout.tree_area, mouse.row) {
+                    self.tree_state.list_state.select(Some(idx));
+                    self.handle_tree_action(Action::Expand).await?;
+                }
+            }
+            MouseEventKind::Down(MouseButton::Left) if in_area(self.layout.value_area) => {
+                self.focus = Focus::Value;
+            }
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+                let action = if mouse.kind == MouseEventKind::ScrollDown {
+                    Action::MoveDown
+                } else {
+                    Action::MoveUp
                 };
+                match self.focus {
+                    Focus::Tree => self.handle_tree_action(action).await?,
+                    Focus::Value => self.handle_value_action(action).await?,
+                }
             }
-            _ => match self.focus {
-                Focus::Tree => self.handle_tree_key(key).await?,
-                Focus::Value => self.handle_value_key(key),
-            },
+            _ => {}
         }
 
         Ok(())
     }
 
-    async fn handle_tree_key(&mut self, key: KeyEvent) -> Result<()> {
-        match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
+    /// Tree-pane meaning of the navigation actions; everything else goes
+    /// through `perform_action`.
+    async fn handle_tree_action(&mut self, action: Action) -> Result<()> {
+        // Whatever the user does now takes precedence over restoring the
+        // pre-rescan selection
+        self.forget_rescan_selection();
+        match action {
+            Action::MoveDown => {
                 self.tree_state.list_state.select_next();
-                self.load_selected_value().await?;
+                self.schedule_load();
             }
-            KeyCode::Char('k') | KeyCode::Up => {
+            Action::MoveUp => {
                 self.tree_state.list_state.select_previous();
-                self.load_selected_value().await?;
+                self.schedule_load();
             }
-            KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
+            Action::Expand => {
                 if let Some(idx) = self.tree_state.list_state.selected() {
                     if let Some(flat_node) = self.tree_state.flattened.get(idx) {
-                        if flat_node.is_folder {
+                        if !flat_node.loaded {
+                            let path = flat_node.node_index.clone();
+                            self.load_folder(&path);
+                        } else if flat_node.is_folder {
                             // Toggle expand
                             self.toggle_node_at_path(&flat_node.node_index.clone());
                             self.tree_state.flatten(&self.tree_nodes);
@@ -318,7 +1636,7 @@ impl App {
                     }
                 }
             }
-            KeyCode::Char('h') | KeyCode::Left => {
+            Action::Collapse => {
                 if let Some(idx) = self.tree_state.list_state.selected() {
                     if let Some(flat_node) = self.tree_state.flattened.get(idx) {
                         if flat_node.is_folder && flat_node.expanded {
@@ -328,201 +1646,2723 @@ impl App {
                     }
                 }
             }
-            KeyCode::Char('g') => {
+            Action::Top => {
                 self.tree_state.list_state.select_first();
-                self.load_selected_value().await?;
+                self.schedule_load();
             }
-            KeyCode::Char('G') => {
+            Action::Bottom => {
                 self.tree_state.list_state.select_last();
-                self.load_selected_value().await?;
-            }
-            KeyCode::Char('r') => {
-                self.load_selected_value().await?;
-            }
-            KeyCode::Char('R') => {
-                self.status_message = "Rescanning...".to_string();
-                self.redis_tx
-                    .send(RedisCommand::ScanKeys {
-                        pattern: "*".to_string(),
-                    })
-                    .await?;
-            }
-            KeyCode::Char('e') => {
-                self.handle_edit().await?;
+                self.schedule_load();
             }
-            KeyCode::Char('d') => {
-                self.handle_delete().await?;
-            }
-            _ => {}
+            Action::PageDown | Action::PageUp => {}
+            action => self.perform_action(action).await?,
         }
 
         Ok(())
     }
 
-    fn handle_value_key(&mut self, key: KeyEvent) {
-        match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.value_scroll = self.value_scroll.saturating_add(1);
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.value_scroll = self.value_scroll.saturating_sub(1);
-            }
-            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.value_scroll = self.value_scroll.saturating_add(10);
+    /// Value-pane meaning of the navigation actions: scrolling, or moving
+    /// the element cursor of collections. `delete` removes the highlighted
+    /// element rather than the key.
+    async fn handle_value_action(&mut self, action: Action) -> Result<()> {
+        match action {
+            Action::MoveDown => self.scroll_value(1, true),
+            Action::MoveUp => self.scroll_value(1, false),
+            Action::PageDown => self.scroll_value(10, true),
+            Action::PageUp => self.scroll_value(10, false),
+            Action::Top => {
+                self.value_scroll = 0;
+                self.element_index = 0;
             }
-            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.value_scroll = self.value_scroll.saturating_sub(10);
+            Action::Delete => self.perform_action(Action::RemoveElement).await?,
+            Action::Search => {
+                let search = self.value_search.get_or_insert_with(ValueSearch::default);
+                let query = search.query.clone();
+                self.open_prompt(PromptKind::ValueSearch, query);
             }
-            KeyCode::Char('0') => {
-                self.value_scroll = 0;
+            Action::Back if self.value_search.is_some() && self.monitor.is_none() => {
+                self.value_search = None;
             }
-            _ => {}
+            Action::Expand if !self.wrap_enabled => self.scroll_value_sideways(true),
+            Action::Collapse if !self.wrap_enabled => self.scroll_value_sideways(false),
+            Action::Expand | Action::Collapse | Action::Bottom => {}
+            action => self.perform_action(action).await?,
         }
+
+        Ok(())
     }
 
-    async fn handle_dialog_key(&mut self, key: KeyEvent) -> Result<()> {
-        match key.code {
-            KeyCode::Esc => {
-                self.current_dialog = None;
-            }
-            KeyCode::Enter => {
-                // Handle confirm actions based on dialog type
-                if let Some(Dialog::DiffPreview { key, new_value, .. }) = &self.current_dialog {
-                    if !self.config.connection.readonly {
-                        self.redis_tx
-                            .send(RedisCommand::SetValue {
-                                key: key.clone(),
-                                value: new_value.as_bytes().to_vec(),
-                            })
-                            .await?;
-                    }
-                }
-                self.current_dialog = None;
-            }
-            _ => {}
-        }
+    /// Fit the view to a resized terminal: wipe what the old size left
+    /// behind, pull the value back if it is now scrolled past its end, and
+    /// keep the selected row in the tree on screen.
+    fn handle_resize(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        width: u16,
+        height: u16,
+    ) -> Result<()> {
+        terminal.clear()?;
+        self.layout = AppLayout::new(Rect::new(0, 0, width, height), self.tree_percent);
+
+        let inner_height = self.layout.value_area.height.saturating_sub(2) as usize;
+        let rows = self.value_rows();
+        self.value_scroll = clamp_scroll(self.value_scroll, rows, inner_height);
 
+        let tree_height = self.layout.tree_area.height.saturating_sub(2) as usize;
+        let list_state = &mut self.tree_state.list_state;
+        let offset = visible_offset(list_state.offset(), list_state.selected(), tree_height);
+        *list_state.offset_mut() = offset;
         Ok(())
     }
 
-    fn toggle_node_at_path(&mut self, path: &[usize]) {
-        fn toggle_recursive(nodes: &mut [TreeNode], path: &[usize]) {
-            if path.is_empty() {
-                return;
-            }
-            let idx = path[0];
-            if path.len() == 1 {
-                if let Some(node) = nodes.get_mut(idx) {
-                    node.expanded = !node.expanded;
+    /// Rows the value takes in the value pane as laid out now.
+    fn value_rows(&self) -> usize {
+        self.pane_view()
+            .line_count(self.layout.value_area.width.saturating_sub(2))
+    }
+
+    /// The value view with the pane's current settings, for measuring.
+    fn pane_view(&self) -> ValueView<'_> {
+        ValueView::new(
+            self.selected_value.as_ref(),
+            self.displayed_key(),
+            &self.theme,
+            self.value_scroll,
+            self.pinned_key.is_some(),
+            self.base64_decoded,
+            self.selected_element_index(),
+        )
+        .score_precision(self.config.ui.zset_score_precision)
+        .rendered(self.string_lines.as_ref())
+        .offset(self.page_offset())
+        .line_numbers(self.config.ui.line_numbers)
+        .truncated(self.value_truncated)
+        .members(self.value_page.map(|p| p.total), self.sort_members)
+        .wrap(self.wrap_enabled, self.value_hscroll)
+    }
+
+    /// Move the element cursor of a collection value, or scroll any other
+    /// value, by `amount` lines.
+    fn scroll_value(&mut self, amount: u16, down: bool) {
+        match self.selected_value.as_ref().and_then(|v| v.element_count()) {
+            Some(count) => {
+                let (offset, total) = self.value_page.map_or((0, count), |p| (p.offset, p.total));
+                let current = offset + self.element_index;
+                let target = if down {
+                    (current + amount as usize).min(total.saturating_sub(1))
+                } else {
+                    current.saturating_sub(amount as usize)
+                };
+                if (offset..offset + count).contains(&target) {
+                    self.element_index = target - offset;
+                } else {
+                    self.request_page(target);
                 }
-            } else if let Some(node) = nodes.get_mut(idx) {
-                toggle_recursive(&mut node.children, &path[1..]);
             }
+            None if self.turn_stream_page(down) => {}
+            None if down => self.value_scroll = self.value_scroll.saturating_add(amount),
+            None => self.value_scroll = self.value_scroll.saturating_sub(amount),
         }
-        toggle_recursive(&mut self.tree_nodes, path);
     }
 
-    async fn load_selected_value(&mut self) -> Result<()> {
-        if let Some(key) = self.tree_state.selected_key() {
-            self.redis_tx
-                .send(RedisCommand::GetValue {
-                    key: key.to_string(),
-                })
-                .await?;
+    /// Page through a stream, whose entries span several lines and so are
+    /// scrolled rather than stepped through: scrolling past the last loaded
+    /// row loads the next (older) page, and above the first row the
+    /// previous one. Returns whether a page was requested.
+    fn turn_stream_page(&mut self, down: bool) -> bool {
+        let (Some(RedisValue::Stream(_)), Some(page)) = (&self.selected_value, self.value_page)
+        else {
+            return false;
+        };
+        let target = if down {
+            let height = self.layout.value_area.height.saturating_sub(2) as usize;
+            if usize::from(self.value_scroll) + height < self.value_rows()
+                || page.offset + page.len >= page.total
+            {
+                return false;
+            }
+            page.offset + page.len
+        } else {
+            if self.value_scroll > 0 || page.offset == 0 {
+                return false;
+            }
+            page.offset - 1
+        };
+        self.request_page(target);
+        true
+    }
+
+    /// Scroll the unwrapped value pane sideways, no further than the
+    /// longest line.
+    fn scroll_value_sideways(&mut self, right: bool) {
+        const STEP: u16 = 8;
+        if !right {
+            self.value_hscroll = self.value_hscroll.saturating_sub(STEP);
+            return;
         }
-        Ok(())
+        let max_width = ValueView::new(
+            self.selected_value.as_ref(),
+            self.displayed_key(),
+            &self.theme,
+            0,
+            false,
+            self.base64_decoded,
+            None,
+        )
+        .score_precision(self.config.ui.zset_score_precision)
+        .rendered(self.string_lines.as_ref())
+        .offset(self.page_offset())
+        .line_numbers(self.config.ui.line_numbers)
+        .max_width();
+        let limit = u16::try_from(max_width.saturating_sub(1)).unwrap_or(u16::MAX);
+        self.value_hscroll = self.value_hscroll.saturating_add(STEP).min(limit);
     }
 
-    fn check_protection(&self, key: &str) -> Option<&ProtectedNamespace> {
-        self.config
-            .ui
-            .protected_namespaces
-            .iter()
-            .find(|ns| key.starts_with(&ns.prefix))
+    /// Load the page of the displayed collection holding element `target`.
+    fn request_page(&mut self, target: usize) {
+        let Some(key) = self.displayed_key().map(|s| s.to_string()) else {
+            return;
+        };
+        let offset = target / VALUE_PAGE_SIZE * VALUE_PAGE_SIZE;
+        self.pending_element = Some(target);
+        self.status_message = format!("Loading elements from {}...", offset);
+        let request_id = self.next_value_request();
+        let _ = self.redis_tx.try_send(RedisCommand::GetValuePage {
+            request_id,
+            key,
+            offset,
+            limit: VALUE_PAGE_SIZE,
+        });
     }
 
-    async fn handle_edit(&mut self) -> Result<()> {
-        if self.config.connection.readonly {
-            self.status_message = "Read-only mode".to_string();
-            return Ok(());
-        }
+    /// Position of the first loaded element of a paged collection value.
+    fn page_offset(&self) -> usize {
+        self.value_page.map_or(0, |p| p.offset)
+    }
 
-        let Some(key) = self.tree_state.selected_key().map(|s| s.to_string()) else {
-            return Ok(());
-        };
+    /// The highlighted collection element, shown while the value pane has
+    /// focus.
+    fn selected_element_index(&self) -> Option<usize> {
+        let count = self.selected_value.as_ref()?.element_count()?;
+        (self.focus == Focus::Value && count > 0).then_some(self.element_index)
+    }
 
-        // Check protection
-        if let Some(ns) = self.check_protection(&key) {
-            match ns.level {
-                ProtectionLevel::Block => {
-                    self.current_dialog = Some(Dialog::Protection {
-                        namespace: ns.prefix.clone(),
-                        level: ns.level,
-                    });
-                    return Ok(());
-                }
-                ProtectionLevel::Confirm | ProtectionLevel::Warn => {
-                    self.current_dialog = Some(Dialog::Protection {
-                        namespace: ns.prefix.clone(),
-                        level: ns.level,
-                    });
-                    // For simplicity, we'll skip edit in this case too
-                    // A full implementation would handle the confirm flow
-                    return Ok(());
-                }
-            }
+    fn toggle_base64(&mut self) {
+        if self.base64_decoded {
+            self.base64_decoded = false;
+            self.render_string_value();
+            self.status_message = "Showing raw value".to_string();
+            return;
         }
-
-        // Get current value
-        let Some(RedisValue::String(current_value)) = &self.selected_value else {
-            self.status_message = "Only string values can be edited".to_string();
-            return Ok(());
+        let bytes = match &self.selected_value {
+            Some(RedisValue::String(s)) => s.as_bytes(),
+            Some(RedisValue::Bytes(b)) => b.as_slice(),
+            _ => {
+                self.status_message = "Only string values can be base64-decoded".to_string();
+                return;
+            }
         };
+        if decode_base64(bytes).is_some() {
+            self.base64_decoded = true;
+            self.render_string_value();
+            self.value_scroll = 0;
+            self.status_message = "Showing base64-decoded value".to_string();
+        } else {
+            self.status_message = "Value is not valid base64".to_string();
+        }
+    }
 
-        // Open editor
-        let editor = ExternalEditor::new()?;
-        match editor.edit(&key, current_value.as_bytes())? {
-            Some(new_value) => {
-                let new_str = String::from_utf8_lossy(&new_value).to_string();
-                self.current_dialog = Some(Dialog::DiffPreview {
-                    key,
-                    old_value: current_value.clone(),
-                    new_value: new_str,
+    async fn perform_action(&mut self, action: Action) -> Result<()> {
+        // Anything but movement acts on the selected key's value, so load
+        // it now rather than after the debounce
+        if self.pending_load.is_some() {
+            self.load_selected_value().await?;
+        }
+        match action {
+            // Navigation depends on the focused pane, see handle_*_action
+            Action::MoveDown
+            | Action::MoveUp
+            | Action::Expand
+            | Action::Collapse
+            | Action::Top
+            | Action::Bottom
+            | Action::PageDown
+            | Action::PageUp => {}
+            Action::SwitchPane => {
+                self.focus = match self.focus {
+                    Focus::Tree => Focus::Value,
+                    Focus::Value => Focus::Tree,
+                };
+            }
+            Action::Search => {
+                let query = self.tree_state.filter.clone().unwrap_or_default();
+                self.open_prompt(PromptKind::Search, query);
+            }
+            Action::SetPattern => {
+                self.open_prompt(PromptKind::Pattern, self.scan_pattern.clone());
+            }
+            Action::GoToKey => {
+                // Start from the selected folder, so only the rest is typed
+                let prefix = self.tree_state.selected_path_prefix().unwrap_or_default();
+                self.open_prompt(PromptKind::GoToKey, escape_key(prefix));
+            }
+            Action::FilterKeys => {
+                let query = self
+                    .tree_state
+                    .key_filter
+                    .as_ref()
+                    .map(|f| f.query.clone())
+                    .unwrap_or_default();
+                self.open_prompt(PromptKind::KeyFilter, query);
+            }
+            Action::CycleTypeFilter => self.cycle_type_filter(),
+            Action::ExpandAll => self.expand_all_selected(),
+            Action::CollapseAll => self.collapse_all(),
+            Action::ShrinkTree | Action::GrowTree => {
+                self.tree_percent = clamp_tree_percent(if action == Action::GrowTree {
+                    self.tree_percent + TREE_PERCENT_STEP
+                } else {
+                    self.tree_percent.saturating_sub(TREE_PERCENT_STEP)
                 });
+                self.status_message = format!("Tree pane: {}%", self.tree_percent);
             }
-            None => {
-                self.status_message = "No changes made".to_string();
+            Action::Rescan => {
+                self.status_message = "Rescanning...".to_string();
+                self.start_scan();
+            }
+            Action::OpenMenu => self.open_context_menu(),
+            Action::CommandPalette => {
+                let is_key =
+                    self.displayed_key().is_some() || self.tree_state.selected_key().is_some();
+                self.current_dialog = Some(Dialog::CommandPalette {
+                    actions: Action::palette(is_key, self.config.connection.readonly),
+                    input: String::new(),
+                    selected: 0,
+                });
+            }
+            Action::SelectDb => {
+                self.current_dialog = Some(Dialog::SelectDb {
+                    current: self.config.connection.db,
+                    input: String::new(),
+                });
+            }
+            Action::ToggleBase64 => self.toggle_base64(),
+            Action::ToggleWrap => {
+                self.wrap_enabled = !self.wrap_enabled;
+                self.value_hscroll = 0;
+                self.status_message = if self.wrap_enabled {
+                    "Wrapping long lines".to_string()
+                } else {
+                    "Wrap off: h/l scroll the value pane sideways".to_string()
+                };
+            }
+            Action::ToggleSort => {
+                self.sort_members = !self.sort_members;
+                self.element_index = 0;
+                if self.sort_members {
+                    if let Some(value) = self.selected_value.as_mut() {
+                        value.sort_members();
+                    }
+                    self.status_message = "Sorting members by name".to_string();
+                } else {
+                    // The server's order is only known by reading it again
+                    self.status_message = "Showing members in server order".to_string();
+                    self.reload_displayed_value().await?;
+                }
+            }
+            Action::ToggleLineNumbers => {
+                let ui = &mut self.config.ui;
+                ui.line_numbers = !ui.line_numbers;
+                self.status_message = if ui.line_numbers {
+                    "Line numbers on".to_string()
+                } else {
+                    "Line numbers off".to_string()
+                };
+            }
+            Action::LoadFull => match self.displayed_key() {
+                Some(key) if self.value_truncated.is_some() => {
+                    let key = key.to_string();
+                    self.status_message = format!("Loading all of {}...", display_key(&key));
+                    let request_id = self.next_value_request();
+                    self.redis_tx
+                        .send(RedisCommand::GetFullValue { request_id, key })
+                        .await?;
+                }
+                _ => self.status_message = "The value is already fully loaded".to_string(),
+            },
+            Action::CreateKey => self.handle_create_key(),
+            Action::Subscribe => self.open_prompt(PromptKind::Subscribe, String::new()),
+            Action::ServerInfo => {
+                self.status_message = "Loading server info...".to_string();
+                self.redis_tx.send(RedisCommand::GetInfo).await?;
+            }
+            Action::Help => self.current_dialog = Some(Dialog::Help),
+            Action::Back if self.monitor.is_some() => self.stop_monitor(),
+            // Esc clears an active search before it quits
+            Action::Back if self.tree_state.filter.is_some() => self.set_search_filter(None),
+            Action::Back if self.tree_state.key_filter.is_some() => self.set_key_filter(None),
+            Action::Back | Action::Quit => self.request_quit(),
+            Action::Edit => self.handle_edit().await?,
+            Action::Import => self.handle_import(),
+            Action::Delete => self.handle_delete().await?,
+            Action::DeleteFolder => self.handle_delete_folder(),
+            Action::RemoveElement => self.handle_remove_element(),
+            Action::Refresh => self.reload_displayed_value().await?,
+            Action::SetTtl => self.handle_set_ttl(),
+            Action::Rename => self.handle_rename(),
+            Action::CopyKey => self.handle_copy_key(),
+            Action::TogglePin => self.toggle_pin().await?,
+            Action::ScopePattern => {
+                // Scope the pattern to the selected folder or key
+                if let Some(path) = self.tree_state.selected_path() {
+                    let pattern = format!("{}*", glob_escape(path));
+                    self.open_prompt(PromptKind::Pattern, pattern);
+                }
+            }
+            Action::CopyPath => {
+                // Folders copy their prefix (e.g. `user:1:`), keys their name
+                let state = &self.tree_state;
+                let is_folder = state.selected_path().is_some()
+                    && state.selected_path() == state.selected_path_prefix();
+                // Raw bytes copy as their `\xNN` escapes
+                if let Some(path) = state.selected_path().map(display_key) {
+                    let what = if is_folder {
+                        format!("prefix {}", path)
+                    } else {
+                        format!("key {}", path)
+                    };
+                    self.copy_to_clipboard(&path, &what);
+                }
+            }
+            Action::CopyValue => {
+                let text = ValueView::new(
+                    self.selected_value.as_ref(),
+                    self.displayed_key(),
+                    &self.theme,
+                    0,
+                    false,
+                    self.base64_decoded,
+                    None,
+                )
+                .score_precision(self.config.ui.zset_score_precision)
+                .rendered(self.string_lines.as_ref())
+                .offset(self.page_offset())
+                .text();
+                match (text, self.displayed_key().map(|s| s.to_string())) {
+                    (Some(text), Some(key)) => {
+                        self.copy_to_clipboard(&text, &format!("value of {}", key))
+                    }
+                    _ => self.status_message = "No value to copy".to_string(),
+                }
             }
         }
-
         Ok(())
     }
 
-    async fn handle_delete(&mut self) -> Result<()> {
-        if self.config.connection.readonly {
-            self.status_message = "Read-only mode".to_string();
-            return Ok(());
-        }
-
-        let Some(key) = self.tree_state.selected_key().map(|s| s.to_string()) else {
-            return Ok(());
+    /// Copy `text`, reporting the outcome in the status bar rather than
+    /// failing, since clipboard access is often unavailable.
+    fn copy_to_clipboard(&mut self, text: &str, what: &str) {
+        self.status_message = match self.clipboard.copy(text) {
+            Ok(CopyMethod::System) => format!("Copied {}", what),
+            Ok(CopyMethod::Osc52) => format!("Copied {} (via terminal)", what),
+            Err(e) => format!("Copy failed: {}", e),
         };
+    }
 
-        // Check protection
-        if let Some(ns) = self.check_protection(&key) {
-            self.current_dialog = Some(Dialog::Protection {
-                namespace: ns.prefix.clone(),
-                level: ns.level,
-            });
-            return Ok(());
-        }
-
-        self.current_dialog = Some(Dialog::Confirm {
-            title: "Delete Key".to_string(),
-            message: format!("Delete '{}'?", key),
-            confirm_text: "yes".to_string(),
+    fn open_context_menu(&mut self) {
+        let Some(idx) = self.tree_state.list_state.selected() else {
+            return;
+        };
+        let Some(node) = self.tree_state.flattened.get(idx) else {
+            return;
+        };
+        let actions = Action::for_node(node.full_key.is_some(), self.config.connection.readonly);
+        self.current_dialog = Some(Dialog::ContextMenu {
+            title: node.name.clone(),
+            actions,
+            selected: 0,
         });
+    }
 
-        Ok(())
+    async fn handle_dialog_key(&mut self, key: KeyEvent) -> Result<()> {
+        let unsaved = self
+            .current_dialog
+            .as_ref()
+            .is_some_and(Dialog::has_unsaved_changes);
+        match &mut self.current_dialog {
+            Some(Dialog::Protection {
+                key: target,
+                level,
+                input,
+                resume,
+                ..
+            }) => {
+                let proceed = match (*level, key.code) {
+                    (_, KeyCode::Esc) | (ProtectionLevel::Block, _) => false,
+                    (ProtectionLevel::Warn, KeyCode::Enter | KeyCode::Char('y')) => true,
+                    (ProtectionLevel::Warn, _) => false,
+                    (ProtectionLevel::Confirm, KeyCode::Enter) if input == "yes" => true,
+                    (ProtectionLevel::Confirm, KeyCode::Enter) => {
+                        self.status_message = "Type 'yes' to confirm".to_string();
+                        return Ok(());
+                    }
+                    (ProtectionLevel::Confirm, code) => {
+                        edit_input(input, code);
+                        return Ok(());
+                    }
+                };
+                let target = target.clone();
+                let resume = *resume;
+                self.current_dialog = None;
+                let pending = self.pending_action.take();
+                if !proceed {
+                    return Ok(());
+                }
+                if let Some(cmd) = pending {
+                    self.send_command(cmd).await?;
+                } else if let Some(action) = resume {
+                    self.protection_ack = Some(target);
+                    self.perform_action(action).await?;
+                }
+                return Ok(());
+            }
+            Some(Dialog::DiffPreview {
+                lines,
+                scroll,
+                confirm_discard,
+                ..
+            }) => {
+                if *confirm_discard {
+                    // Only an explicit `y` throws the edit away
+                    if key.code == KeyCode::Char('y') {
+                        self.current_dialog = None;
+                        self.status_message = "Changes discarded".to_string();
+                    } else {
+                        *confirm_discard = false;
+                    }
+                    return Ok(());
+                }
+                if key.code == KeyCode::Esc && unsaved {
+                    *confirm_discard = true;
+                    return Ok(());
+                }
+                let screen = self.layout.header_area.union(self.layout.status_area);
+                let page = diff_preview_height(screen).max(1);
+                let max_scroll = lines.len().saturating_sub(page);
+                let target = match key.code {
+                    KeyCode::Char('j') | KeyCode::Down => Some(*scroll + 1),
+                    KeyCode::Char('k') | KeyCode::Up => Some(scroll.saturating_sub(1)),
+                    KeyCode::PageDown => Some(*scroll + page),
+                    KeyCode::PageUp => Some(scroll.saturating_sub(page)),
+                    // Enter and Esc are handled below
+                    _ => None,
+                };
+                if let Some(target) = target {
+                    *scroll = target.min(max_scroll);
+                    return Ok(());
+                }
+            }
+            Some(Dialog::ServerInfo { info, scroll }) => {
+                let screen = self.layout.header_area.union(self.layout.status_area);
+                let page = server_info_height(screen).max(1);
+                let max_scroll = server_info_lines(info).len().saturating_sub(page);
+                let target = match key.code {
+                    KeyCode::Char('j') | KeyCode::Down => Some(*scroll + 1),
+                    KeyCode::Char('k') | KeyCode::Up => Some(scroll.saturating_sub(1)),
+                    KeyCode::PageDown => Some(*scroll + page),
+                    KeyCode::PageUp => Some(scroll.saturating_sub(page)),
+                    KeyCode::Char('g') | KeyCode::Home => Some(0),
+                    KeyCode::Char('G') | KeyCode::End => Some(max_scroll),
+                    // Esc is handled below
+                    _ => None,
+                };
+                if let Some(target) = target {
+                    *scroll = target.min(max_scroll);
+                }
+                if key.code != KeyCode::Esc {
+                    return Ok(());
+                }
+            }
+            Some(Dialog::Confirm {
+                confirm_text,
+                input,
+                ..
+            }) => {
+                match key.code {
+                    KeyCode::Enter => {
+                        if input != confirm_text {
+                            self.status_message = format!("Type '{}' to confirm", confirm_text);
+                            return Ok(());
+                        }
+                        self.current_dialog = None;
+                        if let Some(cmd) = self.pending_action.take() {
+                            self.send_command(cmd).await?;
+                        }
+                    }
+                    KeyCode::Esc => {
+                        self.current_dialog = None;
+                        self.pending_action = None;
+                    }
+                    code => {
+                        edit_input(input, code);
+                    }
+                }
+                return Ok(());
+            }
+            Some(Dialog::ContextMenu {
+                actions, selected, ..
+            }) => {
+                if move_selection(selected, actions.len(), key.code) {
+                    return Ok(());
+                }
+                match key.code {
+                    KeyCode::Enter => {
+                        let action = actions.get(*selected).copied();
+                        self.current_dialog = None;
+                        if let Some(action) = action {
+                            self.perform_action(action).await?;
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        self.current_dialog = None;
+                    }
+                    _ => {}
+                }
+                return Ok(());
+            }
+            Some(Dialog::CommandPalette {
+                actions,
+                input,
+                selected,
+            }) => {
+                let matches = Action::filter(actions, input);
+                match key.code {
+                    KeyCode::Enter => {
+                        let action = matches.get(*selected).copied();
+                        self.current_dialog = None;
+                        if let Some(action) = action {
+                            self.perform_action(action).await?;
+                        }
+                    }
+                    KeyCode::Esc => self.current_dialog = None,
+                    KeyCode::Down => {
+                        *selected = (*selected + 1).min(matches.len().saturating_sub(1))
+                    }
+                    KeyCode::Up => *selected = selected.saturating_sub(1),
+                    code => {
+                        if edit_input(input, code) {
+                            *selected = 0;
+                        }
+                    }
+                }
+                return Ok(());
+            }
+            Some(Dialog::ConfirmQuit) => {
+                match key.code {
+                    KeyCode::Enter | KeyCode::Char('y') => self.should_quit = true,
+                    KeyCode::Esc | KeyCode::Char('n') => self.current_dialog = None,
+                    _ => {}
+                }
+                return Ok(());
+            }
+            Some(Dialog::RemoveElement {
+                key: target,
+                element,
+            }) => {
+                match key.code {
+                    KeyCode::Enter | KeyCode::Char('y') => {
+                        let key = target.clone();
+                        let cmd = match element.clone() {
+                            ValueElement::ListIndex(index) => {
+                                Some(RedisCommand::RemoveListElement { key, index })
+                            }
+                            ValueElement::SetMember(member) => {
+                                Some(RedisCommand::RemoveSetMember { key, member })
+                            }
+                            _ => None,
+                        };
+                        self.current_dialog = None;
+                        if let Some(cmd) = cmd {
+                            self.send_command(cmd).await?;
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('q') => {
+                        self.current_dialog = None;
+                    }
+                    _ => {}
+                }
+                return Ok(());
+            }
+            Some(Dialog::HashFieldPicker {
+                key: hash_key,
+                fields,
+                selected,
+            }) => {
+                if move_selection(selected, fields.len(), key.code) {
+                    return Ok(());
+                }
+                match key.code {
+                    KeyCode::Enter => {
+                        let hash_key = hash_key.clone();
+                        let field = fields.get(*selected).cloned();
+                        self.current_dialog = None;
+                        if let Some(field) = field {
+                            self.edit_hash_field(hash_key, field)?;
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        self.current_dialog = None;
+                    }
+                    _ => {}
+                }
+                return Ok(());
+            }
+            Some(Dialog::SetTtl {
+                key: ttl_key,
+                input,
+                ..
+            }) => {
+                match key.code {
+                    KeyCode::Enter => {
+                        let input = input.trim().to_lowercase();
+                        // EXPIRE with 0 deletes the key, which only the
+                        // delete confirmation may do
+                        let seconds = match input.as_str() {
+                            "persist" => None,
+                            _ => match input.parse::<i64>() {
+                                Ok(secs) if secs > 0 => Some(secs),
+                                Ok(_) => {
+                                    self.status_message =
+                                        "TTL must be at least 1 second; delete the key to remove it"
+                                            .to_string();
+                                    return Ok(());
+                                }
+                                Err(_) => {
+                                    self.status_message = format!("Invalid TTL '{}'", input);
+                                    return Ok(());
+                                }
+                            },
+                        };
+                        let key = ttl_key.clone();
+                        self.current_dialog = None;
+                        self.send_command(RedisCommand::SetTtl { key, seconds })
+                            .await?;
+                    }
+                    KeyCode::Esc => {
+                        self.current_dialog = None;
+                    }
+                    code => {
+                        edit_input(input, code);
+                    }
+                }
+                return Ok(());
+            }
+            Some(Dialog::SelectDb { input, .. }) => {
+                match key.code {
+                    KeyCode::Enter => {
+                        let db = match input.trim().parse::<u8>() {
+                            Ok(db) if db <= 15 => db,
+                            _ => {
+                                self.status_message = format!("Invalid database '{}'", input);
+                                return Ok(());
+                            }
+                        };
+                        self.current_dialog = None;
+                        self.redis_tx.send(RedisCommand::SelectDb { db }).await?;
+                    }
+                    KeyCode::Esc => {
+                        self.current_dialog = None;
+                    }
+                    code => {
+                        edit_input(input, code);
+                    }
+                }
+                return Ok(());
+            }
+            Some(Dialog::CreateKey {
+                key_type,
+                name,
+                value,
+                field,
+            }) => {
+                let collection = *key_type != RedisType::String;
+                match (key.code, *field) {
+                    (KeyCode::Tab, _)
+                    | (KeyCode::Enter, CreateKeyField::Name | CreateKeyField::Type) => {
+                        *field = field.next();
+                    }
+                    (KeyCode::BackTab, _) => *field = field.previous(),
+                    (KeyCode::Left | KeyCode::Right | KeyCode::Char(' '), CreateKeyField::Type) => {
+                        let types = &CREATE_KEY_TYPES;
+                        let current = types.iter().position(|t| t == key_type).unwrap_or(0);
+                        let step = if key.code == KeyCode::Left {
+                            types.len() - 1
+                        } else {
+                            1
+                        };
+                        *key_type = types[(current + step) % types.len()];
+                    }
+                    // A collection takes one member per line; Enter on an
+                    // empty line creates it
+                    (KeyCode::Enter, CreateKeyField::Value)
+                        if collection && !value.is_empty() && !value.ends_with('\n') =>
+                    {
+                        value.push('\n');
+                    }
+                    (KeyCode::Enter, CreateKeyField::Value) => {
+                        let name = parse_key(name);
+                        let key_type = *key_type;
+                        let value = value.clone();
+                        if name.is_empty() {
+                            self.status_message = "Key name is empty".to_string();
+                            return Ok(());
+                        }
+                        // Creating must not silently overwrite a key; keys
+                        // that are not loaded are caught by SET NX
+                        if self.loaded_keys.iter().any(|(k, _)| *k == name) {
+                            self.status_message =
+                                format!("Key {} already exists", display_key(&name));
+                            return Ok(());
+                        }
+                        let cmd = if collection {
+                            match parse_members(key_type, &value) {
+                                Ok(value) => RedisCommand::CreateCollection {
+                                    key: name.clone(),
+                                    value,
+                                },
+                                Err(e) => {
+                                    self.status_message = e.to_string();
+                                    return Ok(());
+                                }
+                            }
+                        } else {
+                            RedisCommand::CreateString {
+                                key: name.clone(),
+                                value: value.into_bytes(),
+                            }
+                        };
+                        self.current_dialog = None;
+                        self.send_protected(&name, cmd).await?;
+                    }
+                    (KeyCode::Char('o'), _)
+                        if key.modifiers.contains(KeyModifiers::CONTROL) && collection =>
+                    {
+                        self.status_message =
+                            "Only string values can be read from a file".to_string();
+                    }
+                    (KeyCode::Char('o'), _) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        // Take the new key's value from a file instead
+                        let name = parse_key(name);
+                        if name.is_empty() {
+                            self.status_message = "Key name is empty".to_string();
+                            return Ok(());
+                        }
+                        if self.loaded_keys.iter().any(|(k, _)| *k == name) {
+                            self.status_message =
+                                format!("Key {} already exists", display_key(&name));
+                            return Ok(());
+                        }
+                        if self.allow_write(&name, Action::CreateKey) {
+                            self.current_dialog = Some(Dialog::Import {
+                                key: name,
+                                path_input: String::new(),
+                                create: true,
+                            });
+                        }
+                    }
+                    (KeyCode::Esc, _) => {
+                        self.current_dialog = None;
+                    }
+                    (code, CreateKeyField::Name) => {
+                        edit_input(name, code);
+                    }
+                    (_, CreateKeyField::Type) => {}
+                    (code, CreateKeyField::Value) => {
+                        edit_input(value, code);
+                    }
+                }
+                return Ok(());
+            }
+            Some(Dialog::Import {
+                key: target,
+                path_input,
+                create,
+            }) => {
+                match key.code {
+                    KeyCode::Enter => {
+                        let target = target.clone();
+                        let create = *create;
+                        let path = expand_home(path_input.trim());
+                        if path.as_os_str().is_empty() {
+                            self.status_message = "Enter a file path".to_string();
+                            return Ok(());
+                        }
+                        match std::fs::read(&path) {
+                            Ok(bytes) => self.preview_import(target, bytes, create),
+                            Err(e) => {
+                                self.status_message =
+                                    format!("Cannot read {}: {}", path.display(), e);
+                            }
+                        }
+                    }
+                    KeyCode::Esc => {
+                        self.current_dialog = None;
+                    }
+                    code => {
+                        edit_input(path_input, code);
+                    }
+                }
+                return Ok(());
+            }
+            Some(Dialog::CopyKey { source, dest_input }) => {
+                match key.code {
+                    KeyCode::Enter => {
+                        let source = source.clone();
+                        let dest = parse_key(dest_input);
+                        self.current_dialog = None;
+                        if dest.is_empty() || dest == source {
+                            return Ok(());
+                        }
+                        let target = dest.clone();
+                        let cmd = RedisCommand::CopyKey {
+                            source,
+                            dest,
+                            replace: false,
+                        };
+                        self.send_protected(&target, cmd).await?;
+                    }
+                    KeyCode::Esc => {
+                        self.current_dialog = None;
+                    }
+                    code => {
+                        edit_input(dest_input, code);
+                    }
+                }
+                return Ok(());
+            }
+            Some(Dialog::Rename {
+                old_key,
+                new_key_input,
+            }) => {
+                match key.code {
+                    KeyCode::Enter => {
+                        let old = old_key.clone();
+                        let new = parse_key(new_key_input);
+                        if new.is_empty() || new == old {
+                            self.current_dialog = None;
+                            return Ok(());
+                        }
+                        // The destination may land in a protected namespace
+                        self.current_dialog = None;
+                        let dest = new.clone();
+                        let cmd = RedisCommand::RenameKey {
+                            old,
+                            new,
+                            replace: false,
+                        };
+                        self.send_protected(&dest, cmd).await?;
+                    }
+                    KeyCode::Esc => {
+                        self.current_dialog = None;
+                    }
+                    code => {
+                        edit_input(new_key_input, code);
+                    }
+                }
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        match key.code {
+            KeyCode::Esc => {
+                self.current_dialog = None;
+            }
+            KeyCode::Enter => {
+                // Handle confirm actions based on dialog type
+                if let Some(Dialog::DiffPreview {
+                    key,
+                    element,
+                    new_value,
+                    raw,
+                    create,
+                    ..
+                }) = &self.current_dialog
+                {
+                    if !self.config.connection.readonly {
+                        let key = key.clone();
+                        let cmd = match element.clone() {
+                            // A file read from the create dialog goes through
+                            // SET NX like any other new key
+                            None if *create => Some(RedisCommand::CreateString {
+                                key,
+                                value: raw.clone().unwrap_or_else(|| new_value.as_bytes().to_vec()),
+                            }),
+                            None => Some(RedisCommand::SetValue {
+                                key,
+                                value: raw.clone().unwrap_or_else(|| new_value.as_bytes().to_vec()),
+                            }),
+                            Some(ValueElement::HashField(field)) => {
+                                Some(RedisCommand::SetHashField {
+                                    key,
+                                    field,
+                                    value: new_value.clone(),
+                                })
+                            }
+                            Some(ValueElement::ListIndex(index)) => {
+                                Some(RedisCommand::SetListElement {
+                                    key,
+                                    index,
+                                    value: new_value.clone(),
+                                })
+                            }
+                            Some(ValueElement::ZSetMember(member)) => {
+                                match new_value.trim().parse::<f64>() {
+                                    Ok(score) => {
+                                        Some(RedisCommand::SetZSetScore { key, member, score })
+                                    }
+                                    Err(_) => {
+                                        self.status_message =
+                                            format!("Invalid score '{}'", new_value.trim());
+                                        None
+                                    }
+                                }
+                            }
+                            Some(ValueElement::SetMember(_)) => None,
+                        };
+                        if let Some(cmd) = cmd {
+                            self.send_command(cmd).await?;
+                        }
+                    }
+                }
+                self.current_dialog = None;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Subscribe to `target` on a dedicated connection and show its
+    /// messages in the value pane, replacing any running monitor.
+    fn start_monitor(&mut self, target: String) {
+        self.stop_monitor();
+        let (stop, stop_rx) = oneshot::channel();
+        let (incoming_tx, incoming) = mpsc::channel(MONITOR_QUEUE);
+        tokio::spawn(run_pubsub(
+            self.config.connection.clone(),
+            target.clone(),
+            stop_rx,
+            incoming_tx,
+            self.ui_tx.clone(),
+        ));
+        self.status_message = format!("Subscribed to {}", target);
+        self.monitor = Some(PubSubMonitor {
+            target,
+            messages: VecDeque::new(),
+            incoming,
+            stop,
+        });
+    }
+
+    /// Move queued Pub/Sub messages into the monitor buffer, at most
+    /// `MESSAGES_PER_FRAME` at a time.
+    fn drain_monitor(&mut self) {
+        let Some(monitor) = self.monitor.as_mut() else {
+            return;
+        };
+        let mut received = false;
+        for _ in 0..MESSAGES_PER_FRAME {
+            let Ok(message) = monitor.incoming.try_recv() else {
+                break;
+            };
+            if monitor.messages.len() == MONITOR_BUFFER {
+                monitor.messages.pop_front();
+            }
+            monitor.messages.push_back(message);
+            received = true;
+        }
+        if received {
+            self.touch();
+        }
+    }
+
+    fn stop_monitor(&mut self) {
+        if let Some(monitor) = self.monitor.take() {
+            let _ = monitor.stop.send(());
+            self.status_message = format!("Unsubscribed from {}", monitor.target);
+        }
+    }
+
+    fn start_scan(&mut self) {
+        self.rescan_snapshot = Some(self.tree_state.snapshot(&self.tree_nodes));
+        self.scan_id += 1;
+        self.current_scan.store(self.scan_id, Ordering::Relaxed);
+        self.scanning = true;
+        self.loaded_keys.clear();
+        self.pending_types.clear();
+        self.unloaded_prefixes.clear();
+        self.tree_nodes.clear();
+        self.tree_state.list_state.select(None);
+        self.tree_state.flatten(&self.tree_nodes);
+        let _ = self.redis_tx.try_send(RedisCommand::ScanKeys {
+            scan_id: self.scan_id,
+            pattern: self.scan_pattern.clone(),
+        });
+    }
+
+    fn tree_builder(&self) -> TreeBuilder {
+        TreeBuilder::new(self.config.ui.delimiters.clone())
+            .per_level(self.config.ui.per_level_delimiters)
+    }
+
+    /// Builder for merging scanned keys: during a rescan, folders that were
+    /// expanded before it open again as they are recreated.
+    fn scan_builder(&self) -> TreeBuilder {
+        match &self.rescan_snapshot {
+            Some(snapshot) => self
+                .tree_builder()
+                .expanding(snapshot.expanded_prefixes().clone()),
+            None => self.tree_builder(),
+        }
+    }
+
+    /// Keep the user's own selection rather than the pre-rescan one.
+    fn forget_rescan_selection(&mut self) {
+        if let Some(snapshot) = &mut self.rescan_snapshot {
+            snapshot.forget_selection();
+        }
+    }
+
+    fn rebuild_tree(&mut self) {
+        let snapshot = self.tree_state.snapshot(&self.tree_nodes);
+        let builder = self.tree_builder();
+        self.tree_nodes = builder.build_filtered(&self.loaded_keys, self.type_filter);
+        for prefix in &self.unloaded_prefixes {
+            builder.insert_stub(&mut self.tree_nodes, prefix);
+        }
+        builder.sort_nodes(&mut self.tree_nodes);
+        self.tree_state.restore(&mut self.tree_nodes, &snapshot);
+    }
+
+    /// Add scanned keys under `prefix` (`""` for a full scan) to the tree.
+    /// With lazy loading, keys more than one level below `prefix` are
+    /// represented by unloaded folders instead.
+    fn merge_batch(&mut self, prefix: &str, batch: Vec<(String, RedisType)>) {
+        let builder = self.scan_builder();
+        let levels = if self.config.ui.lazy_load {
+            builder.depth_of(prefix) + 1
+        } else {
+            0
+        };
+        for (key, redis_type) in batch {
+            if let Some(stub) = builder.stub_prefix(&key, levels) {
+                if builder.insert_stub(&mut self.tree_nodes, &stub) {
+                    self.unloaded_prefixes.insert(stub);
+                }
+                continue;
+            }
+            if self.type_filter.is_none_or(|t| t == redis_type) {
+                builder.insert_key(&mut self.tree_nodes, &key, redis_type);
+            }
+            if redis_type == RedisType::Unknown {
+                self.pending_types
+                    .insert(key.clone(), self.loaded_keys.len());
+            }
+            self.loaded_keys.push((key, redis_type));
+        }
+        self.tree_dirty = true;
+    }
+
+    /// Sort and flatten the tree once after the batches merged this frame.
+    /// The selection stays on its key as rows are inserted around it, or
+    /// moves to the pre-rescan one as soon as that is loaded.
+    fn flush_tree(&mut self) {
+        if std::mem::take(&mut self.tree_dirty) {
+            let selection = self.tree_state.selection();
+            self.tree_builder().sort_nodes(&mut self.tree_nodes);
+            self.tree_state.flatten(&self.tree_nodes);
+            if let Some((key, index)) = selection {
+                self.tree_state.reselect_key(&key, index);
+            }
+            if let Some(snapshot) = &self.rescan_snapshot {
+                self.tree_state.restore_selection(snapshot, false);
+            }
+        }
+    }
+
+    /// Patch the types of already merged keys. Nodes are updated in place;
+    /// under a type filter, keys that turn out to match are added.
+    fn resolve_types(&mut self, types: Vec<(String, RedisType)>) {
+        let builder = self.scan_builder();
+        for (key, redis_type) in types {
+            let Some(index) = self.pending_types.remove(&key) else {
+                continue;
+            };
+            if let Some(entry) = self.loaded_keys.get_mut(index) {
+                entry.1 = redis_type;
+            }
+            if self.type_filter.is_none_or(|t| t == redis_type) {
+                builder.insert_key(&mut self.tree_nodes, &key, redis_type);
+            }
+        }
+        self.tree_dirty = true;
+    }
+
+    /// Expand an unloaded folder and scan the keys under it.
+    fn load_folder(&mut self, path: &[usize]) {
+        let Some(node) = node_at_path_mut(&mut self.tree_nodes, path) else {
+            return;
+        };
+        let Some(prefix) = node.prefix.clone() else {
+            return;
+        };
+        node.loaded = true;
+        node.expanded = true;
+        self.unloaded_prefixes.remove(&prefix);
+        self.tree_state.flatten(&self.tree_nodes);
+        self.status_message = format!("Loading {}...", prefix);
+        let _ = self.redis_tx.try_send(RedisCommand::ScanPrefix {
+            scan_id: self.scan_id,
+            prefix,
+        });
+    }
+
+    /// Step the client-side type filter: all -> string -> ... -> stream -> all.
+    fn cycle_type_filter(&mut self) {
+        let all = RedisType::ALL;
+        self.type_filter = match self.type_filter {
+            None => all.first().copied(),
+            Some(t) => all
+                .iter()
+                .position(|&x| x == t)
+                .and_then(|i| all.get(i + 1))
+                .copied(),
+        };
+        self.tree_state.list_state.select(None);
+        self.rebuild_tree();
+        self.status_message = match self.type_filter {
+            Some(t) => format!("Showing {} keys", t.as_str()),
+            None => "Showing all types".to_string(),
+        };
+    }
+
+    fn open_prompt(&mut self, kind: PromptKind, input: String) {
+        self.prompt = Some(Prompt { kind, input });
+    }
+
+    async fn handle_prompt_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(prompt) = self.prompt.as_mut() else {
+            return Ok(());
+        };
+
+        match key.code {
+            KeyCode::Esc => {
+                match prompt.kind {
+                    PromptKind::Search => self.set_search_filter(None),
+                    PromptKind::KeyFilter => self.set_key_filter(None),
+                    PromptKind::ValueSearch => self.value_search = None,
+                    _ => {}
+                }
+                self.prompt = None;
+            }
+            KeyCode::Tab if prompt.kind == PromptKind::ValueSearch => {
+                if let Some(search) = self.value_search.as_mut() {
+                    search.case_sensitive = !search.case_sensitive;
+                    search.current = 0;
+                }
+                self.jump_to_value_match();
+            }
+            KeyCode::Enter => {
+                let Some(prompt) = self.prompt.take() else {
+                    return Ok(());
+                };
+                match prompt.kind {
+                    // The filter is already applied live as the query is typed
+                    PromptKind::Search => {}
+                    PromptKind::ValueSearch if prompt.input.is_empty() => {
+                        self.value_search = None;
+                    }
+                    PromptKind::ValueSearch => {}
+                    PromptKind::Pattern => {
+                        let pattern = if prompt.input.is_empty() {
+                            "*".to_string()
+                        } else {
+                            prompt.input
+                        };
+                        self.status_message = format!("Scanning {}...", pattern);
+                        self.scan_pattern = pattern;
+                        self.start_scan();
+                    }
+                    PromptKind::Subscribe if !prompt.input.is_empty() => {
+                        self.start_monitor(prompt.input);
+                    }
+                    PromptKind::Subscribe => {}
+                    PromptKind::GoToKey if !prompt.input.is_empty() => {
+                        self.go_to_key(parse_key(&prompt.input)).await?;
+                    }
+                    PromptKind::GoToKey => {}
+                    PromptKind::KeyFilter => {
+                        if let Err(e) = KeyFilter::parse(&prompt.input) {
+                            self.status_message = format!("Filter: {}", e);
+                            self.prompt = Some(prompt);
+                        }
+                    }
+                }
+            }
+            code => {
+                if !edit_input(&mut prompt.input, code) {
+                    return Ok(());
+                }
+                let query = prompt.input.clone();
+                match prompt.kind {
+                    PromptKind::Search => {
+                        self.set_search_filter(Some(query).filter(|q| !q.is_empty()));
+                    }
+                    // Half-typed terms like `type:ha` keep the last valid filter
+                    PromptKind::KeyFilter => {
+                        if let Ok(filter) = KeyFilter::parse(&query) {
+                            self.set_key_filter(filter);
+                        }
+                    }
+                    PromptKind::ValueSearch => {
+                        if let Some(search) = self.value_search.as_mut() {
+                            search.query = query;
+                            search.current = 0;
+                        }
+                        self.jump_to_value_match();
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lines of the displayed value that match the value search.
+    fn value_matches(&self) -> Vec<usize> {
+        let Some(search) = &self.value_search else {
+            return Vec::new();
+        };
+        let (lines, _) = ValueView::new(
+            self.selected_value.as_ref(),
+            self.displayed_key(),
+            &self.theme,
+            0,
+            false,
+            self.base64_decoded,
+            None,
+        )
+        .score_precision(self.config.ui.zset_score_precision)
+        .rendered(self.string_lines.as_ref())
+        .offset(self.page_offset())
+        .lines();
+        search.matching_lines(&lines)
+    }
+
+    /// Scroll the value pane (or move the element cursor) to the current
+    /// search match.
+    fn jump_to_value_match(&mut self) {
+        let matches = self.value_matches();
+        let Some(search) = self.value_search.as_mut() else {
+            return;
+        };
+        search.current = search.current.min(matches.len().saturating_sub(1));
+        let Some(&line) = matches.get(search.current) else {
+            return;
+        };
+        if self
+            .selected_value
+            .as_ref()
+            .and_then(|v| v.element_count())
+            .is_some()
+        {
+            self.element_index = line;
+        } else {
+            // With wrapping, lines above the match may take several rows
+            let width = self.layout.value_area.width.saturating_sub(2);
+            let row = self.pane_view().row_of_line(line, width);
+            self.value_scroll = row.try_into().unwrap_or(u16::MAX);
+        }
+    }
+
+    /// Move to the next (or previous) value search match, wrapping around.
+    fn step_value_match(&mut self, forward: bool) {
+        let count = self.value_matches().len();
+        let Some(search) = self.value_search.as_mut() else {
+            return;
+        };
+        if count == 0 {
+            self.status_message = format!("No matches for '{}'", search.query);
+            return;
+        }
+        search.current = if forward {
+            (search.current + 1) % count
+        } else {
+            (search.current + count - 1) % count
+        };
+        self.status_message = format!("Match {}/{}", search.current + 1, count);
+        self.jump_to_value_match();
+    }
+
+    fn set_search_filter(&mut self, query: Option<String>) {
+        self.tree_state.filter = query;
+        self.tree_state.list_state.select(None);
+        self.tree_state.flatten(&self.tree_nodes);
+    }
+
+    fn set_key_filter(&mut self, filter: Option<KeyFilter>) {
+        if self.tree_state.key_filter == filter {
+            return;
+        }
+        self.tree_state.key_filter = filter;
+        self.tree_state.list_state.select(None);
+        self.tree_state.flatten(&self.tree_nodes);
+    }
+
+    fn toggle_node_at_path(&mut self, path: &[usize]) {
+        if let Some(node) = node_at_path_mut(&mut self.tree_nodes, path) {
+            node.expanded = !node.expanded;
+        }
+    }
+
+    /// Expand everything under the selected node, bounded by the configured
+    /// depth so huge subtrees don't flood the render loop.
+    fn expand_all_selected(&mut self) {
+        let Some(path) = self.selected_node_path() else {
+            return;
+        };
+        let max_depth = self.config.ui.expand_all_depth;
+        if let Some(node) = node_at_path_mut(&mut self.tree_nodes, &path) {
+            node.set_expanded_recursive(true, max_depth);
+        }
+        self.tree_state.flatten(&self.tree_nodes);
+    }
+
+    /// Collapse every folder and move the selection to the root-level
+    /// ancestor of the previously selected node.
+    fn collapse_all(&mut self) {
+        let root = self.selected_node_path().and_then(|p| p.first().copied());
+        for node in &mut self.tree_nodes {
+            node.set_expanded_recursive(false, usize::MAX);
+        }
+        self.tree_state.list_state.select(root);
+        self.tree_state.flatten(&self.tree_nodes);
+    }
+
+    fn selected_node_path(&self) -> Option<Vec<usize>> {
+        let idx = self.tree_state.list_state.selected()?;
+        Some(self.tree_state.flattened.get(idx)?.node_index.clone())
+    }
+
+    /// The key whose value is shown in the value pane: the pinned key if
+    /// any, otherwise the tree selection.
+    fn displayed_key(&self) -> Option<&str> {
+        self.pinned_key
+            .as_deref()
+            .or_else(|| self.tree_state.selected_key())
+    }
+
+    async fn load_selected_value(&mut self) -> Result<()> {
+        self.pending_load = None;
+        if let Some(key) = self.reset_for_selection() {
+            let request_id = self.next_value_request();
+            self.redis_tx
+                .send(RedisCommand::GetValue { request_id, key })
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Load the selected value once the selection has stayed put for
+    /// `LOAD_DEBOUNCE`, so scrolling past keys doesn't request each one.
+    fn schedule_load(&mut self) {
+        self.pending_load = Some(Instant::now() + LOAD_DEBOUNCE);
+    }
+
+    async fn check_pending_load(&mut self) -> Result<()> {
+        if self.pending_load.is_some_and(|due| Instant::now() >= due) {
+            self.dirty = true;
+            self.load_selected_value().await?;
+        }
+        Ok(())
+    }
+
+    /// Id for a new value load; only the response to the latest one is
+    /// shown.
+    fn next_value_request(&mut self) -> u64 {
+        self.value_request_id += 1;
+        self.value_request_id
+    }
+
+    /// Load `key` into the value pane.
+    fn request_value(&mut self, key: String) {
+        let request_id = self.next_value_request();
+        let _ = self
+            .redis_tx
+            .try_send(RedisCommand::GetValue { request_id, key });
+    }
+
+    /// Reset the value pane for a new tree selection. Returns the key
+    /// whose value should be loaded, if any.
+    fn reset_for_selection(&mut self) -> Option<String> {
+        // A pinned value pane does not follow the tree selection
+        if self.pinned_key.is_some() {
+            return None;
+        }
+        if std::mem::take(&mut self.base64_decoded) {
+            self.render_string_value();
+        }
+        self.element_index = 0;
+        self.value_page = None;
+        self.pending_element = None;
+        // A folder shows its counts rather than the last key's value
+        self.folder_stats = self.selected_folder_stats();
+        if self.folder_stats.is_some() {
+            self.clear_value();
+            return None;
+        }
+        self.tree_state.selected_key().map(str::to_string)
+    }
+
+    /// Select `key` and load its value. A key that was not scanned (outside
+    /// the SCAN pattern, or in an unloaded folder) is looked up on the
+    /// server and added to the tree if it exists.
+    async fn go_to_key(&mut self, key: String) -> Result<()> {
+        if self.reveal_key(&key) {
+            return self.load_selected_value().await;
+        }
+        if self.loaded_keys.iter().any(|(k, _)| *k == key) {
+            self.status_message = format!("{} is hidden by a filter", display_key(&key));
+            return Ok(());
+        }
+        self.status_message = format!("Looking up {}...", display_key(&key));
+        self.redis_tx.send(RedisCommand::LocateKey { key }).await?;
+        Ok(())
+    }
+
+    /// Expand the folders above `key` and select its row. Returns false if
+    /// the tree does not show it.
+    fn reveal_key(&mut self, key: &str) -> bool {
+        if !expand_to_key(&mut self.tree_nodes, key) {
+            return false;
+        }
+        self.tree_state.flatten(&self.tree_nodes);
+        let current = self.tree_state.list_state.selected().unwrap_or(0);
+        self.tree_state.reselect_key(key, current);
+        if self.tree_state.selected_key() != Some(key) {
+            return false;
+        }
+        self.focus = Focus::Tree;
+        true
+    }
+
+    /// Prefix and counts of the selected row if it is a folder without a
+    /// key of its own.
+    fn selected_folder_stats(&self) -> Option<(String, FolderStats)> {
+        let row = self
+            .tree_state
+            .list_state
+            .selected()
+            .and_then(|i| self.tree_state.flattened.get(i))?;
+        if !row.is_folder || row.full_key.is_some() {
+            return None;
+        }
+        let node = node_at_path(&self.tree_nodes, &row.node_index)?;
+        Some((row.prefix.clone()?, node.folder_stats()))
+    }
+
+    /// Recount the shown folder once more of its keys have loaded.
+    fn refresh_folder_stats(&mut self) {
+        if self.folder_stats.is_some() && self.pinned_key.is_none() {
+            self.folder_stats = self.selected_folder_stats();
+        }
+    }
+
+    /// Reload the displayed key, staying on the same page and element of a
+    /// paged collection.
+    async fn reload_displayed_value(&mut self) -> Result<()> {
+        let Some(key) = self.displayed_key().map(|s| s.to_string()) else {
+            return Ok(());
+        };
+        let request_id = self.next_value_request();
+        let cmd = match self.value_page {
+            Some(page) => {
+                self.pending_element = Some(page.offset + self.element_index);
+                RedisCommand::GetValuePage {
+                    request_id,
+                    key,
+                    offset: page.offset,
+                    limit: VALUE_PAGE_SIZE,
+                }
+            }
+            None => RedisCommand::GetValue { request_id, key },
+        };
+        self.redis_tx.send(cmd).await?;
+        Ok(())
+    }
+
+    async fn toggle_pin(&mut self) -> Result<()> {
+        if let Some(key) = self.pinned_key.take() {
+            self.status_message = format!("Unpinned {}", display_key(&key));
+            self.load_selected_value().await?;
+        } else if let Some(key) = self.tree_state.selected_key().map(|s| s.to_string()) {
+            self.status_message = format!("Pinned {}", display_key(&key));
+            self.pinned_key = Some(key);
+        }
+        Ok(())
+    }
+
+    fn check_protection(&self, key: &str) -> Option<&ProtectedNamespace> {
+        self.config
+            .ui
+            .protected_namespaces
+            .iter()
+            .find(|ns| ns.matches(key))
+    }
+
+    /// Whether `action` may write to `key` right away. For a protected key
+    /// this opens the Protection dialog instead, which re-runs `action`
+    /// once the warning is acknowledged or "yes" is typed.
+    fn allow_write(&mut self, key: &str, action: Action) -> bool {
+        if self.protection_ack.take().is_some_and(|k| k == key) {
+            return true;
+        }
+        self.open_protection(key, Some(action))
+    }
+
+    /// Send `cmd`, a write to `key`, unless the key is protected; then it
+    /// is held in `pending_action` until the Protection dialog is passed.
+    async fn send_protected(&mut self, key: &str, cmd: RedisCommand) -> Result<()> {
+        // Say the key is busy before asking to confirm a write to it
+        if self.refuse_busy_write(&cmd) {
+            return Ok(());
+        }
+        if self.check_protection(key).is_none() {
+            self.send_command(cmd).await?;
+        } else {
+            self.pending_action = Some(cmd);
+            self.open_protection(key, None);
+        }
+        Ok(())
+    }
+
+    /// Send `cmd` to the Redis task. A write is tracked until the task
+    /// reports it finished, and refused while an earlier write to one of
+    /// its keys is still in flight.
+    async fn send_command(&mut self, cmd: RedisCommand) -> Result<()> {
+        if self.refuse_busy_write(&cmd) {
+            return Ok(());
+        }
+        self.pending_writes.extend(cmd.written_keys());
+        self.redis_tx.send(cmd).await?;
+        Ok(())
+    }
+
+    /// Whether `cmd` writes to a key that already has a write in flight,
+    /// saying so in the status bar if it does.
+    fn refuse_busy_write(&mut self, cmd: &RedisCommand) -> bool {
+        let written = cmd.written_keys();
+        let Some(busy) = written.iter().find(|k| self.pending_writes.contains(k)) else {
+            return false;
+        };
+        self.status_message = format!(
+            "Still writing {}; try again once it finishes",
+            display_key(busy)
+        );
+        true
+    }
+
+    /// Open the Protection dialog if `key` is protected. Returns whether
+    /// the key is unprotected.
+    fn open_protection(&mut self, key: &str, resume: Option<Action>) -> bool {
+        let Some(ns) = self.check_protection(key) else {
+            return true;
+        };
+        self.current_dialog = Some(Dialog::Protection {
+            key: key.to_string(),
+            namespace: ns.prefix.clone(),
+            level: ns.level,
+            input: String::new(),
+            resume,
+        });
+        false
+    }
+
+    async fn handle_edit(&mut self) -> Result<()> {
+        if self.config.connection.readonly {
+            self.status_message = "Read-only mode".to_string();
+            return Ok(());
+        }
+
+        let Some(key) = self.displayed_key().map(|s| s.to_string()) else {
+            return Ok(());
+        };
+
+        if !self.allow_write(&key, Action::Edit) {
+            return Ok(());
+        }
+
+        if self.value_truncated.is_some() {
+            self.status_message =
+                "Only part of this value is loaded; press X to load it before editing".to_string();
+            return Ok(());
+        }
+
+        // Get current value
+        let index = self.element_index;
+        let offset = self.page_offset();
+        match &self.selected_value {
+            Some(RedisValue::String(current_value)) => {
+                let current_value = current_value.clone().into_bytes();
+                self.open_editor(key, None, current_value)?;
+            }
+            Some(RedisValue::Bytes(current_value)) => {
+                let current_value = current_value.clone();
+                self.open_editor(key, None, current_value)?;
+            }
+            Some(RedisValue::List(items)) => {
+                if let Some(item) = items.get(index).cloned() {
+                    let element = ValueElement::ListIndex(offset + index);
+                    self.open_editor(key, Some(element), item.into_bytes())?;
+                }
+            }
+            Some(RedisValue::ZSet(items)) => {
+                if let Some((member, score)) = items.get(index).cloned() {
+                    let element = ValueElement::ZSetMember(member);
+                    self.open_editor(key, Some(element), score.to_string().into_bytes())?;
+                }
+            }
+            Some(RedisValue::Hash(items)) if self.focus == Focus::Value => {
+                if let Some((field, _)) = items.get(index).cloned() {
+                    self.edit_hash_field(key, field)?;
+                }
+            }
+            Some(RedisValue::Hash(items)) => {
+                // Pick a field, then edit just that field's value
+                let fields = items.iter().map(|(f, _)| f.clone()).collect();
+                self.current_dialog = Some(Dialog::HashFieldPicker {
+                    key,
+                    fields,
+                    selected: 0,
+                });
+            }
+            _ => {
+                self.status_message =
+                    "Only string, list, zset and hash values can be edited".to_string();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn edit_hash_field(&mut self, key: String, field: String) -> Result<()> {
+        let Some(RedisValue::Hash(items)) = &self.selected_value else {
+            return Ok(());
+        };
+        let Some((_, current_value)) = items.iter().find(|(f, _)| *f == field) else {
+            return Ok(());
+        };
+        let current_value = current_value.clone().into_bytes();
+        self.open_editor(key, Some(ValueElement::HashField(field)), current_value)
+    }
+
+    /// Edit `current_value` in $EDITOR and show the diff preview for writing
+    /// it back to `key` (or to one `element` of `key`). A whole value that
+    /// is not UTF-8 after editing is written back byte for byte.
+    fn open_editor(
+        &mut self,
+        key: String,
+        element: Option<ValueElement>,
+        current_value: Vec<u8>,
+    ) -> Result<()> {
+        let editor = ExternalEditor::new(self.config.ui.editor.as_deref())?;
+        let name = match element {
+            Some(ValueElement::HashField(ref f)) => format!("{}.{}", key, f),
+            Some(ValueElement::ListIndex(i)) => format!("{}.{}", key, i),
+            Some(ValueElement::SetMember(ref m) | ValueElement::ZSetMember(ref m)) => {
+                format!("{}.{}", key, m)
+            }
+            None => key.clone(),
+        };
+        match editor.edit(&name, &current_value)? {
+            EditOutcome::Changed(new_value) => {
+                let new_str = String::from_utf8_lossy(&new_value).into_owned();
+                let raw = match std::str::from_utf8(&new_value) {
+                    Err(_) if element.is_none() => Some(new_value),
+                    _ => None,
+                };
+                self.current_dialog = Some(Dialog::diff_preview(
+                    key,
+                    element,
+                    String::from_utf8_lossy(&current_value).into_owned(),
+                    new_str,
+                    raw,
+                    false,
+                    self.wrap_enabled,
+                ));
+            }
+            EditOutcome::Unchanged => {
+                self.status_message = "No changes made".to_string();
+            }
+            EditOutcome::ExitedImmediately => {
+                self.status_message = format!(
+                    "'{}' exited immediately; GUI editors need a wait flag (code --wait, subl -w)",
+                    editor.program()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ask for a file whose contents replace the displayed string value.
+    fn handle_import(&mut self) {
+        if self.config.connection.readonly {
+            self.status_message = "Read-only mode".to_string();
+            return;
+        }
+        let Some(key) = self.displayed_key().map(|s| s.to_string()) else {
+            return;
+        };
+        if !matches!(
+            self.selected_value,
+            Some(RedisValue::String(_) | RedisValue::Bytes(_))
+        ) {
+            self.status_message = "Only string values can be imported".to_string();
+            return;
+        }
+        if !self.allow_write(&key, Action::Import) {
+            return;
+        }
+        self.current_dialog = Some(Dialog::Import {
+            key,
+            path_input: String::new(),
+            create: false,
+        });
+    }
+
+    /// Show the diff preview for writing a file's `bytes` to `key`. Binary
+    /// contents are summarized in the preview but written unchanged. With
+    /// `create`, `key` is a new key and the write fails if it exists.
+    fn preview_import(&mut self, key: String, bytes: Vec<u8>, create: bool) {
+        let old_value = match &self.selected_value {
+            _ if self.displayed_key() != Some(key.as_str()) => String::new(),
+            Some(RedisValue::String(s)) => s.clone(),
+            Some(RedisValue::Bytes(b)) => String::from_utf8_lossy(b).into_owned(),
+            _ => String::new(),
+        };
+        let new_value = match std::str::from_utf8(&bytes) {
+            Ok(s) => s.to_string(),
+            Err(_) => format!("({} bytes of binary data)", bytes.len()),
+        };
+        self.current_dialog = Some(Dialog::diff_preview(
+            key,
+            None,
+            old_value,
+            new_value,
+            Some(bytes),
+            create,
+            self.wrap_enabled,
+        ));
+    }
+
+    /// Ask to remove the highlighted list element or set member.
+    fn handle_remove_element(&mut self) {
+        if self.config.connection.readonly {
+            self.status_message = "Read-only mode".to_string();
+            return;
+        }
+        let Some(key) = self.displayed_key().map(|s| s.to_string()) else {
+            return;
+        };
+        if !self.allow_write(&key, Action::RemoveElement) {
+            return;
+        }
+
+        let index = self.element_index;
+        let element = match &self.selected_value {
+            Some(RedisValue::List(items)) if index < items.len() => {
+                ValueElement::ListIndex(self.page_offset() + index)
+            }
+            Some(RedisValue::Set(items)) => match items.get(index) {
+                Some(member) => ValueElement::SetMember(member.clone()),
+                None => return,
+            },
+            _ => {
+                self.status_message = "Only list and set elements can be removed".to_string();
+                return;
+            }
+        };
+        self.current_dialog = Some(Dialog::RemoveElement { key, element });
+    }
+
+    fn handle_set_ttl(&mut self) {
+        if self.config.connection.readonly {
+            self.status_message = "Read-only mode".to_string();
+            return;
+        }
+
+        let Some(key) = self.tree_state.selected_key().map(|s| s.to_string()) else {
+            return;
+        };
+
+        if !self.allow_write(&key, Action::SetTtl) {
+            return;
+        }
+
+        // The info bar TTL belongs to the displayed key, which may be pinned
+        let current = if self.displayed_key() == Some(key.as_str()) {
+            self.current_ttl().unwrap_or(-1)
+        } else {
+            -1
+        };
+        self.current_dialog = Some(Dialog::SetTtl {
+            key,
+            current,
+            input: String::new(),
+        });
+    }
+
+    fn handle_rename(&mut self) {
+        if self.config.connection.readonly {
+            self.status_message = "Read-only mode".to_string();
+            return;
+        }
+
+        let Some(key) = self.tree_state.selected_key().map(|s| s.to_string()) else {
+            return;
+        };
+
+        if !self.allow_write(&key, Action::Rename) {
+            return;
+        }
+
+        self.current_dialog = Some(Dialog::Rename {
+            new_key_input: escape_key(&key),
+            old_key: key,
+        });
+    }
+
+    /// Ask for the name of a copy of the selected key. Only the copy is
+    /// written, so the source's protection does not apply.
+    fn handle_copy_key(&mut self) {
+        if self.config.connection.readonly {
+            self.status_message = "Read-only mode".to_string();
+            return;
+        }
+
+        let Some(key) = self.tree_state.selected_key().map(|s| s.to_string()) else {
+            return;
+        };
+        self.current_dialog = Some(Dialog::CopyKey {
+            dest_input: escape_key(&key),
+            source: key,
+        });
+    }
+
+    /// Open the create-key dialog, prefilled with the selected folder's
+    /// prefix (or the selected key's parent prefix).
+    fn handle_create_key(&mut self) {
+        if self.config.connection.readonly {
+            self.status_message = "Read-only mode".to_string();
+            return;
+        }
+
+        let name = escape_key(self.tree_state.selected_path_prefix().unwrap_or_default());
+        self.current_dialog = Some(Dialog::CreateKey {
+            key_type: RedisType::String,
+            name,
+            value: String::new(),
+            field: CreateKeyField::Name,
+        });
+    }
+
+    async fn handle_delete(&mut self) -> Result<()> {
+        if self.config.connection.readonly {
+            self.status_message = "Read-only mode".to_string();
+            return Ok(());
+        }
+
+        let Some(key) = self.tree_state.selected_key().map(|s| s.to_string()) else {
+            // A folder: delete everything under it
+            if let Some(prefix) = self.tree_state.selected_path().map(|s| s.to_string()) {
+                self.handle_delete_prefix(prefix);
+            }
+            return Ok(());
+        };
+
+        if !self.allow_write(&key, Action::Delete) {
+            return Ok(());
+        }
+
+        // A folder that is also a key: this deletes the key alone
+        let message = if self.selected_folder_prefix().is_some() {
+            format!(
+                "Delete '{}'? The keys under it are kept; use \"{}\" for those.",
+                display_key(&key),
+                Action::DeleteFolder.label()
+            )
+        } else {
+            format!("Delete '{}'?", display_key(&key))
+        };
+        self.current_dialog = Some(Dialog::Confirm {
+            title: "Delete Key".to_string(),
+            message,
+            confirm_text: "yes".to_string(),
+            input: String::new(),
+        });
+        self.pending_action = Some(RedisCommand::DeleteKey { key });
+
+        Ok(())
+    }
+
+    /// Delete the selected folder and everything under it, including a key
+    /// named like the folder itself.
+    fn handle_delete_folder(&mut self) {
+        if self.config.connection.readonly {
+            self.status_message = "Read-only mode".to_string();
+            return;
+        }
+        match self.selected_folder_prefix() {
+            Some(prefix) => self.handle_delete_prefix(prefix),
+            None => self.status_message = "Select a folder to delete".to_string(),
+        }
+    }
+
+    /// Prefix of the selected row if it is a folder, whether or not it is
+    /// also a key.
+    fn selected_folder_prefix(&self) -> Option<String> {
+        let state = &self.tree_state;
+        let node = state.flattened.get(state.list_state.selected()?)?;
+        node.prefix.clone().filter(|_| node.is_folder)
+    }
+
+    /// `d` on a folder: confirm deleting every key under `prefix` by typing
+    /// their count, or "yes" when some subfolders are not loaded yet and the
+    /// count is unknown.
+    fn handle_delete_prefix(&mut self, prefix: String) {
+        // Any protected namespace overlapping the subtree guards it, the
+        // strictest first. A glob or regex can't be compared with the prefix
+        // and may match keys that are not loaded, so the server-side delete
+        // keeps every key it matches at `confirm` or `block`; at `warn` it
+        // still warns when it matches a loaded key under the folder.
+        let namespaces = &self.config.ui.protected_namespaces;
+        let keep: Vec<ProtectedNamespace> = namespaces
+            .iter()
+            .filter(|ns| ns.match_mode != MatchMode::Prefix && ns.level != ProtectionLevel::Warn)
+            .cloned()
+            .collect();
+        let overlapping: Vec<(&ProtectedNamespace, String)> = namespaces
+            .iter()
+            .filter_map(|ns| match ns.match_mode {
+                MatchMode::Prefix if prefix.starts_with(&ns.prefix) => Some((ns, prefix.clone())),
+                MatchMode::Prefix if ns.prefix.starts_with(&prefix) => {
+                    Some((ns, ns.prefix.clone()))
+                }
+                MatchMode::Prefix => None,
+                MatchMode::Glob | MatchMode::Regex if ns.level == ProtectionLevel::Warn => self
+                    .loaded_keys
+                    .iter()
+                    .find(|(k, _)| k.starts_with(&prefix) && ns.matches(k))
+                    .map(|(k, _)| (ns, k.clone())),
+                MatchMode::Glob | MatchMode::Regex => None,
+            })
+            .collect();
+        let guard = overlapping
+            .iter()
+            .find(|(ns, _)| ns.level == ProtectionLevel::Block)
+            .or(overlapping.first())
+            .map(|(_, guard)| guard.clone());
+        if let Some(guard) = guard {
+            if !self.allow_write(&guard, Action::Delete) {
+                return;
+            }
+        }
+
+        let loaded = self
+            .loaded_keys
+            .iter()
+            .filter(|(k, _)| k.starts_with(&prefix) && !keep.iter().any(|ns| ns.matches(k)))
+            .count();
+        // The loaded count is the server's only after a finished, unfiltered
+        // scan that reached every folder under the prefix
+        let complete = !self.scanning
+            && self.scan_pattern == "*"
+            && self.type_filter.is_none()
+            && !self
+                .unloaded_prefixes
+                .iter()
+                .any(|p| p.starts_with(&prefix));
+        let (mut message, confirm_text) = if complete {
+            (
+                format!("Delete all {} keys under '{}'?", loaded, prefix),
+                loaded.to_string(),
+            )
+        } else {
+            (
+                format!(
+                    "Delete all keys under '{}'? {} are loaded, but the total is unknown.",
+                    prefix, loaded
+                ),
+                "yes".to_string(),
+            )
+        };
+        if !keep.is_empty() {
+            let patterns: Vec<&str> = keep.iter().map(|ns| ns.prefix.as_str()).collect();
+            message.push_str(&format!(" Keys matching {} are kept.", patterns.join(", ")));
+        }
+        self.current_dialog = Some(Dialog::Confirm {
+            title: "Delete Folder".to_string(),
+            message,
+            confirm_text,
+            input: String::new(),
+        });
+        self.pending_action = Some(RedisCommand::DeletePrefix { prefix, keep });
+    }
+
+    /// `3dd`: delete the keys on `count` rows starting at the selection.
+    /// Folder rows without a key of their own are skipped.
+    async fn handle_delete_rows(&mut self, count: usize) -> Result<()> {
+        if self.config.connection.readonly {
+            self.status_message = "Read-only mode".to_string();
+            return Ok(());
+        }
+        let Some(start) = self.tree_state.list_state.selected() else {
+            return Ok(());
+        };
+        let keys: Vec<String> = self
+            .tree_state
+            .flattened
+            .iter()
+            .skip(start)
+            .take(count)
+            .filter_map(|node| node.full_key.clone())
+            .collect();
+        if keys.is_empty() {
+            self.status_message = "No keys on those rows".to_string();
+            return Ok(());
+        }
+        if count == 1 || (keys.len() == 1 && self.tree_state.selected_key() == Some(&keys[0])) {
+            return self.handle_delete().await;
+        }
+        // The Protection dialog resumes a single-key action, so protected
+        // keys are deleted one at a time
+        if let Some(key) = keys.iter().find(|k| self.check_protection(k).is_some()) {
+            self.status_message =
+                format!("'{}' is protected; delete it on its own", display_key(key));
+            return Ok(());
+        }
+
+        self.current_dialog = Some(Dialog::Confirm {
+            title: "Delete Keys".to_string(),
+            message: format!(
+                "Delete {} keys, '{}' to '{}'?",
+                keys.len(),
+                display_key(&keys[0]),
+                display_key(&keys[keys.len() - 1])
+            ),
+            confirm_text: "yes".to_string(),
+            input: String::new(),
+        });
+        self.pending_action = Some(RedisCommand::DeleteKeys { keys });
+
+        Ok(())
+    }
+}
+
+/// Connect using `config`, reporting each attempt and the outcome to the UI.
+/// Decides `use_unlink` on the first connect if the config left it open,
+/// and drops `touch_ttl` on a replica, which refuses the GETEX it sends.
+async fn connect_reporting(
+    config: &mut ConnectionConfig,
+    ui_tx: &mpsc::Sender<UiMessage>,
+) -> Option<RedisClient> {
+    let result = RedisClient::connect_with_retry(config, |attempt, max| {
+        let _ = ui_tx.try_send(UiMessage::Connecting { attempt, max });
+    })
+    .await;
+    match result {
+        Ok(mut client) => {
+            // Servers that refuse INFO are treated as masters
+            let role = client.server_role().await.unwrap_or(ServerRole::Master);
+            if config.use_unlink.is_none() {
+                // UNLINK arrived in 4.0; servers that hide INFO get it too,
+                // since it falls back to DEL where unknown
+                let version = client.server_version().await.ok();
+                config.use_unlink = Some(version.is_none_or(|v| v >= (4, 0)));
+            }
+            if role == ServerRole::Replica {
+                config.touch_ttl = None;
+            }
+            let _ = ui_tx.send(UiMessage::Connected(role)).await;
+            Some(client)
+        }
+        Err(e) => {
+            let _ = ui_tx
+                .send(UiMessage::ConnectionFailed(format!("{:#}", e)))
+                .await;
+            None
+        }
+    }
+}
+
+/// Run one command from the UI and send its result back.
+async fn run_command(
+    client: &mut RedisClient,
+    cmd: RedisCommand,
+    ui_tx: &mpsc::Sender<UiMessage>,
+    scans: &ScanContext,
+    config: &mut ConnectionConfig,
+) {
+    match cmd {
+        // Scans run beside the command loop so value loads and writes don't
+        // wait for them
+        RedisCommand::ScanKeys { scan_id, pattern } => {
+            let mut client = client.clone();
+            let scans = scans.clone();
+            let count = config.scan_count;
+            tokio::spawn(async move {
+                if stream_scan(&mut client, &scans, scan_id, &pattern, count, None).await {
+                    if let Ok(size) = client.dbsize().await {
+                        let _ = scans.ui_tx.send(UiMessage::DbSize(size)).await;
+                    }
+                }
+            });
+        }
+        RedisCommand::ScanPrefix { scan_id, prefix } => {
+            let mut client = client.clone();
+            let scans = scans.clone();
+            let count = config.scan_count;
+            let pattern = format!("{}*", glob_escape(&prefix));
+            tokio::spawn(async move {
+                stream_scan(&mut client, &scans, scan_id, &pattern, count, Some(prefix)).await;
+            });
+        }
+        RedisCommand::GetValue { request_id, key } => {
+            let max_size = (config.max_value_size > 0).then_some(config.max_value_size);
+            load_value(
+                client,
+                ui_tx,
+                request_id,
+                key,
+                0,
+                VALUE_PAGE_SIZE,
+                config.touch_ttl,
+                max_size,
+            )
+            .await;
+        }
+        RedisCommand::GetFullValue { request_id, key } => {
+            load_value(
+                client,
+                ui_tx,
+                request_id,
+                key,
+                0,
+                VALUE_PAGE_SIZE,
+                config.touch_ttl,
+                None,
+            )
+            .await;
+        }
+        RedisCommand::GetValuePage {
+            request_id,
+            key,
+            offset,
+            limit,
+        } => {
+            load_value(
+                client,
+                ui_tx,
+                request_id,
+                key,
+                offset,
+                limit,
+                config.touch_ttl,
+                None,
+            )
+            .await;
+        }
+        RedisCommand::SetValue { key, value } => {
+            let result = client.set_bytes(&key, &value).await;
+            send_write_result(ui_tx, key, result).await;
+        }
+        RedisCommand::CreateString { key, value } => {
+            let msg = match client.set_bytes_nx(&key, &value).await {
+                Ok(true) => UiMessage::WriteSuccess(key),
+                Ok(false) => UiMessage::Error(format!("Key {} already exists", display_key(&key))),
+                Err(e) => UiMessage::Error(error_message(&e)),
+            };
+            let _ = ui_tx.send(msg).await;
+        }
+        RedisCommand::SetHashField { key, field, value } => {
+            match client.hset(&key, &field, &value).await {
+                Ok(_) => {
+                    let _ = ui_tx.send(UiMessage::WriteSuccess(key)).await;
+                }
+                Err(e) => {
+                    let _ = ui_tx.send(UiMessage::Error(error_message(&e))).await;
+                }
+            }
+        }
+        RedisCommand::CopyKey {
+            source,
+            dest,
+            replace,
+        } => {
+            let msg = match client.copy_key(&source, &dest, replace).await {
+                Ok(()) => UiMessage::KeyCopied { source, dest },
+                Err(e) if is_busy_key(&e) => UiMessage::CopyTargetExists { source, dest },
+                Err(e) => UiMessage::Error(error_message(&e)),
+            };
+            let _ = ui_tx.send(msg).await;
+        }
+        RedisCommand::RenameKey { old, new, replace } => {
+            let msg = match client.rename(&old, &new, replace).await {
+                Ok(true) => UiMessage::RenameSuccess { old, new },
+                Ok(false) => UiMessage::RenameTargetExists { old, new },
+                Err(e) => UiMessage::Error(error_message(&e)),
+            };
+            let _ = ui_tx.send(msg).await;
+        }
+        RedisCommand::SetTtl { key, seconds } => {
+            let result = match seconds {
+                Some(secs) => client.set_ttl(&key, secs).await,
+                None => client.persist(&key).await,
+            };
+            match result {
+                Ok(_) => {
+                    let _ = ui_tx.send(UiMessage::WriteSuccess(key)).await;
+                }
+                Err(e) => {
+                    let _ = ui_tx.send(UiMessage::Error(error_message(&e))).await;
+                }
+            }
+        }
+        RedisCommand::SetListElement { key, index, value } => {
+            let result = client.lset(&key, index, &value).await;
+            send_write_result(ui_tx, key, result).await;
+        }
+        RedisCommand::RemoveListElement { key, index } => {
+            let result = client.lremove_index(&key, index).await;
+            send_write_result(ui_tx, key, result).await;
+        }
+        RedisCommand::RemoveSetMember { key, member } => {
+            let result = client.srem(&key, &member).await;
+            send_write_result(ui_tx, key, result).await;
+        }
+        RedisCommand::SetZSetScore { key, member, score } => {
+            let result = client.zadd(&key, &member, score).await;
+            send_write_result(ui_tx, key, result).await;
+        }
+        RedisCommand::CreateCollection { key, value } => {
+            // RPUSH, SADD, HSET and ZADD would add to a key that exists
+            // but is not loaded, so refuse it here
+            match client.exists(&key).await {
+                Ok(false) => {}
+                Ok(true) => {
+                    let msg = format!("Key {} already exists", display_key(&key));
+                    let _ = ui_tx.send(UiMessage::Error(msg)).await;
+                    return;
+                }
+                Err(e) => {
+                    let _ = ui_tx.send(UiMessage::Error(error_message(&e))).await;
+                    return;
+                }
+            }
+            let result = match &value {
+                RedisValue::List(items) => client.rpush(&key, items).await,
+                RedisValue::Set(members) => client.sadd(&key, members).await,
+                RedisValue::Hash(fields) => client.hset_multiple(&key, fields).await,
+                RedisValue::ZSet(members) => client.zadd_multiple(&key, members).await,
+                _ => Err(anyhow::anyhow!("Only collections are created this way")),
+            };
+            send_write_result(ui_tx, key, result).await;
+        }
+        RedisCommand::LocateKey { key } => {
+            let msg = match client.find_type(&key).await {
+                Ok(redis_type) => UiMessage::KeyLocated { key, redis_type },
+                Err(e) => UiMessage::Error(error_message(&e)),
+            };
+            let _ = ui_tx.send(msg).await;
+        }
+        RedisCommand::GetInfo => {
+            let msg = match client.info().await {
+                Ok(info) => UiMessage::ServerInfo(info),
+                Err(e) => UiMessage::Error(error_message(&e)),
+            };
+            let _ = ui_tx.send(msg).await;
+        }
+        RedisCommand::SelectDb { db } => match client.select_db(db).await {
+            Ok(_) => {
+                // Reconnects should land on the new database too
+                config.db = db;
+                let _ = ui_tx.send(UiMessage::DbSelected(db)).await;
+            }
+            Err(e) => {
+                let _ = ui_tx.send(UiMessage::Error(error_message(&e))).await;
+            }
+        },
+        RedisCommand::DeleteKey { key } => match client
+            .delete_keys(
+                std::slice::from_ref(&key),
+                config.use_unlink.unwrap_or(true),
+            )
+            .await
+        {
+            Ok(_) => {
+                let _ = ui_tx.send(UiMessage::DeleteSuccess(key)).await;
+            }
+            Err(e) => {
+                let _ = ui_tx.send(UiMessage::Error(error_message(&e))).await;
+            }
+        },
+        RedisCommand::DeleteKeys { keys } => {
+            let unlink = config.use_unlink.unwrap_or(true);
+            match client.delete_keys(&keys, unlink).await {
+                Ok(removed) => {
+                    let summary = format!("{} keys", removed);
+                    let _ = ui_tx.send(UiMessage::DeleteSuccess(summary)).await;
+                }
+                Err(e) => {
+                    let _ = ui_tx.send(UiMessage::DeleteFailed(error_message(&e))).await;
+                }
+            }
+        }
+        RedisCommand::DeletePrefix { prefix, keep } => {
+            let unlink = config.use_unlink.unwrap_or(true);
+            let protected = |key: &str| keep.iter().any(|ns| ns.matches(key));
+            match client
+                .delete_prefix(&prefix, config.scan_count, unlink, protected)
+                .await
+            {
+                Ok((removed, 0)) => {
+                    let summary = format!("{} keys under {}", removed, prefix);
+                    let _ = ui_tx.send(UiMessage::DeleteSuccess(summary)).await;
+                }
+                Ok((removed, kept)) => {
+                    let summary = format!(
+                        "{} keys under {} ({} protected keys kept)",
+                        removed, prefix, kept
+                    );
+                    let _ = ui_tx.send(UiMessage::DeleteSuccess(summary)).await;
+                }
+                Err(e) => {
+                    let _ = ui_tx.send(UiMessage::DeleteFailed(error_message(&e))).await;
+                }
+            }
+        }
+    }
+}
+
+/// Scan `pattern`, streaming each batch to the UI as soon as SCAN returns
+/// it. Types are fetched on a cloned connection in the background and
+/// follow as `TypesResolved`, so the tree does not wait on `TYPE` calls.
+/// `prefix` marks a lazy folder load rather than a full scan. Returns
+/// whether the scan ran to the end; a newer scan stops it before its next
+/// batch.
+async fn stream_scan(
+    client: &mut RedisClient,
+    scans: &ScanContext,
+    scan_id: u64,
+    pattern: &str,
+    count: usize,
+    prefix: Option<String>,
+) -> bool {
+    let ui_tx = &scans.ui_tx;
+    let mut scan = client.scan_keys_stream(pattern, count);
+    let mut total = 0;
+    loop {
+        if scans.current_scan.load(Ordering::Relaxed) != scan_id {
+            return false;
+        }
+        match scan.next_batch(client).await {
+            Ok(Some(keys)) => {
+                total += keys.len();
+                let batch = keys
+                    .iter()
+                    .map(|key| (key.clone(), RedisType::Unknown))
+                    .collect();
+                let msg = match &prefix {
+                    Some(prefix) => UiMessage::PrefixBatch {
+                        scan_id,
+                        prefix: prefix.clone(),
+                        batch,
+                    },
+                    None => UiMessage::KeysBatch { scan_id, batch },
+                };
+                let _ = ui_tx.send(msg).await;
+                let lookup = TypeLookup {
+                    client: client.clone(),
+                    scan_id,
+                    keys,
+                };
+                let _ = scans.type_tx.send(lookup).await;
+            }
+            Ok(None) => {
+                let msg = match prefix {
+                    Some(prefix) => UiMessage::PrefixScanComplete {
+                        scan_id,
+                        prefix,
+                        total,
+                    },
+                    None => UiMessage::ScanComplete { scan_id, total },
+                };
+                let _ = ui_tx.send(msg).await;
+                return true;
+            }
+            Err(e) => {
+                let _ = ui_tx.send(UiMessage::ScanFailed(error_message(&e))).await;
+                return false;
+            }
+        }
+    }
+}
+
+/// Where scan tasks report, and which scan is still wanted
+#[derive(Clone)]
+struct ScanContext {
+    ui_tx: mpsc::Sender<UiMessage>,
+    type_tx: mpsc::Sender<TypeLookup>,
+    current_scan: Arc<AtomicU64>,
+}
+
+/// A batch of scanned keys whose types are still to be fetched
+struct TypeLookup {
+    client: RedisClient,
+    scan_id: u64,
+    keys: Vec<String>,
+}
+
+/// Fetch the types of queued scan batches in order, sending each as one
+/// `TypesResolved` or `TypesFailed`. Batches of a scan other than
+/// `current_scan` are dropped unread.
+async fn run_type_lookups(
+    mut lookups: mpsc::Receiver<TypeLookup>,
+    current_scan: Arc<AtomicU64>,
+    ui_tx: mpsc::Sender<UiMessage>,
+) {
+    while let Some(TypeLookup {
+        mut client,
+        scan_id,
+        keys,
+    }) = lookups.recv().await
+    {
+        if scan_id != current_scan.load(Ordering::Relaxed) {
+            continue;
+        }
+        let msg = match client.get_types(&keys).await {
+            Ok(types) => UiMessage::TypesResolved {
+                scan_id,
+                types: keys.into_iter().zip(types).collect(),
+            },
+            Err(e) => UiMessage::TypesFailed {
+                scan_id,
+                keys,
+                error: error_message(&e),
+            },
+        };
+        let _ = ui_tx.send(msg).await;
+    }
+}
+
+/// Load a page of `key`'s value along with its TTL and type, and send it to
+/// the UI tagged with `request_id`. One `TYPE` lookup serves both the size
+/// check and the read, and the details after it are requested together.
+#[allow(clippy::too_many_arguments)]
+async fn load_value(
+    client: &mut RedisClient,
+    ui_tx: &mpsc::Sender<UiMessage>,
+    request_id: u64,
+    key: String,
+    offset: usize,
+    limit: usize,
+    touch_ttl: Option<u64>,
+    max_size: Option<usize>,
+) {
+    let redis_type = match client.get_type(&key).await {
+        Ok(redis_type) => redis_type,
+        Err(e) => {
+            let error = error_message(&e);
+            let _ = ui_tx
+                .send(UiMessage::ValueFailed {
+                    request_id,
+                    key,
+                    error,
+                })
+                .await;
+            return;
+        }
+    };
+    // Strings over `max_size` bytes load only their first `max_size` bytes
+    let oversized = match max_size {
+        Some(max) if redis_type == RedisType::String => client
+            .strlen(&key)
+            .await
+            .ok()
+            .filter(|&len| len > max)
+            .map(|len| (max, len)),
+        _ => None,
+    };
+    let value_result = match (oversized, touch_ttl) {
+        (Some((max, _)), touch) => {
+            if let Some(secs) = touch.and_then(|secs| i64::try_from(secs).ok()) {
+                let _ = client.set_ttl(&key, secs).await;
+            }
+            client
+                .get_string_prefix(&key, max)
+                .await
+                .map(|bytes| (RedisValue::from_bytes(bytes), None))
+        }
+        (None, Some(secs)) => {
+            client
+                .get_value_touch(&key, redis_type, GetExExpiry::Ex(secs), offset, limit)
+                .await
+        }
+        (None, None) => {
+            client
+                .get_value_page_as(&key, redis_type, offset, limit)
+                .await
+        }
+    };
+    if let Ok((RedisValue::None, _)) = value_result {
+        let _ = ui_tx.send(UiMessage::KeyVanished(key)).await;
+        return;
+    }
+    // Sent at once over the shared connection rather than one by one
+    let (mut memory_client, mut encoding_client) = (client.clone(), client.clone());
+    let (ttl_result, memory, encoding) = tokio::join!(
+        client.get_ttl(&key),
+        memory_client.memory_usage(&key),
+        encoding_client.object_encoding(&key),
+    );
+    // Older servers lack MEMORY USAGE; show "-" rather than failing the load
+    let memory = memory.ok().flatten();
+    let encoding = encoding.ok();
+
+    let msg = match (value_result, ttl_result) {
+        (Ok((value, page)), Ok(ttl)) => {
+            // The read re-checks a type that changed under it
+            let redis_type = value.redis_type().unwrap_or(redis_type);
+            let truncated = oversized.map(|(_, len)| len);
+            let size = match truncated {
+                Some(bytes) => Some(ValueSize { bytes, chars: None }),
+                None => value.size(),
+            };
+            UiMessage::ValueLoaded {
+                request_id,
+                key,
+                value,
+                ttl,
+                redis_type,
+                size,
+                page,
+                memory,
+                encoding,
+                truncated,
+            }
+        }
+        (Err(e), _) | (_, Err(e)) => UiMessage::ValueFailed {
+            request_id,
+            key,
+            error: error_message(&e),
+        },
+    };
+    let _ = ui_tx.send(msg).await;
+}
+
+/// Subscribe to `target` (a pattern if it holds glob characters) and
+/// forward messages to `messages_tx` until `stop` fires or is dropped.
+/// Messages the UI has not caught up with are dropped; errors go to `ui_tx`.
+async fn run_pubsub(
+    config: ConnectionConfig,
+    target: String,
+    mut stop: oneshot::Receiver<()>,
+    messages_tx: mpsc::Sender<(String, String)>,
+    ui_tx: mpsc::Sender<UiMessage>,
+) {
+    let is_pattern = target.contains(['*', '?', '[']);
+    let result: Result<()> = async {
+        let mut pubsub = RedisClient::open_pubsub(&config).await?;
+        if is_pattern {
+            pubsub.psubscribe(&target).await?;
+        } else {
+            pubsub.subscribe(&target).await?;
+        }
+        {
+            let mut messages = pubsub.on_message();
+            loop {
+                tokio::select! {
+                    _ = &mut stop => break,
+                    msg = messages.next() => {
+                        let Some(msg) = msg else { break };
+                        let payload = String::from_utf8_lossy(msg.get_payload_bytes()).into_owned();
+                        let channel = msg.get_channel_name().to_string();
+                        let _ = messages_tx.try_send((channel, payload));
+                    }
+                }
+            }
+        }
+        if is_pattern {
+            pubsub.punsubscribe(&target).await?;
+        } else {
+            pubsub.unsubscribe(&target).await?;
+        }
+        Ok(())
+    }
+    .await;
+    if let Err(e) = result {
+        let _ = ui_tx
+            .send(UiMessage::Error(format!("Pub/Sub {}: {}", target, e)))
+            .await;
+    }
+}
+
+/// Report the outcome of a write to `key` back to the UI.
+async fn send_write_result(ui_tx: &mpsc::Sender<UiMessage>, key: String, result: Result<()>) {
+    let msg = match result {
+        Ok(()) => UiMessage::WriteSuccess(key),
+        Err(e) => UiMessage::Error(error_message(&e)),
+    };
+    let _ = ui_tx.send(msg).await;
+}
+
+/// Expand a leading `~/` to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Apply j/k/Up/Down to a list selection. Returns whether the key was used.
+fn move_selection(selected: &mut usize, len: usize, code: KeyCode) -> bool {
+    match code {
+        KeyCode::Char('j') | KeyCode::Down => {
+            *selected = (*selected + 1).min(len.saturating_sub(1));
+            true
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            *selected = selected.saturating_sub(1);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Apply a typing key to a text input. Returns whether the key was used.
+fn edit_input(input: &mut String, code: KeyCode) -> bool {
+    match code {
+        KeyCode::Backspace => {
+            input.pop();
+            true
+        }
+        KeyCode::Char(c) => {
+            input.push(c);
+            true
+        }
+        _ => false,
     }
 }
@@ -0,0 +1,53 @@
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::io::Write;
+
+/// How copied text reached the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyMethod {
+    System,
+    Osc52,
+}
+
+/// The system clipboard, when one is reachable. It is kept open for the
+/// life of the app because on X11 the copied text is served by this
+/// process and disappears when the handle is dropped.
+pub struct Clipboard {
+    system: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    pub fn new() -> Self {
+        Self {
+            system: arboard::Clipboard::new().ok(),
+        }
+    }
+
+    /// Copy text to the system clipboard, falling back to OSC 52 on headless
+    /// or SSH sessions where there is no display server to talk to.
+    pub fn copy(&mut self, text: &str) -> Result<CopyMethod> {
+        if let Some(ref mut system) = self.system {
+            if system.set_text(text).is_ok() {
+                return Ok(CopyMethod::System);
+            }
+        }
+        copy_osc52(text)?;
+        Ok(CopyMethod::Osc52)
+    }
+}
+
+impl Default for Clipboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Copy text with the OSC 52 terminal escape, which works locally and over
+/// SSH without access to a display server.
+pub fn copy_osc52(text: &str) -> Result<()> {
+    let mut out = std::io::stdout();
+    write!(out, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    out.flush()?;
+    Ok(())
+}
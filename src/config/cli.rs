@@ -1,10 +1,12 @@
+use crate::export::ExportFormat;
+use crate::import::OnConflict;
 use clap::Parser;
 
 #[derive(Parser, Debug)]
 #[command(name = "redis-nav")]
 #[command(about = "Terminal UI for browsing and editing Redis databases")]
 pub struct Cli {
-    /// Redis URL (redis://host:port) or profile name
+    /// Redis URL (redis://host:port), comma-separated cluster seed URLs, or profile name
     #[arg(value_name = "CONNECTION")]
     pub connection: Option<String>,
 
@@ -16,18 +18,31 @@ pub struct Cli {
     #[arg(short, long, default_value = "6380")]
     pub port: u16,
 
+    /// Unix domain socket path (instead of host/port)
+    #[arg(short = 's', long, value_name = "PATH")]
+    pub socket: Option<std::path::PathBuf>,
+
+    /// ACL username to authenticate as (Redis 6+) [default: the default user]
+    #[arg(long)]
+    pub user: Option<String>,
+
     /// Redis password (or use REDIS_PASSWORD env)
     #[arg(short = 'a', long)]
     pub password: Option<String>,
 
-    /// Database number
-    #[arg(short = 'n', long, default_value = "0")]
-    pub db: u8,
+    /// Database number [default: the profile's, or 0]
+    #[arg(short = 'n', long)]
+    pub db: Option<u8>,
 
-    /// Key delimiter (can be specified multiple times)
-    #[arg(short, long, default_value = ":")]
+    /// Key delimiter, repeatable [default: the profile's or config's, or ':']
+    #[arg(short, long)]
     pub delimiter: Vec<char>,
 
+    /// Split the first tree level on the first delimiter, the second on the
+    /// second, and so on
+    #[arg(long)]
+    pub per_level: bool,
+
     /// Use named profile from config
     #[arg(long)]
     pub profile: Option<String>,
@@ -36,7 +51,81 @@ pub struct Cli {
     #[arg(long)]
     pub readonly: bool,
 
+    /// Connect to a Redis Cluster, using the connection URL as a seed node
+    #[arg(long)]
+    pub cluster: bool,
+
+    /// PEM CA certificate to verify the server with (rediss:// only)
+    #[arg(long, value_name = "FILE")]
+    pub tls_ca: Option<std::path::PathBuf>,
+
+    /// PEM client certificate for mutual TLS
+    #[arg(long, value_name = "FILE", requires = "tls_key")]
+    pub tls_cert: Option<std::path::PathBuf>,
+
+    /// PEM private key for --tls-cert
+    #[arg(long, value_name = "FILE", requires = "tls_cert")]
+    pub tls_key: Option<std::path::PathBuf>,
+
+    /// Skip TLS certificate and hostname verification
+    #[arg(long)]
+    pub tls_insecure: bool,
+
+    /// Extend string keys' TTL to SECONDS each time they are viewed (GETEX)
+    #[arg(long, value_name = "SECONDS")]
+    pub touch_on_view: Option<u64>,
+
+    /// Rescan the keyspace every SECONDS, keeping expansion and selection (0: off)
+    #[arg(long, value_name = "SECONDS")]
+    pub refresh_interval: Option<u64>,
+
+    /// Keys requested per SCAN call; lower it to keep each call short on busy servers
+    #[arg(long, value_name = "N")]
+    pub scan_count: Option<usize>,
+
+    /// Load only the first BYTES of larger string values until asked (0 for
+    /// no limit) [default: 4194304]
+    #[arg(long, value_name = "BYTES")]
+    pub max_value_size: Option<usize>,
+
+    /// Connection attempts before giving up, with exponential backoff
+    #[arg(long, value_name = "N", default_value = "5")]
+    pub connect_attempts: u32,
+
+    /// Load folder contents on first expand instead of scanning every key upfront
+    #[arg(long)]
+    pub lazy: bool,
+
+    /// Show character count next to byte size for text values
+    #[arg(long)]
+    pub show_chars: bool,
+
     /// Config file path
     #[arg(long)]
     pub config: Option<std::path::PathBuf>,
+
+    /// Export keys matching PATTERN to stdout instead of starting the UI
+    #[arg(long, value_name = "PATTERN", num_args = 0..=1, default_missing_value = "*")]
+    pub export: Option<String>,
+
+    /// Write --export output to FILE instead of stdout
+    #[arg(short, long, value_name = "FILE", requires = "export")]
+    pub output: Option<std::path::PathBuf>,
+
+    /// Output format for --export [default: from the --output extension, or json]
+    #[arg(long, value_enum)]
+    pub export_format: Option<ExportFormat>,
+
+    /// Recreate the keys in a JSON or NDJSON export, with their TTLs, then
+    /// exit; with --readonly only report what would be written
+    #[arg(long, value_name = "FILE", conflicts_with = "export")]
+    pub import: Option<std::path::PathBuf>,
+
+    /// What --import does with keys that already exist
+    #[arg(long, value_enum, default_value_t = OnConflict::Skip, requires = "import")]
+    pub on_conflict: OnConflict,
+
+    /// Print the resolved settings as TOML and exit without connecting
+    #[arg(long)]
+    pub print_config: bool,
 }
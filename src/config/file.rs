@@ -1,7 +1,10 @@
-use super::ProtectedNamespace;
+use super::{IconSet, KeySpec, ProtectedNamespace, ThemeConfig};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming an explicit config file path.
+pub const CONFIG_ENV_VAR: &str = "REDIS_NAV_CONFIG";
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct ConfigFile {
@@ -9,14 +12,57 @@ pub struct ConfigFile {
     pub defaults: Defaults,
     #[serde(default)]
     pub profiles: HashMap<String, Profile>,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    /// Action name -> key(s), replacing that action's default keys
+    #[serde(default)]
+    pub keybindings: HashMap<String, KeySpec>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Defaults {
     #[serde(default)]
     pub delimiters: Vec<String>,
+    /// Split level N on `delimiters[N]` only
+    #[serde(default)]
+    pub per_level: bool,
     #[serde(default)]
     pub theme: Option<String>,
+    #[serde(default)]
+    pub show_char_count: bool,
+    #[serde(default)]
+    pub expand_all_depth: Option<usize>,
+    #[serde(default)]
+    pub lazy_load: bool,
+    #[serde(default)]
+    pub line_numbers: bool,
+    /// Decimal places for sorted set scores; unset shows them exactly
+    #[serde(default)]
+    pub zset_score_precision: Option<usize>,
+    /// `ascii` (the default) or `nerd` for Nerd Font glyphs
+    #[serde(default)]
+    pub icons: Option<IconSet>,
+    /// Editor command with arguments, e.g. `code --wait`; overrides $EDITOR
+    #[serde(default)]
+    pub editor: Option<String>,
+    /// Initial width of the tree pane, in percent of the window
+    #[serde(default)]
+    pub tree_width_percent: Option<u16>,
+    /// Rescan the keyspace every this many seconds; 0 disables it
+    #[serde(default)]
+    pub refresh_interval: Option<u64>,
+    /// Ask for confirmation before quitting
+    #[serde(default)]
+    pub confirm_quit: bool,
+    /// Most redraws per second while something is changing [default: 30]
+    #[serde(default)]
+    pub max_fps: Option<u32>,
+    /// Reopen the folders and key left selected last time [default: on]
+    #[serde(default)]
+    pub restore_session: Option<bool>,
+    /// Delete with UNLINK instead of DEL [default: on for Redis 4.0+]
+    #[serde(default)]
+    pub use_unlink: Option<bool>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -24,15 +70,42 @@ pub struct Profile {
     pub url: Option<String>,
     pub host: Option<String>,
     pub port: Option<u16>,
+    /// ACL username (Redis 6+); the password is then that user's
+    pub username: Option<String>,
     pub password: Option<String>,
     pub password_env: Option<String>,
+    /// Unix domain socket path, used instead of host/port
+    pub socket: Option<PathBuf>,
     pub db: Option<u8>,
     #[serde(default)]
     pub delimiters: Vec<String>,
     #[serde(default)]
+    pub per_level: bool,
+    #[serde(default)]
     pub readonly: bool,
     #[serde(default)]
     pub protected_namespaces: Vec<ProtectedNamespace>,
+    /// Connect as a Redis Cluster client, using `url` (or `nodes`) as seeds
+    #[serde(default)]
+    pub cluster: bool,
+    /// Cluster seed node URLs
+    #[serde(default)]
+    pub nodes: Vec<String>,
+    pub tls_ca: Option<PathBuf>,
+    pub tls_cert: Option<PathBuf>,
+    pub tls_key: Option<PathBuf>,
+    #[serde(default)]
+    pub tls_insecure: bool,
+    /// SCAN COUNT hint for this server (`--scan-count` overrides it)
+    pub scan_count: Option<usize>,
+    /// Size guard for string values (`--max-value-size` overrides it)
+    pub max_value_size: Option<usize>,
+    /// Jump host to reach the server through an `ssh -L` port forward
+    pub ssh_host: Option<String>,
+    pub ssh_user: Option<String>,
+    /// Private key for the jump host
+    pub ssh_key: Option<PathBuf>,
+    pub ssh_port: Option<u16>,
 }
 
 impl ConfigFile {
@@ -42,3 +115,19 @@ impl ConfigFile {
         Ok(config)
     }
 }
+
+/// Pick the config file location: `--config`, then `$REDIS_NAV_CONFIG`,
+/// then `<config_dir>/redis-nav/config.toml`. Returns `None` when none of
+/// these is available, rather than falling back to a CWD-relative path.
+pub fn resolve_config_path(
+    cli: Option<PathBuf>,
+    env: Option<PathBuf>,
+    config_dir: Option<PathBuf>,
+) -> Option<PathBuf> {
+    cli.or(env.filter(|p| !p.as_os_str().is_empty()))
+        .or_else(|| {
+            config_dir
+                .filter(|d| !d.as_os_str().is_empty())
+                .map(|d| d.join("redis-nav").join("config.toml"))
+        })
+}
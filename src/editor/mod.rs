@@ -1,26 +1,47 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
 use std::process::Command;
+use std::time::{Duration, Instant};
 
 use crate::format::{detect_format, DetectedFormat};
 
+/// An unchanged file returned faster than this suggests the editor forked
+/// into the background instead of waiting for the user.
+const IMMEDIATE_EXIT: Duration = Duration::from_secs(1);
+
+/// Result of an editing session.
+#[derive(Debug, PartialEq, Eq)]
+pub enum EditOutcome {
+    Changed(Vec<u8>),
+    Unchanged,
+    /// The editor returned at once without touching the file, as GUI
+    /// editors do unless told to wait (`code --wait`, `subl -w`)
+    ExitedImmediately,
+}
+
 pub struct ExternalEditor {
     temp_dir: PathBuf,
+    /// Editor binary followed by its arguments
+    command: Vec<String>,
 }
 
 impl ExternalEditor {
-    pub fn new() -> Result<Self> {
+    /// Use `configured` (the `[defaults] editor` entry) if set, otherwise
+    /// $EDITOR, $VISUAL, or the platform default.
+    pub fn new(configured: Option<&str>) -> Result<Self> {
         let temp_dir = std::env::temp_dir().join("redis-nav");
         fs::create_dir_all(&temp_dir)?;
-        Ok(Self { temp_dir })
+        let command = split_command(&editor_command(configured))?;
+        Ok(Self { temp_dir, command })
     }
 
-    pub fn edit(&self, key: &str, value: &[u8]) -> Result<Option<Vec<u8>>> {
+    pub fn edit(&self, key: &str, value: &[u8]) -> Result<EditOutcome> {
         let ext = match detect_format(value) {
             DetectedFormat::Json => ".json",
             DetectedFormat::Xml | DetectedFormat::Html => ".xml",
+            DetectedFormat::Yaml => ".yaml",
             _ => ".txt",
         };
 
@@ -35,22 +56,18 @@ impl ExternalEditor {
 
         let before_hash = hash_bytes(value);
 
-        // Get editor
-        let editor = std::env::var("EDITOR")
-            .or_else(|_| std::env::var("VISUAL"))
-            .unwrap_or_else(|_| {
-                if cfg!(windows) {
-                    "notepad".to_string()
-                } else {
-                    "vi".to_string()
-                }
-            });
-
         // Spawn editor
-        let status = Command::new(&editor)
+        let (program, args) = self
+            .command
+            .split_first()
+            .expect("split_command never returns an empty command");
+        let started = Instant::now();
+        let status = Command::new(program)
+            .args(args)
             .arg(&temp_path)
             .status()
-            .map_err(|e| anyhow!("Failed to launch editor '{}': {}", editor, e))?;
+            .map_err(|e| anyhow!("Failed to launch editor '{}': {}", program, e))?;
+        let elapsed = started.elapsed();
 
         if !status.success() {
             fs::remove_file(&temp_path).ok();
@@ -63,12 +80,101 @@ impl ExternalEditor {
 
         let after_hash = hash_bytes(&new_value);
 
-        if before_hash == after_hash {
-            Ok(None) // No changes
+        if before_hash != after_hash {
+            Ok(EditOutcome::Changed(new_value))
+        } else if elapsed < IMMEDIATE_EXIT {
+            Ok(EditOutcome::ExitedImmediately)
         } else {
-            Ok(Some(new_value))
+            Ok(EditOutcome::Unchanged)
         }
     }
+
+    /// The editor binary, for messages.
+    pub fn program(&self) -> &str {
+        &self.command[0]
+    }
+}
+
+fn editor_command(configured: Option<&str>) -> String {
+    configured
+        .map(str::to_string)
+        .or_else(|| std::env::var("EDITOR").ok())
+        .or_else(|| std::env::var("VISUAL").ok())
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        })
+}
+
+/// Split an editor command such as `code --wait` or
+/// `"/opt/My Editor/edit" -n` into words, shell style: whitespace separates
+/// words, quotes group them, and (outside Windows) backslash escapes the
+/// next character.
+pub fn split_command(command: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => bail!("unterminated ' in editor command '{}'", command),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') if !cfg!(windows) => match chars.next() {
+                            Some(c @ ('"' | '\\')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => bail!("unterminated \" in editor command '{}'", command),
+                        },
+                        Some(c) => word.push(c),
+                        None => bail!("unterminated \" in editor command '{}'", command),
+                    }
+                }
+            }
+            '\\' if !cfg!(windows) => {
+                in_word = true;
+                match chars.next() {
+                    Some(c) => word.push(c),
+                    None => bail!("trailing backslash in editor command '{}'", command),
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    if words.is_empty() {
+        bail!("editor command is empty");
+    }
+    Ok(words)
 }
 
 fn sanitize_filename(name: &str) -> String {
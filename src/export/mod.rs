@@ -0,0 +1,385 @@
+use crate::redis_client::key::{is_binary_key, key_to_bytes};
+use crate::redis_client::{error_message, RedisClient, RedisType, RedisValue};
+use anyhow::{bail, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use clap::ValueEnum;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ExportFormat {
+    /// A single JSON array of key objects
+    #[default]
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    /// `key,value` rows (string keys only)
+    Csv,
+    /// redis-cli commands that recreate the data
+    Commands,
+}
+
+impl ExportFormat {
+    /// The format a file name's extension implies: `.json`, `.ndjson` or
+    /// `.jsonl`, `.csv`, and `.redis` or `.txt` for commands.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "json" => Some(Self::Json),
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            "csv" => Some(Self::Csv),
+            "redis" | "txt" => Some(Self::Commands),
+            _ => None,
+        }
+    }
+}
+
+/// What [`export_keys`] wrote, and the keys it left out with the reason.
+#[derive(Debug, Default)]
+pub struct ExportSummary {
+    pub written: usize,
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Streams exported keys to a writer one entry at a time, so large
+/// keyspaces never need to be held in memory.
+pub struct ExportWriter<W: Write> {
+    out: W,
+    format: ExportFormat,
+    written: usize,
+}
+
+impl<W: Write> ExportWriter<W> {
+    pub fn new(out: W, format: ExportFormat) -> Result<Self> {
+        let mut writer = Self {
+            out,
+            format,
+            written: 0,
+        };
+        match format {
+            ExportFormat::Json => writeln!(writer.out, "[")?,
+            ExportFormat::Csv => writeln!(writer.out, "key,value")?,
+            ExportFormat::Ndjson | ExportFormat::Commands => {}
+        }
+        Ok(writer)
+    }
+
+    /// Write one key, with its remaining TTL in seconds if it expires.
+    /// Returns `false` if the value was skipped because the format cannot
+    /// represent it.
+    pub fn write_entry(&mut self, key: &str, value: &RedisValue, ttl: Option<i64>) -> Result<bool> {
+        match self.format {
+            ExportFormat::Json => {
+                let Some(obj) = entry_json(key, value, ttl) else {
+                    return Ok(false);
+                };
+                if self.written > 0 {
+                    writeln!(self.out, ",")?;
+                }
+                write!(self.out, "  {}", obj)?;
+            }
+            ExportFormat::Ndjson => {
+                let Some(obj) = entry_json(key, value, ttl) else {
+                    return Ok(false);
+                };
+                writeln!(self.out, "{}", obj)?;
+            }
+            ExportFormat::Csv => {
+                let RedisValue::String(s) = value else {
+                    return Ok(false);
+                };
+                if is_binary_key(key) {
+                    return Ok(false);
+                }
+                writeln!(self.out, "{},{}", csv_field(key), csv_field(s))?;
+            }
+            ExportFormat::Commands => {
+                let Some(cmd) = entry_command(key, value) else {
+                    return Ok(false);
+                };
+                writeln!(self.out, "{}", cmd)?;
+                if let Some(ttl) = ttl {
+                    writeln!(self.out, "EXPIRE {} {}", cli_key(key), ttl)?;
+                }
+            }
+        }
+        self.written += 1;
+        Ok(true)
+    }
+
+    /// Close the output and return the number of entries written.
+    pub fn finish(mut self) -> Result<usize> {
+        if self.format == ExportFormat::Json {
+            if self.written > 0 {
+                writeln!(self.out)?;
+            }
+            writeln!(self.out, "]")?;
+        }
+        self.out.flush()?;
+        Ok(self.written)
+    }
+}
+
+/// Scan `pattern`, `count` keys per SCAN call, and stream every matching
+/// key through an [`ExportWriter`] batch by batch. Strings longer than
+/// `max_size` bytes are skipped rather than read, as are values the format
+/// cannot hold and keys that fail to read; only a failed scan or write
+/// stops the export.
+pub async fn export_keys<W: Write>(
+    client: &mut RedisClient,
+    pattern: &str,
+    count: usize,
+    format: ExportFormat,
+    max_size: Option<usize>,
+    out: W,
+) -> Result<ExportSummary> {
+    let mut writer = ExportWriter::new(out, format)?;
+    let mut skipped = Vec::new();
+    let mut scan = client.scan_keys_stream(pattern, count);
+    while let Some(batch) = scan.next_batch(client).await? {
+        for key in batch {
+            let (value, ttl) = match read_entry(client, &key, max_size).await {
+                Ok(Some(entry)) => entry,
+                // Expired or deleted since the scan
+                Ok(None) => continue,
+                Err(e) => {
+                    skipped.push((key, error_message(&e)));
+                    continue;
+                }
+            };
+            if !writer.write_entry(&key, &value, ttl)? {
+                let reason = match format {
+                    ExportFormat::Csv if is_binary_key(&key) => {
+                        "key names that are not UTF-8 cannot be written as CSV".to_string()
+                    }
+                    ExportFormat::Csv => {
+                        format!("{} values cannot be written as CSV", type_name(&value))
+                    }
+                    _ => "an empty stream has no entries to XADD".to_string(),
+                };
+                skipped.push((key, reason));
+            }
+        }
+    }
+    Ok(ExportSummary {
+        written: writer.finish()?,
+        skipped,
+    })
+}
+
+/// Read one key's value and TTL for export, or `None` if it is gone.
+async fn read_entry(
+    client: &mut RedisClient,
+    key: &str,
+    max_size: Option<usize>,
+) -> Result<Option<(RedisValue, Option<i64>)>> {
+    if let Some(max) = max_size {
+        if client.get_type(key).await? == RedisType::String {
+            let len = client.strlen(key).await?;
+            if len > max {
+                bail!("{} bytes is over the size limit", len);
+            }
+        }
+    }
+    let value = client.get_value(key).await?;
+    if matches!(value, RedisValue::None) {
+        return Ok(None);
+    }
+    let ttl = client.get_ttl(key).await?;
+    Ok(Some((value, (ttl >= 0).then_some(ttl))))
+}
+
+fn type_name(value: &RedisValue) -> &'static str {
+    match value {
+        RedisValue::String(_) | RedisValue::Bytes(_) => "string",
+        RedisValue::List(_) => "list",
+        RedisValue::Set(_) => "set",
+        RedisValue::ZSet(_) => "zset",
+        RedisValue::Hash(_) => "hash",
+        RedisValue::Stream(_) => "stream",
+        RedisValue::None => "none",
+    }
+}
+
+fn entry_json(key: &str, value: &RedisValue, ttl: Option<i64>) -> Option<Value> {
+    let mut obj = entry_value_json(key, value)?;
+    if let Some(ttl) = ttl {
+        obj["ttl"] = json!(ttl);
+    }
+    Some(obj)
+}
+
+fn entry_value_json(key: &str, value: &RedisValue) -> Option<Value> {
+    let (type_name, value) = match value {
+        RedisValue::String(s) => ("string", json!(s)),
+        RedisValue::Bytes(b) => {
+            let mut obj = key_json(key);
+            obj["type"] = json!("string");
+            obj["encoding"] = json!("base64");
+            obj["value"] = json!(STANDARD.encode(b));
+            return Some(obj);
+        }
+        RedisValue::List(items) => ("list", json!(items)),
+        RedisValue::Set(items) => ("set", json!(items)),
+        RedisValue::ZSet(items) => (
+            "zset",
+            items
+                .iter()
+                .map(|(member, score)| json!([member, json_score(*score)]))
+                .collect(),
+        ),
+        RedisValue::Hash(items) => (
+            "hash",
+            Value::Object(items.iter().map(|(k, v)| (k.clone(), json!(v))).collect()),
+        ),
+        RedisValue::Stream(entries) => (
+            "stream",
+            entries
+                .iter()
+                .map(|(id, fields)| {
+                    let fields: serde_json::Map<String, Value> =
+                        fields.iter().map(|(f, v)| (f.clone(), json!(v))).collect();
+                    json!({ "id": id, "fields": fields })
+                })
+                .collect(),
+        ),
+        RedisValue::None => return None,
+    };
+    let mut obj = key_json(key);
+    obj["type"] = json!(type_name);
+    obj["value"] = value;
+    Some(obj)
+}
+
+/// A sorted set score as JSON. Infinite scores have no JSON number, so
+/// they are written as `"inf"` and `"-inf"`, the way Redis spells them.
+fn json_score(score: f64) -> Value {
+    match score {
+        f64::INFINITY => json!("inf"),
+        f64::NEG_INFINITY => json!("-inf"),
+        score => json!(score),
+    }
+}
+
+/// `{"key": ...}` for an entry. A key that is not UTF-8 is written as
+/// base64 of its bytes, marked with `"key_encoding": "base64"`.
+fn key_json(key: &str) -> Value {
+    if is_binary_key(key) {
+        json!({ "key": STANDARD.encode(key_to_bytes(key)), "key_encoding": "base64" })
+    } else {
+        json!({ "key": key })
+    }
+}
+
+/// `key` quoted for redis-cli, with bytes that are not UTF-8 as `\xNN`.
+fn cli_key(key: &str) -> String {
+    if is_binary_key(key) {
+        redis_cli_repr_bytes(&key_to_bytes(key))
+    } else {
+        redis_cli_repr(key)
+    }
+}
+
+fn entry_command(key: &str, value: &RedisValue) -> Option<String> {
+    let (cmd, args): (&str, Vec<String>) = match value {
+        // One XADD per entry, keeping the original IDs
+        RedisValue::Stream(entries) => {
+            let lines: Vec<String> = entries
+                .iter()
+                .map(|(id, fields)| {
+                    let args: Vec<String> = fields
+                        .iter()
+                        .flat_map(|(f, v)| [redis_cli_repr(f), redis_cli_repr(v)])
+                        .collect();
+                    format!("XADD {} {} {}", cli_key(key), id, args.join(" "))
+                })
+                .collect();
+            return (!lines.is_empty()).then(|| lines.join("\n"));
+        }
+        RedisValue::String(s) => ("SET", vec![redis_cli_repr(s)]),
+        RedisValue::Bytes(b) => ("SET", vec![redis_cli_repr_bytes(b)]),
+        RedisValue::List(items) => ("RPUSH", items.iter().map(|i| redis_cli_repr(i)).collect()),
+        RedisValue::Set(items) => ("SADD", items.iter().map(|i| redis_cli_repr(i)).collect()),
+        RedisValue::ZSet(items) => (
+            "ZADD",
+            items
+                .iter()
+                .flat_map(|(m, score)| [score.to_string(), redis_cli_repr(m)])
+                .collect(),
+        ),
+        RedisValue::Hash(items) => (
+            "HSET",
+            items
+                .iter()
+                .flat_map(|(f, v)| [redis_cli_repr(f), redis_cli_repr(v)])
+                .collect(),
+        ),
+        RedisValue::None => return None,
+    };
+    if args.is_empty() {
+        return None;
+    }
+    Some(format!("{} {} {}", cmd, cli_key(key), args.join(" ")))
+}
+
+/// Quote a string the way redis-cli accepts it on its command line:
+/// bare when safe, otherwise double-quoted with backslash escapes.
+pub fn redis_cli_repr(s: &str) -> String {
+    let needs_quotes = s.is_empty()
+        || s.chars()
+            .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '"' | '\'' | '\\'));
+    if !needs_quotes {
+        return s.to_string();
+    }
+
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let mut buf = [0u8; 4];
+                for b in c.encode_utf8(&mut buf).bytes() {
+                    out.push_str(&format!("\\x{:02x}", b));
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Like [`redis_cli_repr`], for values that are not valid UTF-8: bytes
+/// outside printable ASCII are written as `\xNN`.
+pub fn redis_cli_repr_bytes(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() + 2);
+    out.push('"');
+    for &b in bytes {
+        match b {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            b'\n' => out.push_str("\\n"),
+            b'\r' => out.push_str("\\r"),
+            b'\t' => out.push_str("\\t"),
+            b if b.is_ascii_graphic() || b == b' ' => out.push(b as char),
+            b => out.push_str(&format!("\\x{:02x}", b)),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
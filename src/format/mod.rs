@@ -1,18 +1,122 @@
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine;
 use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
+use time::format_description::well_known::Rfc3339;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DetectedFormat {
     Json,
     Xml,
     Html,
+    Yaml,
+    MessagePack,
     Binary,
     PlainText,
+    Compressed(Compression),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zlib,
+    Zstd,
+}
+
+impl Compression {
+    pub fn label(self) -> &'static str {
+        match self {
+            Compression::Gzip => "GZIP",
+            Compression::Zlib => "ZLIB",
+            Compression::Zstd => "ZSTD",
+        }
+    }
+}
+
+/// Upper bound on decompressed output, so a small compression bomb can't
+/// exhaust memory just by being viewed.
+const MAX_DECOMPRESSED_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Identify a compressed payload by its magic bytes.
+pub fn detect_compression(bytes: &[u8]) -> Option<Compression> {
+    match bytes {
+        [0x1f, 0x8b, ..] => Some(Compression::Gzip),
+        [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(Compression::Zstd),
+        // zlib: CM = 8 and the 16-bit header is a multiple of 31. 0x78 is
+        // also 'x', so require the payload not to look like text.
+        [cmf @ 0x78, flg, ..]
+            if (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0 && !is_valid_text(bytes) =>
+        {
+            Some(Compression::Zlib)
+        }
+        _ => None,
+    }
+}
+
+/// Decompress a gzip, zlib or zstd payload. Returns `None` if the bytes are
+/// not compressed or fail to decode.
+pub fn decompress(bytes: &[u8]) -> Option<Vec<u8>> {
+    use std::io::Read;
+
+    let reader: Box<dyn Read + '_> = match detect_compression(bytes)? {
+        Compression::Gzip => Box::new(flate2::read::GzDecoder::new(bytes)),
+        Compression::Zlib => Box::new(flate2::read::ZlibDecoder::new(bytes)),
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(bytes).ok()?),
+    };
+
+    let mut out = Vec::new();
+    reader
+        .take(MAX_DECOMPRESSED_SIZE)
+        .read_to_end(&mut out)
+        .ok()?;
+    Some(out)
+}
+
+/// Decode a base64 string in either the standard or URL-safe alphabet, with
+/// or without padding. Surrounding whitespace is ignored.
+pub fn decode_base64(bytes: &[u8]) -> Option<Vec<u8>> {
+    let text = std::str::from_utf8(bytes).ok()?.trim();
+    if text.is_empty() {
+        return None;
+    }
+    [STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD]
+        .iter()
+        .find_map(|engine| engine.decode(text).ok())
+}
+
+/// Unix seconds accepted as a timestamp: 2000-01-01 up to 2100-01-01.
+const EPOCH_SECONDS_RANGE: std::ops::Range<i64> = 946_684_800..4_102_444_800;
+
+/// Render a value holding only a Unix timestamp, in seconds or
+/// milliseconds, as an ISO-8601 UTC time. Anything else, including numbers
+/// outside 2000-2100, gives `None`.
+pub fn decode_timestamp(s: &str) -> Option<String> {
+    let s = s.trim();
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let n: i64 = s.parse().ok()?;
+    let nanos = if EPOCH_SECONDS_RANGE.contains(&n) {
+        i128::from(n) * 1_000_000_000
+    } else if EPOCH_SECONDS_RANGE.contains(&(n / 1000)) {
+        i128::from(n) * 1_000_000
+    } else {
+        return None;
+    };
+    let time = time::OffsetDateTime::from_unix_timestamp_nanos(nanos).ok()?;
+    time.format(&Rfc3339).ok()
 }
 
 pub fn detect_format(bytes: &[u8]) -> DetectedFormat {
+    if let Some(compression) = detect_compression(bytes) {
+        return DetectedFormat::Compressed(compression);
+    }
+
     // Check for binary content (non-UTF8 or control chars)
     if !is_valid_text(bytes) {
+        if decode_msgpack(bytes).is_some() {
+            return DetectedFormat::MessagePack;
+        }
         return DetectedFormat::Binary;
     }
 
@@ -22,12 +126,11 @@ pub fn detect_format(bytes: &[u8]) -> DetectedFormat {
     };
 
     // Try JSON
-    if (text.starts_with('{') && text.ends_with('}'))
-        || (text.starts_with('[') && text.ends_with(']'))
+    if ((text.starts_with('{') && text.ends_with('}'))
+        || (text.starts_with('[') && text.ends_with(']')))
+        && serde_json::from_str::<serde_json::Value>(text).is_ok()
     {
-        if serde_json::from_str::<serde_json::Value>(text).is_ok() {
-            return DetectedFormat::Json;
-        }
+        return DetectedFormat::Json;
     }
 
     // Check for XML/HTML
@@ -43,9 +146,109 @@ pub fn detect_format(bytes: &[u8]) -> DetectedFormat {
         return DetectedFormat::Xml;
     }
 
+    if looks_like_yaml(text) {
+        return DetectedFormat::Yaml;
+    }
+
     DetectedFormat::PlainText
 }
 
+/// Decode a MessagePack map or array into JSON. Almost any byte string
+/// starts with a valid MessagePack value, so only a top-level container
+/// that consumes the whole buffer counts.
+pub fn decode_msgpack(bytes: &[u8]) -> Option<serde_json::Value> {
+    if !matches!(bytes.first()?, 0x80..=0x9f | 0xdc..=0xdf) {
+        return None;
+    }
+    let mut rest = bytes;
+    let value = rmpv::decode::read_value(&mut rest).ok()?;
+    rest.is_empty().then(|| msgpack_to_json(value))
+}
+
+/// JSON has no binary, extension or non-string-key types: binary and
+/// extension payloads become hex strings, and map keys are rendered as
+/// JSON text when they are not strings.
+fn msgpack_to_json(value: rmpv::Value) -> serde_json::Value {
+    use rmpv::Value;
+    use serde_json::Value as Json;
+
+    let hex = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()
+    };
+    match value {
+        Value::Nil => Json::Null,
+        Value::Boolean(b) => Json::Bool(b),
+        Value::Integer(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => Json::from(i),
+            (None, Some(u)) => Json::from(u),
+            _ => Json::Null,
+        },
+        Value::F32(f) => Json::from(f64::from(f)),
+        Value::F64(f) => Json::from(f),
+        Value::String(s) => match s.into_str() {
+            Some(s) => Json::String(s),
+            None => Json::Null,
+        },
+        Value::Binary(b) => Json::String(hex(&b)),
+        Value::Array(items) => Json::Array(items.into_iter().map(msgpack_to_json).collect()),
+        Value::Map(entries) => Json::Object(
+            entries
+                .into_iter()
+                .map(|(k, v)| {
+                    let key = match msgpack_to_json(k) {
+                        Json::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    (key, msgpack_to_json(v))
+                })
+                .collect(),
+        ),
+        Value::Ext(kind, data) => serde_json::json!({ "ext": kind, "data": hex(&data) }),
+    }
+}
+
+/// YAML accepts almost any text as a scalar, so require a document marker or a
+/// block of `key:` lines, and then confirm with a real parse into a collection.
+fn looks_like_yaml(text: &str) -> bool {
+    if !text.starts_with("---") {
+        let mut key_lines = 0;
+        for line in text.lines() {
+            let trimmed = line.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') || line.len() != trimmed.len() {
+                continue;
+            }
+            if yaml_key(trimmed).is_some() {
+                key_lines += 1;
+            } else if !trimmed.starts_with("- ") {
+                return false;
+            }
+        }
+        if key_lines < 2 {
+            return false;
+        }
+    }
+
+    matches!(
+        serde_yaml::from_str::<serde_yaml::Value>(text),
+        Ok(serde_yaml::Value::Mapping(_)) | Ok(serde_yaml::Value::Sequence(_))
+    )
+}
+
+/// Split a `key: value` line into its key and the remainder after the colon.
+fn yaml_key(line: &str) -> Option<(&str, &str)> {
+    let colon = line.find(':')?;
+    let (key, rest) = line.split_at(colon);
+    let rest = &rest[1..];
+    let valid_key = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'));
+    (valid_key && (rest.is_empty() || rest.starts_with(' '))).then_some((key, rest))
+}
+
 fn is_valid_text(bytes: &[u8]) -> bool {
     // Check for common binary signatures
     if bytes.len() >= 4 {
@@ -112,7 +315,10 @@ pub fn format_as_hex(bytes: &[u8]) -> Vec<Line<'static>> {
             Span::styled(addr, Style::default().fg(Color::DarkGray)),
             Span::styled(hex_part, Style::default().fg(Color::Yellow)),
             Span::raw(padding),
-            Span::styled(format!(" |{}|", ascii_part), Style::default().fg(Color::Cyan)),
+            Span::styled(
+                format!(" |{}|", ascii_part),
+                Style::default().fg(Color::Cyan),
+            ),
         ]);
 
         lines.push(line);
@@ -139,12 +345,12 @@ pub fn highlight_json(json_str: &str) -> Vec<Line<'static>> {
 
 fn highlight_json_line(line: &str) -> Vec<Span<'static>> {
     let mut spans = Vec::new();
-    let mut chars = line.chars().peekable();
+    let chars = line.chars();
     let mut current = String::new();
     let mut in_string = false;
     let mut is_key = true;
 
-    while let Some(c) = chars.next() {
+    for c in chars {
         match c {
             '"' if !in_string => {
                 if !current.is_empty() {
@@ -156,7 +362,10 @@ fn highlight_json_line(line: &str) -> Vec<Span<'static>> {
             '"' if in_string => {
                 current.push(c);
                 let color = if is_key { Color::Blue } else { Color::Green };
-                spans.push(Span::styled(std::mem::take(&mut current), Style::default().fg(color)));
+                spans.push(Span::styled(
+                    std::mem::take(&mut current),
+                    Style::default().fg(color),
+                ));
                 in_string = false;
             }
             ':' if !in_string => {
@@ -177,11 +386,18 @@ fn highlight_json_line(line: &str) -> Vec<Span<'static>> {
                 if !current.is_empty() {
                     spans.push(Span::raw(std::mem::take(&mut current)));
                 }
-                spans.push(Span::styled(c.to_string(), Style::default().fg(Color::White)));
+                spans.push(Span::styled(
+                    c.to_string(),
+                    Style::default().fg(Color::White),
+                ));
                 is_key = c == '{';
             }
             _ if !in_string && (c.is_numeric() || c == '-' || c == '.') => {
-                if current.is_empty() || current.chars().all(|x| x.is_numeric() || x == '-' || x == '.') {
+                if current.is_empty()
+                    || current
+                        .chars()
+                        .all(|x| x.is_numeric() || x == '-' || x == '.')
+                {
                     current.push(c);
                 } else {
                     spans.push(Span::raw(std::mem::take(&mut current)));
@@ -199,7 +415,10 @@ fn highlight_json_line(line: &str) -> Vec<Span<'static>> {
             spans.push(Span::styled(current, Style::default().fg(Color::Magenta)));
         } else if current == "null" {
             spans.push(Span::styled(current, Style::default().fg(Color::DarkGray)));
-        } else if current.chars().all(|c| c.is_numeric() || c == '-' || c == '.' || c.is_whitespace()) {
+        } else if current
+            .chars()
+            .all(|c| c.is_numeric() || c == '-' || c == '.' || c.is_whitespace())
+        {
             // Check if it's a number (might have leading whitespace)
             let trimmed = current.trim();
             if !trimmed.is_empty() && trimmed.parse::<f64>().is_ok() {
@@ -207,7 +426,10 @@ fn highlight_json_line(line: &str) -> Vec<Span<'static>> {
                 if !leading.is_empty() {
                     spans.push(Span::raw(leading));
                 }
-                spans.push(Span::styled(trimmed.to_string(), Style::default().fg(Color::Yellow)));
+                spans.push(Span::styled(
+                    trimmed.to_string(),
+                    Style::default().fg(Color::Yellow),
+                ));
             } else {
                 spans.push(Span::raw(current));
             }
@@ -218,3 +440,237 @@ fn highlight_json_line(line: &str) -> Vec<Span<'static>> {
 
     spans
 }
+
+pub fn pretty_yaml(yaml_str: &str) -> anyhow::Result<String> {
+    let value: serde_yaml::Value = serde_yaml::from_str(yaml_str)?;
+    Ok(serde_yaml::to_string(&value)?)
+}
+
+pub fn highlight_yaml(yaml_str: &str) -> Vec<Line<'static>> {
+    yaml_str
+        .lines()
+        .map(|line| Line::from(highlight_yaml_line(line)))
+        .collect()
+}
+
+fn highlight_yaml_line(line: &str) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut rest = line.trim_start();
+    let indent = &line[..line.len() - rest.len()];
+    if !indent.is_empty() {
+        spans.push(Span::raw(indent.to_string()));
+    }
+
+    if rest.starts_with('#') || rest == "---" || rest == "..." {
+        spans.push(Span::styled(
+            rest.to_string(),
+            Style::default().fg(Color::DarkGray),
+        ));
+        return spans;
+    }
+
+    while let Some(item) = rest.strip_prefix("- ") {
+        spans.push(Span::styled("- ", Style::default().fg(Color::White)));
+        rest = item;
+    }
+
+    if let Some((key, value)) = yaml_key(rest) {
+        spans.push(Span::styled(
+            key.to_string(),
+            Style::default().fg(Color::Blue),
+        ));
+        spans.push(Span::raw(":"));
+        rest = value;
+        let value = rest.trim_start();
+        if value.len() != rest.len() {
+            spans.push(Span::raw(rest[..rest.len() - value.len()].to_string()));
+        }
+        rest = value;
+    }
+
+    if !rest.is_empty() {
+        spans.push(yaml_scalar_span(rest));
+    }
+
+    spans
+}
+
+fn yaml_scalar_span(value: &str) -> Span<'static> {
+    let color = match value {
+        "true" | "false" => Color::Magenta,
+        "null" | "~" => Color::DarkGray,
+        "|" | ">" | "|-" | ">-" => Color::White,
+        _ if value.parse::<f64>().is_ok() => Color::Yellow,
+        _ => Color::Green,
+    };
+    Span::styled(value.to_string(), Style::default().fg(color))
+}
+
+/// Re-indent an XML document, one element per line.
+/// Fails on malformed markup, including unclosed elements.
+pub fn pretty_xml(xml_str: &str) -> anyhow::Result<String> {
+    use quick_xml::events::Event;
+
+    let mut reader = quick_xml::Reader::from_str(xml_str);
+    reader.config_mut().trim_text(true);
+    let mut writer = quick_xml::Writer::new_with_indent(Vec::new(), b' ', 2);
+    let mut depth = 0usize;
+    loop {
+        let event = reader.read_event()?;
+        match &event {
+            Event::Eof => break,
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        writer.write_event(event)?;
+    }
+    if depth > 0 {
+        anyhow::bail!("unclosed element");
+    }
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
+/// Elements HTML never closes, like `<br>`.
+const HTML_VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Re-indent an HTML document like [`pretty_xml`], but the way browsers
+/// read it: void elements such as `<br>` need no closing tag, and unclosed
+/// or mismatched tags are kept as written. Fails only on markup the XML
+/// reader cannot tokenize.
+pub fn pretty_html(html: &str) -> anyhow::Result<String> {
+    use quick_xml::events::Event;
+
+    let is_void = |name: &str| {
+        HTML_VOID_ELEMENTS
+            .iter()
+            .any(|v| v.eq_ignore_ascii_case(name))
+    };
+    let mut reader = quick_xml::Reader::from_str(html);
+    let config = reader.config_mut();
+    config.trim_text(true);
+    config.check_end_names = false;
+    config.allow_unmatched_ends = true;
+    let mut writer = quick_xml::Writer::new_with_indent(Vec::new(), b' ', 2);
+    loop {
+        let event = match reader.read_event()? {
+            Event::Eof => break,
+            Event::Start(e) if is_void(e.name().into_inner()) => Event::Empty(e),
+            Event::End(e) if is_void(e.name().into_inner()) => continue,
+            event => event,
+        };
+        writer.write_event(event)?;
+    }
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
+/// What part of the markup a character of XML belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum XmlToken {
+    Text,
+    /// Inside a tag, between its name and attributes
+    Markup,
+    TagName,
+    AttrName,
+    /// A quoted attribute value and its quote character
+    AttrValue(char),
+    /// Comments, `<?...?>` declarations and `<!DOCTYPE>`
+    Comment,
+}
+
+fn xml_style(token: XmlToken) -> Style {
+    match token {
+        XmlToken::Text => Style::default(),
+        XmlToken::Markup => Style::default().fg(Color::White),
+        XmlToken::TagName => Style::default().fg(Color::Blue),
+        XmlToken::AttrName => Style::default().fg(Color::Cyan),
+        XmlToken::AttrValue(_) => Style::default().fg(Color::Green),
+        XmlToken::Comment => Style::default().fg(Color::DarkGray),
+    }
+}
+
+/// Color tag names, attribute names, attribute values and text. This only
+/// tokenizes, so malformed markup is still shown line for line.
+pub fn highlight_xml(xml_str: &str) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let mut token = XmlToken::Text;
+
+    let flush = |spans: &mut Vec<Span<'static>>, current: &mut String, token: XmlToken| {
+        if !current.is_empty() {
+            spans.push(Span::styled(std::mem::take(current), xml_style(token)));
+        }
+    };
+
+    for (i, c) in xml_str.char_indices() {
+        if c == '\n' {
+            flush(&mut spans, &mut current, token);
+            lines.push(Line::from(std::mem::take(&mut spans)));
+            continue;
+        }
+        match token {
+            XmlToken::Text if c == '<' => {
+                flush(&mut spans, &mut current, token);
+                if xml_str[i + 1..].starts_with(['!', '?']) {
+                    token = XmlToken::Comment;
+                    current.push(c);
+                } else {
+                    token = XmlToken::TagName;
+                    spans.push(Span::styled("<", xml_style(XmlToken::Markup)));
+                }
+            }
+            XmlToken::Text => current.push(c),
+            XmlToken::Comment => {
+                current.push(c);
+                let closed = if current.starts_with("<!--") {
+                    current.len() > 6 && current.ends_with("-->")
+                } else {
+                    c == '>'
+                };
+                if closed {
+                    flush(&mut spans, &mut current, token);
+                    token = XmlToken::Text;
+                }
+            }
+            XmlToken::TagName if c == '/' && current.is_empty() => {
+                spans.push(Span::styled("/", xml_style(XmlToken::Markup)));
+            }
+            XmlToken::TagName | XmlToken::AttrName | XmlToken::Markup if c == '>' => {
+                flush(&mut spans, &mut current, token);
+                spans.push(Span::styled(">", xml_style(XmlToken::Markup)));
+                token = XmlToken::Text;
+            }
+            XmlToken::TagName | XmlToken::AttrName if c.is_whitespace() || c == '/' || c == '=' => {
+                flush(&mut spans, &mut current, token);
+                token = XmlToken::Markup;
+                current.push(c);
+            }
+            XmlToken::Markup if c == '"' || c == '\'' => {
+                flush(&mut spans, &mut current, token);
+                token = XmlToken::AttrValue(c);
+                current.push(c);
+            }
+            XmlToken::Markup if !(c.is_whitespace() || c == '/' || c == '=') => {
+                flush(&mut spans, &mut current, token);
+                token = XmlToken::AttrName;
+                current.push(c);
+            }
+            XmlToken::AttrValue(quote) if c == quote => {
+                current.push(c);
+                flush(&mut spans, &mut current, token);
+                token = XmlToken::Markup;
+            }
+            _ => current.push(c),
+        }
+    }
+    flush(&mut spans, &mut current, token);
+    if !spans.is_empty() {
+        lines.push(Line::from(spans));
+    }
+
+    lines
+}
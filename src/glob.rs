@@ -0,0 +1,78 @@
+/// Redis-style glob match: `*` matches any run of characters, `?` any one,
+/// `[abc]`/`[a-z]`/`[^a]` a set, and `\` escapes the next character.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Pattern position after the last `*` and the text position it resumed
+    // at. Only the last star ever needs to take more, so a mismatch
+    // retries from there, keeping the match linear in practice rather than
+    // exponential in the number of stars.
+    let mut star = None;
+    while t < text.len() {
+        if pattern.get(p) == Some(&'*') {
+            p += 1;
+            star = Some((p, t));
+            continue;
+        }
+        if let Some(next) = match_one(&pattern, p, text[t]) {
+            p = next;
+            t += 1;
+            continue;
+        }
+        let Some((star_p, star_t)) = star else {
+            return false;
+        };
+        p = star_p;
+        t = star_t + 1;
+        star = Some((star_p, t));
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Match `c` against the pattern element at `p` (anything but `*`),
+/// returning the position after the element if it matched.
+fn match_one(pattern: &[char], p: usize, c: char) -> Option<usize> {
+    let (&first, rest) = pattern.get(p..)?.split_first()?;
+    match first {
+        '?' => Some(p + 1),
+        '[' => match match_class(rest, c) {
+            Some((true, after)) => Some(pattern.len() - after.len()),
+            Some((false, _)) => None,
+            // No closing bracket: a literal '['
+            None => (c == '[').then_some(p + 1),
+        },
+        '\\' if !rest.is_empty() => (rest[0] == c).then_some(p + 2),
+        _ => (first == c).then_some(p + 1),
+    }
+}
+
+/// Match `c` against the class following a `[`, returning whether it
+/// matched and the pattern after the closing `]`.
+fn match_class(class: &[char], c: char) -> Option<(bool, &[char])> {
+    let (negate, mut i) = match class.first() {
+        Some('^') => (true, 1),
+        _ => (false, 0),
+    };
+    let mut matched = false;
+    let mut first = true;
+    while i < class.len() {
+        match class[i] {
+            ']' if !first => return Some((matched != negate, &class[i + 1..])),
+            '\\' if i + 1 < class.len() => {
+                matched |= class[i + 1] == c;
+                i += 2;
+            }
+            lo if i + 2 < class.len() && class[i + 1] == '-' && class[i + 2] != ']' => {
+                matched |= (lo..=class[i + 2]).contains(&c);
+                i += 3;
+            }
+            other => {
+                matched |= other == c;
+                i += 1;
+            }
+        }
+        first = false;
+    }
+    None
+}
@@ -0,0 +1,208 @@
+use crate::config::{ProtectedNamespace, ProtectionLevel};
+use crate::redis_client::key::key_from_bytes;
+use crate::redis_client::{error_message, RedisClient, RedisValue};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use clap::ValueEnum;
+use serde_json::Value;
+
+/// What `--import` does with a key that already exists on the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OnConflict {
+    /// Keep the existing key
+    #[default]
+    Skip,
+    /// Replace it with the imported value
+    Overwrite,
+}
+
+/// One key read back from a JSON or NDJSON export.
+#[derive(Debug, Clone)]
+pub struct ImportEntry {
+    pub key: String,
+    pub value: RedisValue,
+    /// Seconds left before the key expired when it was exported
+    pub ttl: Option<u64>,
+}
+
+/// What importing a key does, or would do in a dry run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportAction {
+    Create,
+    Overwrite,
+    Skip(String),
+    /// The key could not be checked or written
+    Failed(String),
+}
+
+/// Read the entries of an export written with `--export-format json` (one
+/// array) or `ndjson` (one object per line).
+pub fn parse_export(input: &str) -> Result<Vec<ImportEntry>> {
+    if input.trim_start().starts_with('[') {
+        let entries: Vec<Value> = serde_json::from_str(input)?;
+        entries
+            .iter()
+            .enumerate()
+            .map(|(i, obj)| parse_entry(obj).with_context(|| format!("entry {}", i + 1)))
+            .collect()
+    } else {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|obj| parse_entry(&obj))
+                    .with_context(|| format!("line {}", i + 1))
+            })
+            .collect()
+    }
+}
+
+fn parse_entry(obj: &Value) -> Result<ImportEntry> {
+    let key = obj["key"]
+        .as_str()
+        .ok_or_else(|| anyhow!("missing \"key\""))?;
+    let key = if obj["key_encoding"] == "base64" {
+        key_from_bytes(&STANDARD.decode(key)?)
+    } else {
+        key.to_string()
+    };
+    let key = key.as_str();
+    let value = &obj["value"];
+    let strings = |value: &Value| -> Result<Vec<String>> {
+        value
+            .as_array()
+            .ok_or_else(|| anyhow!("{} is not an array", key))?
+            .iter()
+            .map(|item| json_str(item).map(str::to_string))
+            .collect()
+    };
+    let value = match obj["type"].as_str() {
+        Some("string") if obj["encoding"] == "base64" => {
+            RedisValue::from_bytes(STANDARD.decode(json_str(value)?)?)
+        }
+        Some("string") => RedisValue::String(json_str(value)?.to_string()),
+        Some("list") => RedisValue::List(strings(value)?),
+        Some("set") => RedisValue::Set(strings(value)?),
+        Some("zset") => RedisValue::ZSet(
+            value
+                .as_array()
+                .ok_or_else(|| anyhow!("{} is not an array", key))?
+                .iter()
+                .map(|pair| {
+                    let score =
+                        json_score(&pair[1]).ok_or_else(|| anyhow!("bad score in {}", key))?;
+                    Ok((json_str(&pair[0])?.to_string(), score))
+                })
+                .collect::<Result<_>>()?,
+        ),
+        Some("hash") => RedisValue::Hash(fields(value)?),
+        Some("stream") => RedisValue::Stream(
+            value
+                .as_array()
+                .ok_or_else(|| anyhow!("{} is not an array", key))?
+                .iter()
+                .map(|entry| {
+                    Ok((
+                        json_str(&entry["id"])?.to_string(),
+                        fields(&entry["fields"])?,
+                    ))
+                })
+                .collect::<Result<_>>()?,
+        ),
+        Some(other) => bail!("unknown type '{}' for {}", other, key),
+        None => bail!("missing \"type\" for {}", key),
+    };
+    let ttl = match &obj["ttl"] {
+        Value::Null => None,
+        ttl => Some(ttl.as_u64().ok_or_else(|| anyhow!("bad ttl for {}", key))?),
+    };
+    Ok(ImportEntry {
+        key: key.to_string(),
+        value,
+        ttl,
+    })
+}
+
+/// A sorted set score: a number, or `"inf"`/`"-inf"`, which JSON numbers
+/// cannot hold.
+fn json_score(value: &Value) -> Option<f64> {
+    match value.as_str() {
+        Some("inf" | "+inf") => Some(f64::INFINITY),
+        Some("-inf") => Some(f64::NEG_INFINITY),
+        Some(_) => None,
+        None => value.as_f64(),
+    }
+}
+
+fn json_str(value: &Value) -> Result<&str> {
+    value
+        .as_str()
+        .ok_or_else(|| anyhow!("expected a string, found {}", value))
+}
+
+fn fields(value: &Value) -> Result<Vec<(String, String)>> {
+    value
+        .as_object()
+        .ok_or_else(|| anyhow!("expected an object, found {}", value))?
+        .iter()
+        .map(|(field, v)| Ok((field.clone(), json_str(v)?.to_string())))
+        .collect()
+}
+
+/// Decide what to do with an imported key. Keys under a `confirm` or
+/// `block` namespace are skipped, as there is no one to type "yes";
+/// `warn` namespaces are written.
+pub fn plan_import(
+    exists: bool,
+    on_conflict: OnConflict,
+    protection: Option<&ProtectedNamespace>,
+) -> ImportAction {
+    if let Some(ns) = protection.filter(|ns| ns.level != ProtectionLevel::Warn) {
+        return ImportAction::Skip(format!("in protected namespace {}", ns.prefix));
+    }
+    match (exists, on_conflict) {
+        (false, _) => ImportAction::Create,
+        (true, OnConflict::Overwrite) => ImportAction::Overwrite,
+        (true, OnConflict::Skip) => ImportAction::Skip("already exists".to_string()),
+    }
+}
+
+/// Write `entries` to the server, or with `dry_run` only work out what
+/// would be written. Returns each key with what was done to it; a key that
+/// fails is reported as [`ImportAction::Failed`] and the rest still go.
+pub async fn import_entries(
+    client: &mut RedisClient,
+    entries: &[ImportEntry],
+    on_conflict: OnConflict,
+    protected: &[ProtectedNamespace],
+    dry_run: bool,
+) -> Vec<(String, ImportAction)> {
+    let mut outcomes = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let action = match client.exists(&entry.key).await {
+            Ok(exists) => {
+                let protection = protected.iter().find(|ns| ns.matches(&entry.key));
+                plan_import(exists, on_conflict, protection)
+            }
+            Err(e) => ImportAction::Failed(error_message(&e)),
+        };
+        let action = match action {
+            ImportAction::Create | ImportAction::Overwrite if !dry_run => {
+                match client
+                    .write_value(&entry.key, &entry.value, entry.ttl)
+                    .await
+                {
+                    Ok(()) => action,
+                    Err(e) => ImportAction::Failed(error_message(&e)),
+                }
+            }
+            action => action,
+        };
+        outcomes.push((entry.key.clone(), action));
+    }
+    outcomes
+}
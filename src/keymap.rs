@@ -0,0 +1,307 @@
+use crate::action::Action;
+use crate::config::KeySpec;
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Default bindings, reproducing the built-in keys. A `[keybindings]` entry
+/// replaces every default key of the action it names.
+const DEFAULT_BINDINGS: &[(Action, &[&str])] = &[
+    (Action::MoveDown, &["j", "down"]),
+    (Action::MoveUp, &["k", "up"]),
+    (Action::Expand, &["l", "right", "enter"]),
+    (Action::Collapse, &["h", "left"]),
+    (Action::Top, &["g", "0"]),
+    (Action::Bottom, &["G"]),
+    (Action::PageDown, &["ctrl+d"]),
+    (Action::PageUp, &["ctrl+u"]),
+    (Action::SwitchPane, &["tab"]),
+    (Action::Search, &["/"]),
+    (Action::SetPattern, &["f"]),
+    (Action::ScopePattern, &["*"]),
+    (Action::CycleTypeFilter, &["F"]),
+    (Action::FilterKeys, &["ctrl+f"]),
+    (Action::GoToKey, &["o"]),
+    (Action::ExpandAll, &["E"]),
+    (Action::CollapseAll, &["C"]),
+    (Action::ShrinkTree, &["<"]),
+    (Action::GrowTree, &[">"]),
+    (Action::Rescan, &["R"]),
+    (Action::OpenMenu, &["m"]),
+    (Action::CommandPalette, &[":", "ctrl+p"]),
+    (Action::SelectDb, &["s"]),
+    (Action::Subscribe, &["P"]),
+    (Action::ServerInfo, &["I"]),
+    (Action::CreateKey, &["a"]),
+    (Action::Edit, &["e"]),
+    (Action::Import, &["i"]),
+    (Action::Delete, &["d"]),
+    (Action::DeleteFolder, &["D"]),
+    (Action::Refresh, &["r"]),
+    (Action::TogglePin, &["p"]),
+    (Action::ToggleBase64, &["b"]),
+    (Action::ToggleLineNumbers, &["L"]),
+    (Action::ToggleWrap, &["w"]),
+    (Action::ToggleSort, &["S"]),
+    (Action::LoadFull, &["X"]),
+    (Action::CopyPath, &["y"]),
+    (Action::CopyValue, &["Y"]),
+    (Action::SetTtl, &["t"]),
+    (Action::Rename, &["n"]),
+    (Action::CopyKey, &["c"]),
+    (Action::Help, &["?"]),
+    (Action::Back, &["esc"]),
+    (Action::Quit, &["q", "ctrl+c"]),
+];
+
+/// A key plus modifiers, normalized so that parsed strings and incoming
+/// events compare equal: characters never carry SHIFT (`G` is already
+/// uppercase) and shift+tab is always `BackTab`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyCombo {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyCombo {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(event: &KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+
+    /// Parse strings like `j`, `G`, `ctrl+n`, `alt+enter`, `shift+tab`.
+    /// Modifier and named-key matching is case-insensitive; single
+    /// characters are taken literally.
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (mods, key) = if s == "+" {
+            ("", "+")
+        } else if let Some(prefix) = s.strip_suffix("++") {
+            (prefix, "+")
+        } else {
+            s.rsplit_once('+').unwrap_or(("", s))
+        };
+        if key.is_empty() {
+            bail!("invalid key '{}'", s);
+        }
+
+        let mut modifiers = KeyModifiers::NONE;
+        for part in mods.split('+').filter(|p| !p.is_empty()) {
+            modifiers |= match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                other => bail!("unknown modifier '{}' in key '{}'", other, s),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                if modifiers.contains(KeyModifiers::SHIFT) {
+                    KeyCode::Char(c.to_ascii_uppercase())
+                } else {
+                    KeyCode::Char(c)
+                }
+            }
+            _ => named_key(&key.to_ascii_lowercase())
+                .ok_or_else(|| anyhow!("unknown key '{}'", s))?,
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+fn named_key(name: &str) -> Option<KeyCode> {
+    let code = match name {
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        _ => {
+            let n: u8 = name.strip_prefix('f')?.parse().ok()?;
+            return (1..=12).contains(&n).then_some(KeyCode::F(n));
+        }
+    };
+    Some(code)
+}
+
+/// Maps key combos to actions.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<KeyCombo, Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut bindings = HashMap::new();
+        for (action, keys) in DEFAULT_BINDINGS {
+            for key in *keys {
+                let combo = KeyCombo::parse(key).expect("default keybinding parses");
+                bindings.insert(combo, *action);
+            }
+        }
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// The default map with `[keybindings]` entries applied. Fails on
+    /// unknown action names, unparseable keys, or one key bound to two
+    /// actions in the table.
+    pub fn with_overrides(overrides: &HashMap<String, KeySpec>) -> Result<Self> {
+        let mut keymap = Self::default();
+        let mut claimed: HashMap<KeyCombo, &str> = HashMap::new();
+
+        // Sorted so that errors are reported deterministically
+        let mut entries: Vec<_> = overrides.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (name, spec) in entries {
+            let action = Action::from_name(name).ok_or_else(|| {
+                anyhow!(
+                    "unknown action '{}' in [keybindings] (valid actions: {})",
+                    name,
+                    Action::ALL
+                        .iter()
+                        .map(|a| a.name())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })?;
+            keymap.bindings.retain(|_, a| *a != action);
+            for key in spec.keys() {
+                let combo =
+                    KeyCombo::parse(key).map_err(|e| anyhow!("[keybindings] {}: {}", name, e))?;
+                if let Some(other) = claimed.insert(combo, name) {
+                    bail!(
+                        "[keybindings] key '{}' is bound to both '{}' and '{}'",
+                        key,
+                        other,
+                        name
+                    );
+                }
+                keymap.bindings.insert(combo, action);
+            }
+        }
+        Ok(keymap)
+    }
+
+    pub fn action_for(&self, event: &KeyEvent) -> Option<Action> {
+        self.bindings.get(&KeyCombo::from_event(event)).copied()
+    }
+
+    /// The letter `event` stands for in a motion as [`parse_motion`] reads
+    /// it, from the action it is bound to: whatever keys `move_down` has
+    /// count as `j`, and likewise `k`, `G`, `g` and `d`. Unbound digits
+    /// are counts.
+    pub fn motion_char(&self, event: &KeyEvent) -> Option<char> {
+        let c = match self.action_for(event) {
+            Some(Action::MoveDown) => 'j',
+            Some(Action::MoveUp) => 'k',
+            Some(Action::Bottom) => 'G',
+            Some(Action::Top) => 'g',
+            Some(Action::Delete) => 'd',
+            Some(_) => return None,
+            None => match event.code {
+                KeyCode::Char(c @ '0'..='9') if event.modifiers.is_empty() => c,
+                _ => return None,
+            },
+        };
+        Some(c)
+    }
+}
+
+/// How long a partial motion like `g` or `5` waits for its next key.
+pub const MOTION_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Result of parsing the keys typed so far as a vim-style tree motion: an
+/// optional count followed by `j`, `k`, `G`, `gg` or `dd`, written with the
+/// letters of [`Keymap::motion_char`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    /// More keys are needed; `fallback` runs if none arrive in time
+    Pending { fallback: Option<Action> },
+    /// Run `action`, repeated or aimed at row `count` when one was given
+    Run {
+        action: Action,
+        count: Option<usize>,
+    },
+    /// Not a motion; the buffer is dropped and the last key handled alone
+    Invalid,
+}
+
+/// Parse the pending motion keys in `buffer`, e.g. `10j`, `gg`, `5G`, `3dd`.
+pub fn parse_motion(buffer: &str) -> Motion {
+    let keys = buffer.trim_start_matches(|c: char| c.is_ascii_digit());
+    let digits = &buffer[..buffer.len() - keys.len()];
+    if digits.starts_with('0') {
+        return Motion::Invalid;
+    }
+    let count = (!digits.is_empty()).then(|| digits.parse().unwrap_or(usize::MAX));
+
+    let action = match keys {
+        "" if count.is_some() => return Motion::Pending { fallback: None },
+        "g" | "d" => {
+            // A lone `g` or `d` keeps its single-key meaning
+            let single = if keys == "g" {
+                Action::Top
+            } else {
+                Action::Delete
+            };
+            return Motion::Pending {
+                fallback: count.is_none().then_some(single),
+            };
+        }
+        "j" => Action::MoveDown,
+        "k" => Action::MoveUp,
+        "gg" => Action::Top,
+        "G" => Action::Bottom,
+        "dd" => Action::Delete,
+        _ => return Motion::Invalid,
+    };
+    Motion::Run { action, count }
+}
+
+/// Row a counted motion lands on in a list of `len` rows: `count` rows
+/// up or down from `selected`, or row `count` (1-based) for `gg`/`G`.
+pub fn motion_target(
+    action: Action,
+    count: usize,
+    selected: Option<usize>,
+    len: usize,
+) -> Option<usize> {
+    let last = len.checked_sub(1)?;
+    let current = selected.unwrap_or(0);
+    let row = match action {
+        Action::MoveDown => current.saturating_add(count),
+        Action::MoveUp => current.saturating_sub(count),
+        Action::Top | Action::Bottom => count.saturating_sub(1),
+        _ => return None,
+    };
+    Some(row.min(last))
+}
@@ -1,7 +1,15 @@
+pub mod action;
 pub mod app;
+pub mod clipboard;
 pub mod config;
 pub mod editor;
+pub mod export;
 pub mod format;
+pub mod glob;
+pub mod import;
+pub mod keymap;
 pub mod redis_client;
+pub mod session;
 pub mod tree;
+pub mod tunnel;
 pub mod ui;
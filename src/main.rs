@@ -1,31 +1,56 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use clap::Parser;
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::execute;
 use redis_nav::app::App;
 use redis_nav::config::cli::Cli;
-use redis_nav::config::file::ConfigFile;
-use redis_nav::config::{AppConfig, ConnectionConfig, UiConfig};
+use redis_nav::config::file::{resolve_config_path, ConfigFile, Defaults, CONFIG_ENV_VAR};
+use redis_nav::config::{
+    clamp_tree_percent, cluster_seeds, share_credentials, tcp_url, unix_socket_url,
+    with_credentials, AppConfig, ConnectionConfig, SessionSettings, TlsOptions, UiConfig,
+    DEFAULT_EXPAND_ALL_DEPTH, DEFAULT_MAX_FPS, DEFAULT_MAX_VALUE_SIZE, DEFAULT_SCAN_COUNT,
+    DEFAULT_TREE_PERCENT,
+};
+use redis_nav::export::{export_keys, ExportFormat};
+use redis_nav::import::{import_entries, parse_export, ImportAction};
+use redis_nav::keymap::Keymap;
+use redis_nav::redis_client::key::display_key;
+use redis_nav::redis_client::RedisClient;
+use redis_nav::session::session_path;
+use redis_nav::tunnel::{local_url, tunnel_target, SshOptions, SshTunnel};
+use redis_nav::ui::theme::config_warnings;
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     // Load config file if it exists
-    let config_path = cli.config.clone().unwrap_or_else(|| {
-        dirs::config_dir()
-            .unwrap_or_default()
-            .join("redis-nav")
-            .join("config.toml")
-    });
-
-    let file_config = if config_path.exists() {
-        ConfigFile::load(&config_path).ok()
-    } else {
-        None
+    let config_path = resolve_config_path(
+        cli.config.clone(),
+        std::env::var_os(CONFIG_ENV_VAR).map(PathBuf::from),
+        dirs::config_dir(),
+    );
+    // Printed for headless runs, shown in the status line otherwise
+    let mut warnings = Vec::new();
+    if config_path.is_none() {
+        warnings.push(format!(
+            "could not determine config directory; set {} to use a config file",
+            CONFIG_ENV_VAR
+        ));
+    }
+
+    let file_config = match config_path {
+        Some(ref path) if path.exists() => Some(
+            ConfigFile::load(path).with_context(|| format!("cannot load {}", path.display()))?,
+        ),
+        _ => None,
     };
 
     // Build connection URL
     let url = if let Some(ref conn) = cli.connection {
-        if conn.starts_with("redis://") || conn.starts_with("rediss://") {
+        if conn.contains("://") {
             conn.clone()
         } else if let Some(ref fc) = file_config {
             // Try to use as profile name
@@ -54,61 +79,309 @@ async fn main() -> Result<()> {
             .clone()
             .or_else(|| std::env::var("REDIS_PASSWORD").ok());
 
-        if let Some(pass) = password {
-            format!("redis://:{}@{}:{}", pass, cli.host, cli.port)
+        if let Some(ref socket) = cli.socket {
+            unix_socket_url(socket, cli.user.as_deref(), password.as_deref())
         } else {
-            format!("redis://{}:{}", cli.host, cli.port)
+            tcp_url(
+                &cli.host,
+                cli.port,
+                cli.user.as_deref(),
+                password.as_deref(),
+            )
         }
     };
 
-    // Build delimiters
-    let delimiters = if !cli.delimiter.is_empty() {
-        cli.delimiter.clone()
-    } else if let Some(ref fc) = file_config {
-        fc.defaults
-            .delimiters
-            .iter()
-            .filter_map(|s| s.chars().next())
-            .collect()
-    } else {
-        vec![':']
+    // Name of the profile in use, for display
+    let profile_name = match cli.connection {
+        Some(ref conn) => file_config
+            .as_ref()
+            .filter(|fc| !conn.contains("://") && fc.profiles.contains_key(conn))
+            .map(|_| conn.clone()),
+        None => cli.profile.clone(),
     };
 
-    // Build protected namespaces
-    let protected_namespaces = if let Some(ref fc) = file_config {
-        if let Some(ref profile_name) = cli.profile {
-            fc.profiles
-                .get(profile_name)
-                .map(|p| p.protected_namespaces.clone())
-                .unwrap_or_default()
-        } else {
-            vec![]
+    // Cluster mode: a profile's seed nodes, several seed URLs, or --cluster
+    let profile = profile_name
+        .as_ref()
+        .and_then(|name| file_config.as_ref()?.profiles.get(name));
+    let cluster_nodes = match profile {
+        Some(p) if !p.nodes.is_empty() => {
+            let (username, password) = profile_credentials(p, &cli);
+            let seeds: Vec<String> = p
+                .nodes
+                .iter()
+                .map(|node| with_credentials(node, username, password.as_deref()))
+                .collect();
+            share_credentials(&seeds)
         }
-    } else {
-        vec![]
+        _ => cluster_seeds(&url, cli.cluster || profile.is_some_and(|p| p.cluster)),
+    };
+    let url = cluster_nodes.first().cloned().unwrap_or(url);
+
+    // TLS flags override the profile's settings
+    let tls = TlsOptions {
+        ca: cli
+            .tls_ca
+            .clone()
+            .or_else(|| profile.and_then(|p| p.tls_ca.clone())),
+        cert: cli
+            .tls_cert
+            .clone()
+            .or_else(|| profile.and_then(|p| p.tls_cert.clone())),
+        key: cli
+            .tls_key
+            .clone()
+            .or_else(|| profile.and_then(|p| p.tls_key.clone())),
+        insecure: cli.tls_insecure || profile.is_some_and(|p| p.tls_insecure),
+    };
+    if tls.insecure {
+        warnings.push("TLS certificate verification is disabled".to_string());
+    }
+
+    // Reach the server through a jump host: forward a local port to it and
+    // connect there instead. The tunnel closes when it is dropped on exit.
+    // --print-config shows the server itself and opens no tunnel.
+    let ssh = profile
+        .and_then(SshOptions::from_profile)
+        .filter(|_| !cli.print_config);
+    let (url, tunnelled_url, tunnel, tls) = match ssh {
+        Some(ssh) => {
+            if !cluster_nodes.is_empty() {
+                anyhow::bail!("SSH tunnels are not supported for cluster connections");
+            }
+            let (host, port) = tunnel_target(&url)?;
+            eprintln!("Opening SSH tunnel via {}...", ssh.destination());
+            let mut tunnel = SshTunnel::open(&ssh, &host, port)?;
+            // Through the tunnel redis would check the certificate against
+            // 127.0.0.1; a relay checks it against the server's own name and
+            // hands redis a plain connection. Unverified TLS needs no relay.
+            let relayed = url.starts_with("rediss://") && !tls.insecure;
+            if relayed {
+                tunnel.relay_tls(&host, &tls).await?;
+            }
+            let local = local_url(&url, tunnel.local_port(), relayed)?;
+            let tls = if relayed { TlsOptions::default() } else { tls };
+            (local, Some(url), Some(tunnel), tls)
+        }
+        None => (url, None, None, tls),
+    };
+
+    // CLI flags, then the profile, then [defaults]
+    let no_defaults = Defaults::default();
+    let defaults = file_config.as_ref().map_or(&no_defaults, |fc| &fc.defaults);
+    let session = SessionSettings::resolve(&cli, profile, defaults);
+
+    let protected_namespaces = profile
+        .map(|p| p.protected_namespaces.clone())
+        .unwrap_or_default();
+
+    // Touch-on-view writes on every read, so it is refused in read-only mode
+    if session.readonly && cli.touch_on_view.is_some() {
+        bail!("--touch-on-view cannot be used in read-only mode");
+    }
+    let touch_ttl = cli.touch_on_view;
+
+    // Theme: a named base from [defaults] plus [theme] color overrides
+    let mut theme = file_config
+        .as_ref()
+        .map(|fc| fc.theme.clone())
+        .unwrap_or_default();
+    theme.name = file_config
+        .as_ref()
+        .and_then(|fc| fc.defaults.theme.clone());
+    // Like the rest of the config file, a bad theme is an error
+    let problems = config_warnings(&theme);
+    if !problems.is_empty() {
+        bail!("invalid theme config: {}", problems.join("; "));
+    }
+
+    let keymap = match file_config.as_ref() {
+        Some(fc) => Keymap::with_overrides(&fc.keybindings)?,
+        None => Keymap::default(),
     };
 
     let config = AppConfig {
+        config_file: config_path.filter(|_| file_config.is_some()),
         connection: ConnectionConfig {
             url,
-            db: cli.db,
-            readonly: cli.readonly,
+            tunnelled_url,
+            db: session.db,
+            readonly: session.readonly,
+            profile: profile_name,
+            touch_ttl,
+            cluster_nodes,
+            tls,
+            scan_count: cli
+                .scan_count
+                .or_else(|| profile.and_then(|p| p.scan_count))
+                .unwrap_or(DEFAULT_SCAN_COUNT)
+                .max(1),
+            connect_attempts: cli.connect_attempts,
+            max_value_size: cli
+                .max_value_size
+                .or_else(|| profile.and_then(|p| p.max_value_size))
+                .unwrap_or(DEFAULT_MAX_VALUE_SIZE),
+            use_unlink: file_config.as_ref().and_then(|fc| fc.defaults.use_unlink),
         },
         ui: UiConfig {
-            delimiters,
+            theme,
+            delimiters: session.delimiters,
+            per_level_delimiters: session.per_level,
             protected_namespaces,
+            show_char_count: cli.show_chars
+                || file_config
+                    .as_ref()
+                    .is_some_and(|fc| fc.defaults.show_char_count),
+            expand_all_depth: file_config
+                .as_ref()
+                .and_then(|fc| fc.defaults.expand_all_depth)
+                .unwrap_or(DEFAULT_EXPAND_ALL_DEPTH),
+            lazy_load: cli.lazy || file_config.as_ref().is_some_and(|fc| fc.defaults.lazy_load),
+            line_numbers: file_config
+                .as_ref()
+                .is_some_and(|fc| fc.defaults.line_numbers),
+            zset_score_precision: file_config
+                .as_ref()
+                .and_then(|fc| fc.defaults.zset_score_precision),
+            icons: file_config
+                .as_ref()
+                .and_then(|fc| fc.defaults.icons)
+                .unwrap_or_default(),
+            editor: file_config
+                .as_ref()
+                .and_then(|fc| fc.defaults.editor.clone()),
+            tree_width_percent: clamp_tree_percent(
+                file_config
+                    .as_ref()
+                    .and_then(|fc| fc.defaults.tree_width_percent)
+                    .unwrap_or(DEFAULT_TREE_PERCENT),
+            ),
+            refresh_interval: cli
+                .refresh_interval
+                .or_else(|| file_config.as_ref()?.defaults.refresh_interval)
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs),
+            confirm_quit: file_config
+                .as_ref()
+                .is_some_and(|fc| fc.defaults.confirm_quit),
+            max_fps: file_config
+                .as_ref()
+                .and_then(|fc| fc.defaults.max_fps)
+                .unwrap_or(DEFAULT_MAX_FPS)
+                .max(1),
+            session_file: file_config
+                .as_ref()
+                .and_then(|fc| fc.defaults.restore_session)
+                .unwrap_or(true)
+                .then(|| session_path(dirs::config_dir()))
+                .flatten(),
+            keymap,
         },
     };
 
+    if cli.print_config || cli.import.is_some() || cli.export.is_some() {
+        for warning in &warnings {
+            eprintln!("warning: {}", warning);
+        }
+    }
+
+    if cli.print_config {
+        print!("{}", config.to_toml()?);
+        return Ok(());
+    }
+
+    // Headless import: read-only makes it a dry run
+    if let Some(ref path) = cli.import {
+        let input = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        let entries =
+            parse_export(&input).with_context(|| format!("cannot import {}", path.display()))?;
+        let mut client = RedisClient::connect_with(&config.connection).await?;
+        let dry_run = config.connection.readonly;
+        let protected = &config.ui.protected_namespaces;
+        let outcomes =
+            import_entries(&mut client, &entries, cli.on_conflict, protected, dry_run).await;
+        let (mut created, mut overwritten, mut skipped, mut failed) = (0, 0, 0, 0);
+        for (key, action) in &outcomes {
+            let key = display_key(key);
+            match action {
+                ImportAction::Create => {
+                    created += 1;
+                    if dry_run {
+                        eprintln!("would create {}", key);
+                    }
+                }
+                ImportAction::Overwrite => {
+                    overwritten += 1;
+                    if dry_run {
+                        eprintln!("would overwrite {}", key);
+                    }
+                }
+                ImportAction::Skip(reason) => {
+                    skipped += 1;
+                    eprintln!("warning: skipped {}: {}", key, reason);
+                }
+                ImportAction::Failed(reason) => {
+                    failed += 1;
+                    eprintln!("error: failed to import {}: {}", key, reason);
+                }
+            }
+        }
+        let verb = if dry_run { "Would import" } else { "Imported" };
+        eprintln!(
+            "{} {} keys ({} new, {} overwritten), skipped {}",
+            verb,
+            created + overwritten,
+            created,
+            overwritten,
+            skipped
+        );
+        if failed > 0 {
+            bail!("{} keys failed to import", failed);
+        }
+        return Ok(());
+    }
+
+    // Headless export skips the UI entirely
+    if let Some(ref pattern) = cli.export {
+        let format = cli
+            .export_format
+            .or_else(|| cli.output.as_deref().and_then(ExportFormat::from_path))
+            .unwrap_or_default();
+        let max_size = config.connection.max_value_size;
+        let max_size = (max_size > 0).then_some(max_size);
+        let mut client = RedisClient::connect_with(&config.connection).await?;
+        let scan_count = config.connection.scan_count;
+        let summary = match cli.output {
+            Some(ref path) => {
+                let file = std::fs::File::create(path)
+                    .with_context(|| format!("cannot create {}", path.display()))?;
+                let out = std::io::BufWriter::new(file);
+                export_keys(&mut client, pattern, scan_count, format, max_size, out).await?
+            }
+            None => {
+                let out = std::io::stdout().lock();
+                export_keys(&mut client, pattern, scan_count, format, max_size, out).await?
+            }
+        };
+        for (key, reason) in &summary.skipped {
+            eprintln!("warning: skipped {}: {}", display_key(key), reason);
+        }
+        eprintln!("Exported {} keys", summary.written);
+        return Ok(());
+    }
+
     // Initialize terminal
     let mut terminal = ratatui::init();
+    execute!(std::io::stdout(), EnableMouseCapture)?;
     terminal.clear()?;
 
     // Run app
-    let mut app = App::new(config).await?;
+    let mut app = App::new(config).await?.tunnel(tunnel).warnings(warnings);
     let result = app.run(&mut terminal).await;
 
     // Restore terminal
+    let _ = execute!(std::io::stdout(), DisableMouseCapture);
     ratatui::restore();
 
     result
@@ -124,7 +397,22 @@ fn build_url_from_profile(
 
     let host = profile.host.as_deref().unwrap_or(&cli.host);
     let port = profile.port.unwrap_or(cli.port);
+    let (username, password) = profile_credentials(profile, cli);
 
+    if let Some(socket) = cli.socket.as_ref().or(profile.socket.as_ref()) {
+        return Ok(unix_socket_url(socket, username, password.as_deref()));
+    }
+
+    Ok(tcp_url(host, port, username, password.as_deref()))
+}
+
+/// Username and password for a profile: `--user` over the profile's
+/// `username`, and the profile's `password` or `password_env` over
+/// `--password` and `REDIS_PASSWORD`.
+fn profile_credentials<'a>(
+    profile: &'a redis_nav::config::file::Profile,
+    cli: &'a Cli,
+) -> (Option<&'a str>, Option<String>) {
     let password = profile
         .password
         .clone()
@@ -136,10 +424,6 @@ fn build_url_from_profile(
         })
         .or_else(|| cli.password.clone())
         .or_else(|| std::env::var("REDIS_PASSWORD").ok());
-
-    if let Some(pass) = password {
-        Ok(format!("redis://:{}@{}:{}", pass, host, port))
-    } else {
-        Ok(format!("redis://{}:{}", host, port))
-    }
+    let username = cli.user.as_deref().or(profile.username.as_deref());
+    (username, password)
 }
@@ -0,0 +1,114 @@
+use std::fmt::Write;
+
+/// Redis keys are arbitrary bytes, but the tree, prompts and filters work on
+/// `String`. A key byte that is not part of valid UTF-8 is carried as the
+/// code point `BYTE_BASE + byte`, at the end of the plane 16 private-use
+/// area, so [`key_to_bytes`] gets the exact original bytes back. A key
+/// that really holds a character from that range has its UTF-8 bytes
+/// carried the same way, so the two can't be confused.
+const BYTE_BASE: u32 = 0x10FF00;
+
+/// The key named by raw `bytes`, with bytes that are not UTF-8 kept as
+/// stand-in characters.
+pub fn key_from_bytes(bytes: &[u8]) -> String {
+    let mut key = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            if raw_byte(c).is_some() {
+                key.extend(c.encode_utf8(&mut [0; 4]).bytes().map(byte_char));
+            } else {
+                key.push(c);
+            }
+        }
+        key.extend(chunk.invalid().iter().map(|&b| byte_char(b)));
+    }
+    key
+}
+
+/// The raw bytes of `key`, as sent to Redis.
+pub fn key_to_bytes(key: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(key.len());
+    for c in key.chars() {
+        match raw_byte(c) {
+            Some(b) => bytes.push(b),
+            None => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    bytes
+}
+
+/// `key` for display: raw bytes and control characters become `\xNN`.
+pub fn display_key(key: &str) -> String {
+    escape(key, false)
+}
+
+/// `key` as typed at a key prompt: like [`display_key`], with backslashes
+/// doubled so [`parse_key`] reads it back unchanged.
+pub fn escape_key(key: &str) -> String {
+    escape(key, true)
+}
+
+/// The key typed at a prompt: `\xNN` is the byte `NN` and `\\` a single
+/// backslash, so keys that are not UTF-8 can be typed. Any other backslash
+/// is kept as is.
+pub fn parse_key(input: &str) -> String {
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(pos) = rest.find('\\') {
+        bytes.extend_from_slice(&key_to_bytes(&rest[..pos]));
+        let after = &rest[pos + 1..];
+        if let Some(tail) = after.strip_prefix('\\') {
+            bytes.push(b'\\');
+            rest = tail;
+            continue;
+        }
+        let hex = after
+            .strip_prefix('x')
+            .and_then(|hex| hex.get(..2))
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()));
+        match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+            Some(b) => {
+                bytes.push(b);
+                rest = &after[3..];
+            }
+            None => {
+                bytes.push(b'\\');
+                rest = after;
+            }
+        }
+    }
+    bytes.extend_from_slice(&key_to_bytes(rest));
+    key_from_bytes(&bytes)
+}
+
+fn escape(key: &str, backslash: bool) -> String {
+    let mut shown = String::with_capacity(key.len());
+    for c in key.chars() {
+        match raw_byte(c) {
+            Some(b) => {
+                let _ = write!(shown, "\\x{:02x}", b);
+            }
+            None if c.is_ascii_control() => {
+                let _ = write!(shown, "\\x{:02x}", c as u32);
+            }
+            None if c == '\\' && backslash => shown.push_str("\\\\"),
+            None => shown.push(c),
+        }
+    }
+    shown
+}
+
+/// Whether `key` holds bytes that are not valid UTF-8.
+pub fn is_binary_key(key: &str) -> bool {
+    key.chars().any(|c| raw_byte(c).is_some())
+}
+
+fn byte_char(b: u8) -> char {
+    char::from_u32(BYTE_BASE + b as u32).expect("plane 16 code point")
+}
+
+fn raw_byte(c: char) -> Option<u8> {
+    (c as u32)
+        .checked_sub(BYTE_BASE)
+        .and_then(|offset| u8::try_from(offset).ok())
+}
@@ -1,22 +1,219 @@
+pub mod key;
+
+use crate::config::{ConnectionConfig, TlsOptions};
+use crate::format::{detect_format, DetectedFormat};
+use crate::redis_client::key::{display_key, key_from_bytes, key_to_bytes};
 use anyhow::Result;
-use redis::aio::MultiplexedConnection;
-use redis::{AsyncCommands, Client};
+use anyhow::{anyhow, bail, Context};
+use redis::aio::{ConnectionLike, MultiplexedConnection, PubSub};
+use redis::cluster::{ClusterClient, TlsMode};
+use redis::cluster_async::ClusterConnection;
+use redis::cluster_routing::{RoutingInfo, SingleNodeRoutingInfo};
+use redis::{
+    AsyncCommands, Client, ClientTlsConfig, Cmd, ConnectionAddr, IntoConnectionInfo, Pipeline,
+    RedisFuture, TlsCertificates, Value,
+};
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashSet};
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Maximum number of commands sent in one `TYPE` pipeline.
+const TYPE_PIPELINE_CHUNK: usize = 500;
 
+/// Number of collection elements loaded per page in the value pane.
+pub const VALUE_PAGE_SIZE: usize = 200;
+
+/// Placeholder written over a list element so `LREM` removes exactly that
+/// one. Random per call, so no element already in the list can match it.
+fn list_tombstone() -> String {
+    let nonce = RandomState::new().build_hasher().finish();
+    format!("__redis_nav_removed_{:016x}__", nonce)
+}
+
+/// Cloning shares the underlying connection, so background work can run
+/// alongside the main command loop.
+#[derive(Clone)]
 pub struct RedisClient {
-    connection: MultiplexedConnection,
+    connection: Connection,
+}
+
+/// A standalone or cluster connection. Commands go through
+/// [`ConnectionLike`], so callers need not care which one is in use.
+#[derive(Clone)]
+struct Connection {
+    kind: ConnectionKind,
+    /// Set once a command fails in a way that needs a new connection;
+    /// shared by every clone
+    lost: Arc<AtomicBool>,
+}
+
+#[derive(Clone)]
+enum ConnectionKind {
+    Single(MultiplexedConnection),
+    Cluster(ClusterConnection),
+}
+
+impl Connection {
+    fn new(kind: ConnectionKind) -> Self {
+        Self {
+            kind,
+            lost: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+/// Wrap a command future so that connection-level failures set `lost`.
+fn track_lost<'a, T: Send + 'a>(
+    lost: Arc<AtomicBool>,
+    fut: RedisFuture<'a, T>,
+) -> RedisFuture<'a, T> {
+    Box::pin(async move {
+        let result = fut.await;
+        if let Err(e) = &result {
+            if e.is_unrecoverable_error() || e.is_connection_dropped() {
+                lost.store(true, Ordering::Relaxed);
+            }
+        }
+        result
+    })
+}
+
+impl ConnectionLike for Connection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        let lost = self.lost.clone();
+        let fut = match &mut self.kind {
+            ConnectionKind::Single(conn) => conn.req_packed_command(cmd),
+            ConnectionKind::Cluster(conn) => conn.req_packed_command(cmd),
+        };
+        track_lost(lost, fut)
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        let lost = self.lost.clone();
+        let fut = match &mut self.kind {
+            ConnectionKind::Single(conn) => conn.req_packed_commands(cmd, offset, count),
+            ConnectionKind::Cluster(conn) => conn.req_packed_commands(cmd, offset, count),
+        };
+        track_lost(lost, fut)
+    }
+
+    fn get_db(&self) -> i64 {
+        match &self.kind {
+            ConnectionKind::Single(conn) => conn.get_db(),
+            ConnectionKind::Cluster(conn) => conn.get_db(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum RedisValue {
     String(String),
+    /// A string value that is not valid UTF-8
+    Bytes(Vec<u8>),
     List(Vec<String>),
     Set(Vec<String>),
     ZSet(Vec<(String, f64)>),
     Hash(Vec<(String, String)>),
-    Stream(String), // Simplified for now
+    /// Stream entries; a paged load holds the newest entries first
+    Stream(Vec<StreamEntry>),
+    /// The key no longer exists, e.g. it was deleted or expired after the
+    /// scan that listed it
     None,
 }
 
+/// A stream entry: its ID and its field/value pairs in order.
+pub type StreamEntry = (String, Vec<(String, String)>);
+
+/// Byte length of a value, plus its character count when it is text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueSize {
+    pub bytes: usize,
+    pub chars: Option<usize>,
+}
+
+impl RedisValue {
+    /// Wrap a raw string value, keeping it as text when it is valid UTF-8.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(s) => RedisValue::String(s),
+            Err(e) => RedisValue::Bytes(e.into_bytes()),
+        }
+    }
+
+    /// The type the value was read as, or `None` for a missing key.
+    pub fn redis_type(&self) -> Option<RedisType> {
+        Some(match self {
+            RedisValue::String(_) | RedisValue::Bytes(_) => RedisType::String,
+            RedisValue::List(_) => RedisType::List,
+            RedisValue::Set(_) => RedisType::Set,
+            RedisValue::ZSet(_) => RedisType::ZSet,
+            RedisValue::Hash(_) => RedisType::Hash,
+            RedisValue::Stream(_) => RedisType::Stream,
+            RedisValue::None => return None,
+        })
+    }
+
+    /// Number of elements in a collection value.
+    pub fn element_count(&self) -> Option<usize> {
+        match self {
+            RedisValue::List(items) | RedisValue::Set(items) => Some(items.len()),
+            RedisValue::ZSet(items) => Some(items.len()),
+            RedisValue::Hash(items) => Some(items.len()),
+            _ => None,
+        }
+    }
+
+    /// Sort set members and hash fields by name, and sorted set members by
+    /// member instead of score. Lists and streams keep their order.
+    pub fn sort_members(&mut self) {
+        match self {
+            RedisValue::Set(items) => items.sort_unstable(),
+            RedisValue::ZSet(items) => items.sort_by(|a, b| a.0.cmp(&b.0)),
+            RedisValue::Hash(items) => items.sort_by(|a, b| a.0.cmp(&b.0)),
+            _ => {}
+        }
+    }
+
+    pub fn size(&self) -> Option<ValueSize> {
+        match self {
+            RedisValue::String(s) => {
+                let chars = match detect_format(s.as_bytes()) {
+                    DetectedFormat::Binary | DetectedFormat::MessagePack => None,
+                    _ => Some(s.chars().count()),
+                };
+                Some(ValueSize {
+                    bytes: s.len(),
+                    chars,
+                })
+            }
+            RedisValue::Bytes(b) => Some(ValueSize {
+                bytes: b.len(),
+                chars: None,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Which window of a list, set, sorted set or stream a paged load returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValuePage {
+    /// Position of the first loaded element
+    pub offset: usize,
+    /// Number of elements loaded
+    pub len: usize,
+    /// Number of elements in the whole collection
+    pub total: usize,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RedisType {
     String,
@@ -28,45 +225,106 @@ pub enum RedisType {
     Unknown,
 }
 
-impl RedisClient {
-    pub async fn connect(url: &str) -> Result<Self> {
-        let client = Client::open(url)?;
-        let connection = client.get_multiplexed_async_connection().await?;
-        Ok(Self { connection })
-    }
+/// Replication role reported by `INFO replication`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerRole {
+    Master,
+    /// A read-only replica (`role:slave`)
+    Replica,
+}
 
-    pub async fn scan_keys(&mut self, pattern: &str, count: usize) -> Result<Vec<String>> {
-        let mut keys = Vec::new();
-        let mut cursor: u64 = 0;
+/// The role from the text of `INFO replication`, if it names one.
+pub fn parse_role(info: &str) -> Option<ServerRole> {
+    info.lines()
+        .find_map(|line| line.trim().strip_prefix("role:"))
+        .and_then(|role| match role {
+            "master" => Some(ServerRole::Master),
+            "slave" | "replica" => Some(ServerRole::Replica),
+            _ => None,
+        })
+}
 
-        loop {
-            let (new_cursor, batch): (u64, Vec<String>) = redis::cmd("SCAN")
-                .arg(cursor)
-                .arg("MATCH")
-                .arg(pattern)
-                .arg("COUNT")
-                .arg(count)
-                .query_async(&mut self.connection)
-                .await?;
+/// Major and minor server version from the text of `INFO server`.
+pub fn parse_version(info: &str) -> Option<(u32, u32)> {
+    let version = info
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("redis_version:"))?;
+    let mut parts = version.split('.').map(|p| p.parse::<u32>().ok());
+    Some((parts.next()??, parts.next().flatten().unwrap_or(0)))
+}
 
-            keys.extend(batch);
-            cursor = new_cursor;
+/// `INFO` split into sections (`Server`, `Memory`, ...), each mapping field
+/// names to values.
+pub type ServerInfo = BTreeMap<String, BTreeMap<String, String>>;
 
-            if cursor == 0 {
-                break;
-            }
+/// Sections and fields from the text of `INFO`. Fields before the first
+/// `# Section` header are kept under an empty section name.
+pub fn parse_info(info: &str) -> ServerInfo {
+    let mut sections = ServerInfo::new();
+    let mut section = String::new();
+    for line in info.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('#') {
+            section = name.trim().to_string();
+            sections.entry(section.clone()).or_default();
+        } else if let Some((field, value)) = line.split_once(':') {
+            sections
+                .entry(section.clone())
+                .or_default()
+                .insert(field.to_string(), value.to_string());
         }
+    }
+    sections
+}
 
-        Ok(keys)
+/// Share of key lookups that found their key, from `keyspace_hits` and
+/// `keyspace_misses`; `None` before any lookup.
+pub fn keyspace_hit_ratio(info: &ServerInfo) -> Option<f64> {
+    let stats = info.get("Stats")?;
+    let hits: f64 = stats.get("keyspace_hits")?.parse().ok()?;
+    let misses: f64 = stats.get("keyspace_misses")?.parse().ok()?;
+    (hits + misses > 0.0).then(|| hits / (hits + misses))
+}
+
+/// Expiry change applied by `GETEX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GetExExpiry {
+    /// Leave the TTL unchanged (plain `GETEX key`)
+    Keep,
+    /// Set the TTL to this many seconds (`EX`)
+    Ex(u64),
+    /// Remove the TTL (`PERSIST`)
+    Persist,
+}
+
+/// Build a `GETEX` command for `key` with the given expiry option.
+pub fn getex_cmd(key: &str, expiry: GetExExpiry) -> redis::Cmd {
+    let mut cmd = redis::cmd("GETEX");
+    cmd.arg(key_to_bytes(key));
+    match expiry {
+        GetExExpiry::Keep => {}
+        GetExExpiry::Ex(secs) => {
+            cmd.arg("EX").arg(secs);
+        }
+        GetExExpiry::Persist => {
+            cmd.arg("PERSIST");
+        }
     }
+    cmd
+}
 
-    pub async fn get_type(&mut self, key: &str) -> Result<RedisType> {
-        let type_str: String = redis::cmd("TYPE")
-            .arg(key)
-            .query_async(&mut self.connection)
-            .await?;
+impl RedisType {
+    pub const ALL: &'static [RedisType] = &[
+        RedisType::String,
+        RedisType::List,
+        RedisType::Set,
+        RedisType::ZSet,
+        RedisType::Hash,
+        RedisType::Stream,
+    ];
 
-        Ok(match type_str.as_str() {
+    /// Parse a `TYPE` reply.
+    pub fn parse(type_str: &str) -> Self {
+        match type_str {
             "string" => RedisType::String,
             "list" => RedisType::List,
             "set" => RedisType::Set,
@@ -74,49 +332,1084 @@ impl RedisClient {
             "hash" => RedisType::Hash,
             "stream" => RedisType::Stream,
             _ => RedisType::Unknown,
+        }
+    }
+
+    /// The name Redis uses for this type in `TYPE` replies.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RedisType::String => "string",
+            RedisType::List => "list",
+            RedisType::Set => "set",
+            RedisType::ZSet => "zset",
+            RedisType::Hash => "hash",
+            RedisType::Stream => "stream",
+            RedisType::Unknown => "unknown",
+        }
+    }
+}
+
+/// How many times a read that hit `WRONGTYPE` is retried after checking
+/// the key's type again.
+const WRONGTYPE_RETRIES: usize = 3;
+
+/// Whether `err` is a `WRONGTYPE` reply: the key changed type between the
+/// `TYPE` lookup and the command that read it.
+pub fn is_wrong_type(err: &redis::RedisError) -> bool {
+    err.code() == Some("WRONGTYPE")
+}
+
+/// Whether `err` rejects a command the server does not know, e.g. `UNLINK`
+/// before Redis 4.0.
+fn is_unknown_command(err: &redis::RedisError) -> bool {
+    err.code() == Some("ERR") && err.to_string().contains("unknown command")
+}
+
+/// Whether `err` is a `BUSYKEY` reply: `RESTORE` without `REPLACE` onto a
+/// key that exists.
+pub fn is_busy_key(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<redis::RedisError>()
+        .is_some_and(|e| e.code() == Some("BUSYKEY"))
+}
+
+fn is_wrong_type_error(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<redis::RedisError>()
+        .is_some_and(is_wrong_type)
+}
+
+/// Text for an error from a Redis command, for the status bar. ACL
+/// rejections (`NOPERM`) are spelled out, since a restricted user hits them
+/// on ordinary browsing commands.
+pub fn error_message(err: &anyhow::Error) -> String {
+    match err.downcast_ref::<redis::RedisError>() {
+        Some(e) if e.code() == Some("NOPERM") => format!(
+            "permission denied: {}",
+            e.detail()
+                .unwrap_or("the ACL user may not run this command")
+        ),
+        _ => err.to_string(),
+    }
+}
+
+/// Escape glob metacharacters so `s` matches literally in a SCAN MATCH
+/// pattern.
+pub fn glob_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Delay before retry number `attempt` (1-based) of a failed connect:
+/// 500ms, doubling each time, capped at 8s.
+pub fn backoff_delay(attempt: u32) -> Duration {
+    let ms = 500u64.saturating_mul(1 << attempt.saturating_sub(1).min(4));
+    Duration::from_millis(ms)
+}
+
+/// Build a client for `url`, with `db` (when non-zero) overriding the URL's
+/// database and the TLS options applied.
+fn open_client(url: &str, db: u8, tls: &TlsOptions) -> Result<Client> {
+    let mut info = url.into_connection_info()?;
+    if db != 0 {
+        info.redis.db = i64::from(db);
+    }
+    if let ConnectionAddr::TcpTls { insecure, .. } = &mut info.addr {
+        *insecure |= tls.insecure;
+    }
+    let client = match load_tls_certificates(tls)? {
+        Some(certs) => Client::build_with_tls(info, certs)?,
+        None => Client::open(info)?,
+    };
+    Ok(client)
+}
+
+impl RedisClient {
+    /// Connect to `url`. A non-zero `db` overrides any database given in the
+    /// URL path; `tls` only applies to `rediss://` URLs. Unix sockets are
+    /// given as `redis+unix:///path/to/redis.sock`.
+    pub async fn connect(url: &str, db: u8, tls: &TlsOptions) -> Result<Self> {
+        let client = open_client(url, db, tls)?;
+        let connection = match &client.get_connection_info().addr {
+            ConnectionAddr::Unix(path) => {
+                let path = path.clone();
+                client
+                    .get_multiplexed_async_connection()
+                    .await
+                    .with_context(|| format!("cannot connect to Unix socket {}", path.display()))?
+            }
+            _ => client.get_multiplexed_async_connection().await?,
+        };
+        Ok(Self {
+            connection: Connection::new(ConnectionKind::Single(connection)),
+        })
+    }
+
+    /// Connect to a Redis Cluster through any of the given seed nodes.
+    pub async fn connect_cluster(urls: &[String], tls: &TlsOptions) -> Result<Self> {
+        let mut builder = ClusterClient::builder(urls.to_vec());
+        if let Some(certs) = load_tls_certificates(tls)? {
+            builder = builder.certs(certs);
+        }
+        if tls.insecure {
+            builder = builder.tls(TlsMode::Insecure);
+        }
+        let client = builder.build()?;
+        let connection = client.get_async_connection().await?;
+        Ok(Self {
+            connection: Connection::new(ConnectionKind::Cluster(connection)),
         })
     }
 
+    /// Open a dedicated Pub/Sub connection. In cluster mode the first seed
+    /// node is used, since classic Pub/Sub messages reach every node.
+    pub async fn open_pubsub(config: &ConnectionConfig) -> Result<PubSub> {
+        let url = config.cluster_nodes.first().unwrap_or(&config.url);
+        let client = open_client(url, 0, &config.tls)?;
+        Ok(client.get_async_pubsub().await?)
+    }
+
+    /// Connect in standalone or cluster mode, as `config` asks.
+    pub async fn connect_with(config: &ConnectionConfig) -> Result<Self> {
+        if config.cluster_nodes.is_empty() {
+            Self::connect(&config.url, config.db, &config.tls).await
+        } else {
+            Self::connect_cluster(&config.cluster_nodes, &config.tls).await
+        }
+    }
+
+    /// Connect like [`connect_with`](Self::connect_with), retrying with
+    /// exponential backoff up to `config.connect_attempts` times.
+    /// `on_attempt(attempt, max)` is called before each try.
+    pub async fn connect_with_retry(
+        config: &ConnectionConfig,
+        mut on_attempt: impl FnMut(u32, u32),
+    ) -> Result<Self> {
+        let max = config.connect_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            on_attempt(attempt, max);
+            match Self::connect_with(config).await {
+                Ok(client) => return Ok(client),
+                Err(e) if attempt >= max => {
+                    return Err(e.context(format!("giving up after {} attempts", max)))
+                }
+                Err(_) => {
+                    tokio::time::sleep(backoff_delay(attempt)).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Whether a command has failed because the connection went away, so
+    /// that it must be replaced.
+    pub fn connection_lost(&self) -> bool {
+        self.connection.lost.load(Ordering::Relaxed)
+    }
+
+    pub fn is_cluster(&self) -> bool {
+        matches!(self.connection.kind, ConnectionKind::Cluster(_))
+    }
+
+    /// Switch the connection to another logical database.
+    pub async fn select_db(&mut self, db: u8) -> Result<()> {
+        if self.is_cluster() {
+            bail!("Redis Cluster only supports database 0");
+        }
+        let _: () = redis::cmd("SELECT")
+            .arg(db)
+            .query_async(&mut self.connection)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn scan_keys(&mut self, pattern: &str, count: usize) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut scan = self.scan_keys_stream(pattern, count);
+
+        while let Some(batch) = scan.next_batch(self).await? {
+            keys.extend(batch);
+        }
+
+        Ok(keys)
+    }
+
+    /// Start an incremental SCAN; pull batches with [`KeyScan::next_batch`]
+    /// to act on keys as they arrive instead of waiting for the full scan.
+    pub fn scan_keys_stream(&self, pattern: &str, count: usize) -> KeyScan {
+        KeyScan {
+            pattern: pattern.to_string(),
+            count,
+            cursor: 0,
+            nodes: None,
+            seen: HashSet::new(),
+            done: false,
+        }
+    }
+
+    /// Addresses of the cluster's master nodes, from `CLUSTER SLOTS`.
+    async fn cluster_masters(&mut self) -> Result<Vec<(String, u16)>> {
+        let slots: Vec<Vec<Value>> = redis::cmd("CLUSTER")
+            .arg("SLOTS")
+            .query_async(&mut self.connection)
+            .await?;
+
+        let mut masters = Vec::new();
+        for range in slots {
+            // [start, end, [host, port, id, ...], replicas...]
+            let Some(Value::Array(master)) = range.get(2) else {
+                continue;
+            };
+            if let (Some(host), Some(port)) = (master.first(), master.get(1)) {
+                let host: String = redis::from_redis_value(host)?;
+                let port: u16 = redis::from_redis_value(port)?;
+                if !masters.contains(&(host.clone(), port)) {
+                    masters.push((host, port));
+                }
+            }
+        }
+        Ok(masters)
+    }
+
+    pub async fn get_type(&mut self, key: &str) -> Result<RedisType> {
+        Ok(self.find_type(key).await?.unwrap_or(RedisType::Unknown))
+    }
+
+    /// Like [`get_type`](Self::get_type), but `None` if the key does not
+    /// exist, which `TYPE` reports as `none`.
+    pub async fn find_type(&mut self, key: &str) -> Result<Option<RedisType>> {
+        let type_str: String = redis::cmd("TYPE")
+            .arg(key_to_bytes(key))
+            .query_async(&mut self.connection)
+            .await?;
+
+        Ok((type_str != "none").then(|| RedisType::parse(&type_str)))
+    }
+
+    /// Look up the types of many keys, pipelining `TYPE` in chunks to avoid
+    /// one round-trip per key. Results are in the same order as `keys`.
+    pub async fn get_types(&mut self, keys: &[String]) -> Result<Vec<RedisType>> {
+        let mut types = Vec::with_capacity(keys.len());
+
+        // A cluster pipeline cannot span hash slots, so query keys concurrently
+        if let ConnectionKind::Cluster(conn) = &self.connection.kind {
+            let mut tasks = tokio::task::JoinSet::new();
+            for (i, key) in keys.iter().enumerate() {
+                let mut conn = conn.clone();
+                let key = key_to_bytes(key);
+                tasks.spawn(async move {
+                    let reply: redis::RedisResult<String> =
+                        redis::cmd("TYPE").arg(&key).query_async(&mut conn).await;
+                    (i, reply)
+                });
+            }
+            types.resize(keys.len(), RedisType::Unknown);
+            while let Some(joined) = tasks.join_next().await {
+                let (i, reply) = joined?;
+                types[i] = RedisType::parse(&reply?);
+            }
+            return Ok(types);
+        }
+
+        for chunk in keys.chunks(TYPE_PIPELINE_CHUNK) {
+            let mut pipe = redis::pipe();
+            for key in chunk {
+                pipe.cmd("TYPE").arg(key_to_bytes(key));
+            }
+            let replies: Vec<String> = pipe.query_async(&mut self.connection).await?;
+            types.extend(replies.iter().map(|t| RedisType::parse(t)));
+        }
+
+        Ok(types)
+    }
+
+    /// Read a whole value. Returns [`RedisValue::None`] if the key no longer
+    /// exists, and re-checks the type if it changed while being read.
     pub async fn get_value(&mut self, key: &str) -> Result<RedisValue> {
-        let key_type = self.get_type(key).await?;
+        let mut retries = 0;
+        loop {
+            match self.read_value(key).await {
+                Err(e) if retries < WRONGTYPE_RETRIES && is_wrong_type_error(&e) => retries += 1,
+                result => return result,
+            }
+        }
+    }
+
+    async fn read_value(&mut self, key: &str) -> Result<RedisValue> {
+        let key = &key_to_bytes(key);
+        let type_str: String = redis::cmd("TYPE")
+            .arg(key)
+            .query_async(&mut self.connection)
+            .await?;
 
-        match key_type {
+        let value = match RedisType::parse(&type_str) {
             RedisType::String => {
-                let val: String = self.connection.get(key).await?;
-                Ok(RedisValue::String(val))
+                let val: Option<Vec<u8>> = self.connection.get(key).await?;
+                return Ok(val.map_or(RedisValue::None, RedisValue::from_bytes));
             }
             RedisType::List => {
                 let val: Vec<String> = self.connection.lrange(key, 0, -1).await?;
-                Ok(RedisValue::List(val))
+                RedisValue::List(val)
             }
             RedisType::Set => {
                 let val: Vec<String> = self.connection.smembers(key).await?;
-                Ok(RedisValue::Set(val))
+                RedisValue::Set(val)
             }
             RedisType::ZSet => {
                 let val: Vec<(String, f64)> = self.connection.zrange_withscores(key, 0, -1).await?;
-                Ok(RedisValue::ZSet(val))
+                RedisValue::ZSet(val)
             }
             RedisType::Hash => {
                 let val: Vec<(String, String)> = self.connection.hgetall(key).await?;
-                Ok(RedisValue::Hash(val))
+                RedisValue::Hash(val)
+            }
+            RedisType::Stream => {
+                let val: Vec<StreamEntry> = redis::cmd("XRANGE")
+                    .arg(key)
+                    .arg("-")
+                    .arg("+")
+                    .query_async(&mut self.connection)
+                    .await?;
+                return Ok(RedisValue::Stream(val));
+            }
+            _ if type_str == "none" => RedisValue::None,
+            _ => bail!("cannot show values of type '{}'", type_str),
+        };
+        // Redis deletes emptied lists, sets and hashes, so an empty read
+        // means the key vanished after the TYPE lookup
+        Ok(match value.element_count() {
+            Some(0) => RedisValue::None,
+            _ => value,
+        })
+    }
+
+    /// Like [`get_value`](Self::get_value), but lists, sets, sorted sets,
+    /// hashes and streams only load `limit` elements starting at `offset`.
+    pub async fn get_value_page(
+        &mut self,
+        key: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(RedisValue, Option<ValuePage>)> {
+        let redis_type = self.get_type(key).await?;
+        self.get_value_page_as(key, redis_type, offset, limit).await
+    }
+
+    /// Like [`get_value_page`](Self::get_value_page), for a key whose type
+    /// the caller has already looked up, saving a `TYPE` round trip. The
+    /// type is only looked up again if the key has changed type since.
+    pub async fn get_value_page_as(
+        &mut self,
+        key: &str,
+        mut redis_type: RedisType,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(RedisValue, Option<ValuePage>)> {
+        let mut retries = 0;
+        loop {
+            match self.read_value_page(key, redis_type, offset, limit).await {
+                Err(e) if retries < WRONGTYPE_RETRIES && is_wrong_type_error(&e) => {
+                    retries += 1;
+                    redis_type = self.get_type(key).await?;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn read_value_page(
+        &mut self,
+        key: &str,
+        redis_type: RedisType,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(RedisValue, Option<ValuePage>)> {
+        let stop = (offset + limit) as isize - 1;
+        let raw = &key_to_bytes(key);
+        let (value, total) = match redis_type {
+            RedisType::String => {
+                let val: Option<Vec<u8>> = self.connection.get(raw).await?;
+                return Ok((val.map_or(RedisValue::None, RedisValue::from_bytes), None));
+            }
+            RedisType::List => {
+                let total: usize = self.connection.llen(raw).await?;
+                let items: Vec<String> = self.connection.lrange(raw, offset as isize, stop).await?;
+                (RedisValue::List(items), total)
+            }
+            RedisType::ZSet => {
+                let total: usize = self.connection.zcard(raw).await?;
+                let items: Vec<(String, f64)> = self
+                    .connection
+                    .zrange_withscores(raw, offset as isize, stop)
+                    .await?;
+                (RedisValue::ZSet(items), total)
+            }
+            RedisType::Set => {
+                let total: usize = self.connection.scard(raw).await?;
+                let items = self.sscan_page(key, offset, limit).await?;
+                (RedisValue::Set(items), total)
+            }
+            RedisType::Hash => {
+                let total: usize = self.connection.hlen(raw).await?;
+                let fields = self.hscan_page(key, offset, limit).await?;
+                (RedisValue::Hash(fields), total)
+            }
+            RedisType::Stream => {
+                let total: usize = self.connection.xlen(raw).await?;
+                let entries = self.xrevrange_page(key, offset, limit).await?;
+                (RedisValue::Stream(entries), total)
             }
-            _ => Ok(RedisValue::None),
+            // Missing keys and types without a viewer
+            RedisType::Unknown => return Ok((self.read_value(key).await?, None)),
+        };
+        if total == 0 && !matches!(value, RedisValue::Stream(_)) {
+            return Ok((RedisValue::None, None));
         }
+        let len = match &value {
+            RedisValue::Stream(entries) => entries.len(),
+            value => value.element_count().unwrap_or(0),
+        };
+        Ok((value, Some(ValuePage { offset, len, total })))
+    }
+
+    /// Members `offset..offset + limit` of a set in `SSCAN` order. Sets have
+    /// no index, so earlier members are scanned and skipped.
+    async fn sscan_page(&mut self, key: &str, offset: usize, limit: usize) -> Result<Vec<String>> {
+        let mut members = Vec::new();
+        let mut seen = HashSet::new();
+        let mut cursor = 0u64;
+        loop {
+            let (next, batch): (u64, Vec<String>) = redis::cmd("SSCAN")
+                .arg(key_to_bytes(key))
+                .arg(cursor)
+                .arg("COUNT")
+                .arg(limit)
+                .query_async(&mut self.connection)
+                .await?;
+            merge_scan_batch(&mut members, &mut seen, batch);
+            cursor = next;
+            if cursor == 0 || members.len() >= offset + limit {
+                break;
+            }
+        }
+        Ok(members.into_iter().skip(offset).take(limit).collect())
+    }
+
+    /// One `HSCAN` step from `cursor`: the next cursor (0 when done) and a
+    /// batch of field/value pairs, of roughly `count` fields.
+    pub async fn hscan(
+        &mut self,
+        key: &str,
+        cursor: u64,
+        count: usize,
+    ) -> Result<(u64, Vec<(String, String)>)> {
+        let (next, batch): (u64, Vec<(String, String)>) = redis::cmd("HSCAN")
+            .arg(key_to_bytes(key))
+            .arg(cursor)
+            .arg("COUNT")
+            .arg(count)
+            .query_async(&mut self.connection)
+            .await?;
+        Ok((next, batch))
+    }
+
+    /// Fields `offset..offset + limit` of a hash in `HSCAN` order, without
+    /// the server-blocking `HGETALL`. Like sets, hashes have no index, so
+    /// earlier fields are scanned and skipped.
+    async fn hscan_page(
+        &mut self,
+        key: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<(String, String)>> {
+        let mut fields = Vec::new();
+        let mut seen = HashSet::new();
+        let mut cursor = 0u64;
+        loop {
+            let (next, batch) = self.hscan(key, cursor, limit).await?;
+            for (field, value) in batch {
+                if seen.insert(field.clone()) {
+                    fields.push((field, value));
+                }
+            }
+            cursor = next;
+            if cursor == 0 || fields.len() >= offset + limit {
+                break;
+            }
+        }
+        Ok(fields.into_iter().skip(offset).take(limit).collect())
+    }
+
+    /// Stream entries `offset..offset + limit` counting back from the newest.
+    /// Stream IDs cannot be indexed, so the newer entries are read and
+    /// skipped.
+    async fn xrevrange_page(
+        &mut self,
+        key: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<StreamEntry>> {
+        let entries: Vec<StreamEntry> = redis::cmd("XREVRANGE")
+            .arg(key_to_bytes(key))
+            .arg("+")
+            .arg("-")
+            .arg("COUNT")
+            .arg(offset + limit)
+            .query_async(&mut self.connection)
+            .await?;
+        Ok(entries.into_iter().skip(offset).collect())
+    }
+
+    /// Read a string and update its expiry in one atomic step. `None` if the
+    /// key does not exist.
+    pub async fn getex(&mut self, key: &str, expiry: GetExExpiry) -> Result<Option<Vec<u8>>> {
+        let val: Option<Vec<u8>> = getex_cmd(key, expiry)
+            .query_async(&mut self.connection)
+            .await?;
+        Ok(val)
+    }
+
+    /// Like [`get_value_page_as`](Self::get_value_page_as), but string
+    /// values are read with `GETEX` so viewing them also applies `expiry`.
+    pub async fn get_value_touch(
+        &mut self,
+        key: &str,
+        redis_type: RedisType,
+        expiry: GetExExpiry,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(RedisValue, Option<ValuePage>)> {
+        match redis_type {
+            RedisType::String => match self.getex(key, expiry).await {
+                Ok(val) => Ok((val.map_or(RedisValue::None, RedisValue::from_bytes), None)),
+                // No longer a string; read it as whatever it is now
+                Err(e) if is_wrong_type_error(&e) => self.get_value_page(key, offset, limit).await,
+                Err(e) => Err(e),
+            },
+            _ => self.get_value_page_as(key, redis_type, offset, limit).await,
+        }
+    }
+
+    /// Byte length of a string value (`STRLEN`).
+    pub async fn strlen(&mut self, key: &str) -> Result<usize> {
+        let len: usize = self.connection.strlen(key_to_bytes(key)).await?;
+        Ok(len)
+    }
+
+    /// Bytes `start..=end` of a string value (`GETRANGE`).
+    pub async fn get_range(&mut self, key: &str, start: usize, end: usize) -> Result<Vec<u8>> {
+        let bytes: Vec<u8> = self
+            .connection
+            .getrange(key_to_bytes(key), start as isize, end as isize)
+            .await?;
+        Ok(bytes)
+    }
+
+    /// The first `max` bytes of a string value, shortened to end on a whole
+    /// character when the value is UTF-8 text cut mid-character.
+    pub async fn get_string_prefix(&mut self, key: &str, max: usize) -> Result<Vec<u8>> {
+        if max == 0 {
+            return Ok(Vec::new());
+        }
+        let mut bytes = self.get_range(key, 0, max - 1).await?;
+        if let Err(e) = std::str::from_utf8(&bytes) {
+            if e.error_len().is_none() {
+                bytes.truncate(e.valid_up_to());
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Whether the server is a master or a replica. Cluster connections
+    /// always route writes to masters, so they report `Master`.
+    pub async fn server_role(&mut self) -> Result<ServerRole> {
+        if self.is_cluster() {
+            return Ok(ServerRole::Master);
+        }
+        let info: String = redis::cmd("INFO")
+            .arg("replication")
+            .query_async(&mut self.connection)
+            .await?;
+        parse_role(&info).ok_or_else(|| anyhow!("no role in INFO replication"))
+    }
+
+    /// Server version as (major, minor), from `INFO server`.
+    pub async fn server_version(&mut self) -> Result<(u32, u32)> {
+        let info: String = redis::cmd("INFO")
+            .arg("server")
+            .query_async(&mut self.connection)
+            .await?;
+        parse_version(&info).ok_or_else(|| anyhow!("no version in INFO server"))
+    }
+
+    /// The default `INFO` sections, parsed. In cluster mode this describes
+    /// whichever node answers.
+    pub async fn info(&mut self) -> Result<ServerInfo> {
+        let cmd = redis::cmd("INFO");
+        let info: String = match &mut self.connection.kind {
+            ConnectionKind::Single(_) => cmd.query_async(&mut self.connection).await?,
+            ConnectionKind::Cluster(conn) => {
+                let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random);
+                redis::from_redis_value(&conn.route_command(&cmd, routing).await?)?
+            }
+        };
+        Ok(parse_info(&info))
+    }
+
+    /// Round-trip time of a `PING`.
+    pub async fn ping(&mut self) -> Result<Duration> {
+        let started = Instant::now();
+        let _: String = redis::cmd("PING").query_async(&mut self.connection).await?;
+        Ok(started.elapsed())
+    }
+
+    pub async fn exists(&mut self, key: &str) -> Result<bool> {
+        let exists: bool = self.connection.exists(key_to_bytes(key)).await?;
+        Ok(exists)
+    }
+
+    /// Number of keys in the current database (`DBSIZE`), summed across
+    /// primaries in cluster mode.
+    pub async fn dbsize(&mut self) -> Result<u64> {
+        let size: u64 = redis::cmd("DBSIZE")
+            .query_async(&mut self.connection)
+            .await?;
+        Ok(size)
     }
 
     pub async fn get_ttl(&mut self, key: &str) -> Result<i64> {
-        let ttl: i64 = self.connection.ttl(key).await?;
+        let ttl: i64 = self.connection.ttl(key_to_bytes(key)).await?;
         Ok(ttl)
     }
 
+    /// Bytes the key and its value take in server memory (`MEMORY USAGE`),
+    /// or `None` if the key does not exist.
+    pub async fn memory_usage(&mut self, key: &str) -> Result<Option<i64>> {
+        let bytes: Option<i64> = redis::cmd("MEMORY")
+            .arg("USAGE")
+            .arg(key_to_bytes(key))
+            .query_async(&mut self.connection)
+            .await?;
+        Ok(bytes)
+    }
+
+    /// Internal representation of the value (`OBJECT ENCODING`), e.g.
+    /// `listpack`, `hashtable`, `embstr`.
+    pub async fn object_encoding(&mut self, key: &str) -> Result<String> {
+        let encoding: String = redis::cmd("OBJECT")
+            .arg("ENCODING")
+            .arg(key_to_bytes(key))
+            .query_async(&mut self.connection)
+            .await?;
+        Ok(encoding)
+    }
+
+    /// Expire `key` in `seconds`. Zero or less would delete the key at
+    /// once, so it is refused.
+    pub async fn set_ttl(&mut self, key: &str, seconds: i64) -> Result<()> {
+        if seconds <= 0 {
+            bail!("TTL must be at least 1 second, got {}", seconds);
+        }
+        let _: () = self.connection.expire(key_to_bytes(key), seconds).await?;
+        Ok(())
+    }
+
+    pub async fn persist(&mut self, key: &str) -> Result<()> {
+        let _: () = self.connection.persist(key_to_bytes(key)).await?;
+        Ok(())
+    }
+
     pub async fn set_string(&mut self, key: &str, value: &str) -> Result<()> {
-        let _: () = self.connection.set(key, value).await?;
+        let _: () = self.connection.set(key_to_bytes(key), value).await?;
+        Ok(())
+    }
+
+    /// Set a string key to raw bytes, which need not be valid UTF-8.
+    pub async fn set_bytes(&mut self, key: &str, value: &[u8]) -> Result<()> {
+        let _: () = self.connection.set(key_to_bytes(key), value).await?;
+        Ok(())
+    }
+
+    /// Create a string key with `SET NX`. Returns false, writing nothing,
+    /// when `key` already exists.
+    pub async fn set_bytes_nx(&mut self, key: &str, value: &[u8]) -> Result<bool> {
+        let reply: Option<String> = redis::cmd("SET")
+            .arg(key_to_bytes(key))
+            .arg(value)
+            .arg("NX")
+            .query_async(&mut self.connection)
+            .await?;
+        Ok(reply.is_some())
+    }
+
+    pub async fn hset(&mut self, key: &str, field: &str, value: &str) -> Result<()> {
+        let _: () = self
+            .connection
+            .hset(key_to_bytes(key), field, value)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn lset(&mut self, key: &str, index: usize, value: &str) -> Result<()> {
+        let _: () = self
+            .connection
+            .lset(key_to_bytes(key), index as isize, value)
+            .await?;
+        Ok(())
+    }
+
+    /// Remove the list element at `index`. `LREM` matches by value, so the
+    /// element is first overwritten with a placeholder to leave equal
+    /// elements elsewhere in the list alone.
+    pub async fn lremove_index(&mut self, key: &str, index: usize) -> Result<()> {
+        let key = key_to_bytes(key);
+        let tombstone = list_tombstone();
+        let _: () = redis::pipe()
+            .atomic()
+            .cmd("LSET")
+            .arg(&key)
+            .arg(index)
+            .arg(&tombstone)
+            .ignore()
+            .cmd("LREM")
+            .arg(&key)
+            .arg(1)
+            .arg(&tombstone)
+            .ignore()
+            .query_async(&mut self.connection)
+            .await?;
         Ok(())
     }
 
+    pub async fn srem(&mut self, key: &str, member: &str) -> Result<()> {
+        let _: () = self.connection.srem(key_to_bytes(key), member).await?;
+        Ok(())
+    }
+
+    pub async fn rpush(&mut self, key: &str, items: &[String]) -> Result<()> {
+        let _: () = self.connection.rpush(key_to_bytes(key), items).await?;
+        Ok(())
+    }
+
+    pub async fn sadd(&mut self, key: &str, members: &[String]) -> Result<()> {
+        let _: () = self.connection.sadd(key_to_bytes(key), members).await?;
+        Ok(())
+    }
+
+    pub async fn hset_multiple(&mut self, key: &str, fields: &[(String, String)]) -> Result<()> {
+        let _: () = self
+            .connection
+            .hset_multiple(key_to_bytes(key), fields)
+            .await?;
+        Ok(())
+    }
+
+    /// `ZADD` several `(member, score)` pairs at once.
+    pub async fn zadd_multiple(&mut self, key: &str, members: &[(String, f64)]) -> Result<()> {
+        let items: Vec<(f64, &str)> = members
+            .iter()
+            .map(|(member, score)| (*score, member.as_str()))
+            .collect();
+        let _: () = self
+            .connection
+            .zadd_multiple(key_to_bytes(key), &items)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn zadd(&mut self, key: &str, member: &str, score: f64) -> Result<()> {
+        let _: () = self
+            .connection
+            .zadd(key_to_bytes(key), member, score)
+            .await?;
+        Ok(())
+    }
+
+    /// Serialized value of `key` (`DUMP`), or `None` if it does not exist.
+    pub async fn dump(&mut self, key: &str) -> Result<Option<Vec<u8>>> {
+        let data: Option<Vec<u8>> = redis::cmd("DUMP")
+            .arg(key_to_bytes(key))
+            .query_async(&mut self.connection)
+            .await?;
+        Ok(data)
+    }
+
+    /// Create `key` from `DUMP` output, expiring in `ttl_ms` milliseconds
+    /// (0 for never). Without `replace` an existing `key` fails the command
+    /// with `BUSYKEY`.
+    pub async fn restore(
+        &mut self,
+        key: &str,
+        ttl_ms: u64,
+        data: &[u8],
+        replace: bool,
+    ) -> Result<()> {
+        let mut cmd = redis::cmd("RESTORE");
+        cmd.arg(key_to_bytes(key)).arg(ttl_ms).arg(data);
+        if replace {
+            cmd.arg("REPLACE");
+        }
+        let _: () = cmd.query_async(&mut self.connection).await?;
+        Ok(())
+    }
+
+    /// Replace `key` with `value`, expiring in `ttl` seconds if given, in
+    /// one `MULTI` block so the key is never seen half written.
+    pub async fn write_value(
+        &mut self,
+        key: &str,
+        value: &RedisValue,
+        ttl: Option<u64>,
+    ) -> Result<()> {
+        let empty = match value {
+            RedisValue::Stream(entries) => entries.is_empty(),
+            RedisValue::None => true,
+            value => value.element_count() == Some(0),
+        };
+        if empty {
+            bail!("nothing to write for {}", display_key(key));
+        }
+        let key = key_to_bytes(key);
+        let mut pipe = redis::pipe();
+        pipe.atomic().cmd("DEL").arg(&key).ignore();
+        match value {
+            RedisValue::String(s) => {
+                pipe.cmd("SET").arg(&key).arg(s);
+            }
+            RedisValue::Bytes(b) => {
+                pipe.cmd("SET").arg(&key).arg(b);
+            }
+            RedisValue::List(items) => {
+                pipe.cmd("RPUSH").arg(&key).arg(items);
+            }
+            RedisValue::Set(members) => {
+                pipe.cmd("SADD").arg(&key).arg(members);
+            }
+            RedisValue::ZSet(members) => {
+                pipe.cmd("ZADD").arg(&key);
+                for (member, score) in members {
+                    pipe.arg(score).arg(member);
+                }
+            }
+            RedisValue::Hash(fields) => {
+                pipe.cmd("HSET").arg(&key);
+                for (field, value) in fields {
+                    pipe.arg(field).arg(value);
+                }
+            }
+            // Entries keep their IDs, oldest first
+            RedisValue::Stream(entries) => {
+                for (id, fields) in entries {
+                    pipe.cmd("XADD").arg(&key).arg(id);
+                    for (field, value) in fields {
+                        pipe.arg(field).arg(value);
+                    }
+                    pipe.ignore();
+                }
+            }
+            RedisValue::None => {}
+        }
+        pipe.ignore();
+        if let Some(secs) = ttl {
+            pipe.cmd("EXPIRE").arg(&key).arg(secs).ignore();
+        }
+        let _: () = pipe.query_async(&mut self.connection).await?;
+        Ok(())
+    }
+
+    /// Copy `source` to `dest` with `DUMP` and `RESTORE`, keeping its type,
+    /// encoding and remaining TTL.
+    pub async fn copy_key(&mut self, source: &str, dest: &str, replace: bool) -> Result<()> {
+        let missing = || anyhow::anyhow!("{} no longer exists", display_key(source));
+        let data = self.dump(source).await?.ok_or_else(missing)?;
+        let ttl: i64 = self.connection.pttl(key_to_bytes(source)).await?;
+        // -2: expired since the dump; -1: no expiry
+        if ttl == -2 {
+            return Err(missing());
+        }
+        self.restore(dest, ttl.max(0) as u64, &data, replace).await
+    }
+
+    /// Rename `old` to `new`. Without `replace` this uses `RENAMENX` and
+    /// returns false, leaving both keys alone, when `new` already exists.
+    pub async fn rename(&mut self, old: &str, new: &str, replace: bool) -> Result<bool> {
+        let (old, new) = (key_to_bytes(old), key_to_bytes(new));
+        if replace {
+            let _: () = self.connection.rename(old, new).await?;
+            Ok(true)
+        } else {
+            Ok(self.connection.rename_nx(old, new).await?)
+        }
+    }
+
     pub async fn delete(&mut self, key: &str) -> Result<()> {
-        let _: () = self.connection.del(key).await?;
+        let _: () = self.connection.del(key_to_bytes(key)).await?;
         Ok(())
     }
+
+    /// Remove `keys` with `UNLINK` when `unlink` is set, else with `DEL`.
+    /// Returns how many existed.
+    pub async fn delete_keys(&mut self, keys: &[String], unlink: bool) -> Result<usize> {
+        if unlink {
+            return self.unlink(keys).await;
+        }
+        if keys.is_empty() {
+            return Ok(0);
+        }
+        Ok(self.connection.del(raw_keys(keys)).await?)
+    }
+
+    /// Remove `keys` with `UNLINK`, which frees their memory in the
+    /// background instead of blocking the server like `DEL`. Falls back to
+    /// `DEL` on servers too old for it. Returns how many existed.
+    pub async fn unlink(&mut self, keys: &[String]) -> Result<usize> {
+        if keys.is_empty() {
+            return Ok(0);
+        }
+        let keys = raw_keys(keys);
+        let result: redis::RedisResult<usize> = self.connection.unlink(&keys).await;
+        match result {
+            Err(e) if is_unknown_command(&e) => Ok(self.connection.del(&keys).await?),
+            result => Ok(result?),
+        }
+    }
+
+    /// Delete every key under `prefix` for which `keep` is false, a SCAN
+    /// batch at a time, as [`delete_keys`](Self::delete_keys) does. Returns
+    /// the number of keys removed and the number kept.
+    pub async fn delete_prefix(
+        &mut self,
+        prefix: &str,
+        count: usize,
+        unlink: bool,
+        keep: impl Fn(&str) -> bool,
+    ) -> Result<(usize, usize)> {
+        let pattern = format!("{}*", glob_escape(prefix));
+        let mut scan = self.scan_keys_stream(&pattern, count);
+        let (mut removed, mut kept) = (0, 0);
+        while let Some(mut batch) = scan.next_batch(self).await? {
+            let before = batch.len();
+            batch.retain(|key| !keep(key));
+            kept += before - batch.len();
+            if !batch.is_empty() {
+                removed += self.delete_keys(&batch, unlink).await?;
+            }
+        }
+        Ok((removed, kept))
+    }
+}
+
+/// An in-progress SCAN over the keyspace. Against a cluster, each master
+/// is scanned in turn.
+pub struct KeyScan {
+    pattern: String,
+    count: usize,
+    cursor: u64,
+    /// Cluster masters still to scan, current node first; fetched on the
+    /// first batch and left `None` for a standalone server
+    nodes: Option<Vec<(String, u16)>>,
+    seen: HashSet<String>,
+    done: bool,
+}
+
+impl KeyScan {
+    /// Fetch the next SCAN batch, with keys already seen in earlier batches
+    /// removed. Returns `None` once the cursor wraps back to zero.
+    pub async fn next_batch(&mut self, client: &mut RedisClient) -> Result<Option<Vec<String>>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let mut cmd = redis::cmd("SCAN");
+        cmd.arg(self.cursor)
+            .arg("MATCH")
+            .arg(key_to_bytes(&self.pattern))
+            .arg("COUNT")
+            .arg(self.count);
+
+        let (new_cursor, batch): (u64, Vec<Vec<u8>>) = match &client.connection.kind {
+            ConnectionKind::Single(_) => cmd.query_async(&mut client.connection).await?,
+            ConnectionKind::Cluster(_) => {
+                if self.nodes.is_none() {
+                    let mut masters = client.cluster_masters().await?;
+                    masters.reverse();
+                    self.nodes = Some(masters);
+                }
+                let Some((host, port)) = self.nodes.as_ref().and_then(|n| n.last()).cloned() else {
+                    self.done = true;
+                    return Ok(None);
+                };
+                let ConnectionKind::Cluster(conn) = &mut client.connection.kind else {
+                    unreachable!();
+                };
+                let routing =
+                    RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port });
+                let reply = conn.route_command(&cmd, routing).await?;
+                redis::from_redis_value(&reply)?
+            }
+        };
+
+        let batch = batch.iter().map(|key| key_from_bytes(key)).collect();
+        let mut keys = Vec::new();
+        merge_scan_batch(&mut keys, &mut self.seen, batch);
+        self.cursor = new_cursor;
+        if new_cursor == 0 {
+            // Move on to the next cluster master, if any remain
+            match self.nodes.as_mut() {
+                Some(nodes) => {
+                    nodes.pop();
+                    self.done = nodes.is_empty();
+                }
+                None => self.done = true,
+            }
+        }
+
+        Ok(Some(keys))
+    }
+}
+
+/// `keys` as raw bytes for a multi-key command.
+fn raw_keys(keys: &[String]) -> Vec<Vec<u8>> {
+    keys.iter().map(|key| key_to_bytes(key)).collect()
+}
+
+/// Read the CA and client certificate files named in `tls`. Returns `None`
+/// when no certificates are configured, so the system roots are used.
+pub fn load_tls_certificates(tls: &TlsOptions) -> Result<Option<TlsCertificates>> {
+    let client_tls = match (&tls.cert, &tls.key) {
+        (Some(cert), Some(key)) => Some(ClientTlsConfig {
+            client_cert: std::fs::read(cert)
+                .with_context(|| format!("reading {}", cert.display()))?,
+            client_key: std::fs::read(key).with_context(|| format!("reading {}", key.display()))?,
+        }),
+        (None, None) => None,
+        _ => bail!("a TLS client certificate and key must be given together"),
+    };
+    let root_cert = match &tls.ca {
+        Some(ca) => Some(std::fs::read(ca).with_context(|| format!("reading {}", ca.display()))?),
+        None => None,
+    };
+
+    if client_tls.is_none() && root_cert.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(TlsCertificates {
+        client_tls,
+        root_cert,
+    }))
+}
+
+/// Append a SCAN batch to `keys`, skipping keys already returned by an
+/// earlier iteration. SCAN guarantees completeness, not uniqueness.
+pub fn merge_scan_batch(keys: &mut Vec<String>, seen: &mut HashSet<String>, batch: Vec<String>) {
+    for key in batch {
+        if seen.insert(key.clone()) {
+            keys.push(key);
+        }
+    }
 }
@@ -0,0 +1,51 @@
+use crate::config::mask_url;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Where the tree was left when the app last quit, restored on the next
+/// start against the same server.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionState {
+    /// Prefixes of the expanded folders
+    #[serde(default)]
+    pub expanded_paths: Vec<String>,
+    /// Selected key, or a folder's prefix
+    pub selected_key: Option<String>,
+}
+
+/// `<config_dir>/redis-nav/session.toml`, next to the config file.
+pub fn session_path(config_dir: Option<PathBuf>) -> Option<PathBuf> {
+    config_dir
+        .filter(|d| !d.as_os_str().is_empty())
+        .map(|d| d.join("redis-nav").join("session.toml"))
+}
+
+/// Name a connection's session is stored under: its URL with any password
+/// masked, so none is written to disk, plus the database.
+pub fn session_key(url: &str, db: u8) -> String {
+    format!("{} db {}", mask_url(url), db)
+}
+
+/// The saved session for `key`, if there is one. A missing or unreadable
+/// file just means nothing to restore.
+pub fn load_session(path: &Path, key: &str) -> Option<SessionState> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let mut sessions: BTreeMap<String, SessionState> = toml::from_str(&content).ok()?;
+    sessions.remove(key)
+}
+
+/// Store `state` for `key`, keeping other connections' sessions.
+pub fn save_session(path: &Path, key: &str, state: SessionState) -> Result<()> {
+    let mut sessions: BTreeMap<String, SessionState> = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default();
+    sessions.insert(key.to_string(), state);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, toml::to_string(&sessions)?)?;
+    Ok(())
+}
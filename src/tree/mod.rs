@@ -1,12 +1,21 @@
+use crate::glob::glob_match;
 use crate::redis_client::RedisType;
+use std::collections::HashSet;
 
 #[derive(Debug, Clone)]
 pub struct TreeNode {
     pub name: String,
     pub full_key: Option<String>,
+    /// Key prefix shared by all children, including the trailing delimiter
+    pub prefix: Option<String>,
     pub node_type: NodeType,
+    /// For a folder, a key named exactly like the folder (`user` next to
+    /// `user:1`), with its type
+    pub own_key: Option<(String, RedisType)>,
     pub children: Vec<TreeNode>,
     pub expanded: bool,
+    /// False for a lazily loaded folder whose children have not been
+    /// scanned yet
     pub loaded: bool,
 }
 
@@ -17,11 +26,13 @@ pub enum NodeType {
 }
 
 impl TreeNode {
-    pub fn new_folder(name: String) -> Self {
+    pub fn new_folder(name: String, prefix: String) -> Self {
         Self {
             name,
             full_key: None,
+            prefix: Some(prefix),
             node_type: NodeType::Folder,
+            own_key: None,
             children: Vec::new(),
             expanded: false,
             loaded: true,
@@ -32,7 +43,9 @@ impl TreeNode {
         Self {
             name,
             full_key: Some(full_key),
+            prefix: None,
             node_type: NodeType::Key(redis_type),
+            own_key: None,
             children: Vec::new(),
             expanded: false,
             loaded: true,
@@ -43,24 +56,185 @@ impl TreeNode {
         matches!(self.node_type, NodeType::Folder)
     }
 
+    /// The key this node selects: its own key for a leaf, or the key
+    /// sharing a folder's name.
+    pub fn key(&self) -> Option<&str> {
+        self.full_key
+            .as_deref()
+            .or(self.own_key.as_ref().map(|(k, _)| k.as_str()))
+    }
+
+    /// Type of the key this node selects, if any.
+    pub fn key_type(&self) -> Option<RedisType> {
+        match self.node_type {
+            NodeType::Key(redis_type) => Some(redis_type),
+            NodeType::Folder => self.own_key.as_ref().map(|(_, t)| *t),
+        }
+    }
+
+    /// Turn a leaf into a folder with `prefix`, keeping the leaf's key as
+    /// the folder's own key. Returns false if it already was a folder.
+    fn make_folder(&mut self, prefix: String) -> bool {
+        self.prefix.get_or_insert(prefix);
+        let NodeType::Key(redis_type) = self.node_type else {
+            return false;
+        };
+        if let Some(key) = self.full_key.take() {
+            self.own_key = Some((key, redis_type));
+        }
+        self.node_type = NodeType::Folder;
+        true
+    }
+
     pub fn child_count(&self) -> usize {
         self.children.len()
     }
+
+    /// Counts for a folder's subtree, from the keys loaded so far.
+    pub fn folder_stats(&self) -> FolderStats {
+        let mut stats = FolderStats {
+            children: self.children.len(),
+            keys: 0,
+            types: Vec::new(),
+            complete: true,
+        };
+        self.add_descendants(&mut stats);
+        stats
+            .types
+            .sort_by(|a, b| b.1.cmp(&a.1).then(a.0.as_str().cmp(b.0.as_str())));
+        stats
+    }
+
+    fn add_descendants(&self, stats: &mut FolderStats) {
+        stats.complete &= self.loaded;
+        for child in &self.children {
+            if let Some(redis_type) = child.key_type() {
+                stats.keys += 1;
+                match stats.types.iter_mut().find(|(t, _)| *t == redis_type) {
+                    Some((_, count)) => *count += 1,
+                    None => stats.types.push((redis_type, 1)),
+                }
+            }
+            child.add_descendants(stats);
+        }
+    }
+
+    /// Expand or collapse this node and its descendants, down to
+    /// `max_depth` levels below this node.
+    pub fn set_expanded_recursive(&mut self, expanded: bool, max_depth: usize) {
+        if self.children.is_empty() {
+            return;
+        }
+        self.expanded = expanded;
+        if max_depth == 0 {
+            return;
+        }
+        for child in &mut self.children {
+            child.set_expanded_recursive(expanded, max_depth - 1);
+        }
+    }
+}
+
+/// Summary of a folder shown in the value pane in place of a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FolderStats {
+    /// Direct children: subfolders and keys
+    pub children: usize,
+    /// Keys anywhere below the folder
+    pub keys: usize,
+    /// Key count per type, most common first
+    pub types: Vec<(RedisType, usize)>,
+    /// False while some subfolder has not been scanned (lazy loading)
+    pub complete: bool,
+}
+
+/// Look up a node by its index path from the root, as stored in
+/// `FlatNode::node_index`.
+pub fn node_at_path<'a>(nodes: &'a [TreeNode], path: &[usize]) -> Option<&'a TreeNode> {
+    let (&first, rest) = path.split_first()?;
+    let node = nodes.get(first)?;
+    if rest.is_empty() {
+        Some(node)
+    } else {
+        node_at_path(&node.children, rest)
+    }
+}
+
+/// Names of the nodes along an index path, from the root-level ancestor
+/// down to the node itself.
+pub fn path_names<'a>(nodes: &'a [TreeNode], path: &[usize]) -> Vec<&'a str> {
+    let mut names = Vec::with_capacity(path.len());
+    let mut level = nodes;
+    for &index in path {
+        let Some(node) = level.get(index) else {
+            break;
+        };
+        names.push(node.name.as_str());
+        level = &node.children;
+    }
+    names
+}
+
+/// Look up a node by its index path from the root, as stored in
+/// `FlatNode::node_index`.
+pub fn node_at_path_mut<'a>(nodes: &'a mut [TreeNode], path: &[usize]) -> Option<&'a mut TreeNode> {
+    let (&first, rest) = path.split_first()?;
+    let node = nodes.get_mut(first)?;
+    if rest.is_empty() {
+        Some(node)
+    } else {
+        node_at_path_mut(&mut node.children, rest)
+    }
 }
 
 pub struct TreeBuilder {
     delimiters: Vec<char>,
+    /// Split level N only on `delimiters[N]` (the last one for deeper
+    /// levels) instead of on any delimiter at every level
+    per_level: bool,
+    /// Prefixes of folders that start out expanded when inserted
+    expand: HashSet<String>,
 }
 
 impl TreeBuilder {
     pub fn new(delimiters: Vec<char>) -> Self {
-        Self { delimiters }
+        Self {
+            delimiters,
+            per_level: false,
+            expand: HashSet::new(),
+        }
+    }
+
+    /// Give each tree level its own delimiter, in configured order.
+    pub fn per_level(mut self, per_level: bool) -> Self {
+        self.per_level = per_level;
+        self
+    }
+
+    /// Create the folders with these prefixes expanded, so a tree rebuilt
+    /// batch by batch opens up as it was while it fills.
+    pub fn expanding(mut self, prefixes: HashSet<String>) -> Self {
+        self.expand = prefixes;
+        self
     }
 
     pub fn build(&self, keys: &[(String, RedisType)]) -> Vec<TreeNode> {
+        self.build_filtered(keys, None)
+    }
+
+    /// Build a tree containing only keys of `type_filter` (and the folders
+    /// leading to them), or all keys when `None`.
+    pub fn build_filtered(
+        &self,
+        keys: &[(String, RedisType)],
+        type_filter: Option<RedisType>,
+    ) -> Vec<TreeNode> {
         let mut root_children: Vec<TreeNode> = Vec::new();
 
         for (key, redis_type) in keys {
+            if type_filter.is_some_and(|t| t != *redis_type) {
+                continue;
+            }
             self.insert_key(&mut root_children, key, *redis_type);
         }
 
@@ -68,7 +242,10 @@ impl TreeBuilder {
         root_children
     }
 
-    fn insert_key(&self, nodes: &mut Vec<TreeNode>, key: &str, redis_type: RedisType) {
+    /// Insert one key into an existing tree. Inserting a key that is already
+    /// present updates it in place. Call [`sort_nodes`](Self::sort_nodes)
+    /// after a batch of inserts.
+    pub fn insert_key(&self, nodes: &mut Vec<TreeNode>, key: &str, redis_type: RedisType) {
         let parts = self.split_key(key);
 
         if parts.is_empty() {
@@ -98,11 +275,14 @@ impl TreeBuilder {
         if remaining.is_empty() {
             // This is a leaf node (actual key)
             if let Some(idx) = node_idx {
-                // Convert folder to key if needed, or update
-                if nodes[idx].is_folder() {
-                    // Keep as folder but mark it also has a key
-                    nodes[idx].full_key = Some(full_key.to_string());
-                    nodes[idx].node_type = NodeType::Key(redis_type);
+                // A folder of the same name keeps its children and gains
+                // the key; re-inserting an existing key refreshes its type
+                let node = &mut nodes[idx];
+                if node.is_folder() {
+                    node.own_key = Some((full_key.to_string(), redis_type));
+                } else {
+                    node.full_key = Some(full_key.to_string());
+                    node.node_type = NodeType::Key(redis_type);
                 }
             } else {
                 nodes.push(TreeNode::new_key(
@@ -113,10 +293,17 @@ impl TreeBuilder {
             }
         } else {
             // This is an intermediate node (folder)
+            let prefix = folder_prefix(full_key, name);
+            let expand = self.expand.contains(&prefix);
             let idx = if let Some(idx) = node_idx {
+                if nodes[idx].make_folder(prefix) {
+                    nodes[idx].expanded = expand;
+                }
                 idx
             } else {
-                nodes.push(TreeNode::new_folder(name.to_string()));
+                let mut folder = TreeNode::new_folder(name.to_string(), prefix);
+                folder.expanded = expand;
+                nodes.push(folder);
                 nodes.len() - 1
             };
 
@@ -124,12 +311,69 @@ impl TreeBuilder {
         }
     }
 
+    /// Number of tree levels in `prefix`: 0 for the root (`""`), 1 for
+    /// `user:`, 2 for `user:1:`.
+    pub fn depth_of(&self, prefix: &str) -> usize {
+        self.split_key(prefix).len()
+    }
+
+    /// When `key` lies more than `levels` levels deep, the prefix of its
+    /// folder at depth `levels`, which stands in for it until loaded.
+    pub fn stub_prefix(&self, key: &str, levels: usize) -> Option<String> {
+        let parts = self.split_key(key);
+        if levels == 0 || parts.len() <= levels {
+            return None;
+        }
+        Some(folder_prefix(key, parts[levels - 1]))
+    }
+
+    /// Add an unloaded folder for `prefix`, creating its ancestors as needed.
+    /// Returns false if the folder already exists.
+    pub fn insert_stub(&self, nodes: &mut Vec<TreeNode>, prefix: &str) -> bool {
+        let parts = self.split_key(prefix);
+        self.insert_stub_parts(nodes, &parts, prefix)
+    }
+
+    fn insert_stub_parts(&self, nodes: &mut Vec<TreeNode>, parts: &[&str], prefix: &str) -> bool {
+        let Some((&name, remaining)) = parts.split_first() else {
+            return false;
+        };
+        let folder = folder_prefix(prefix, name);
+        let expand = self.expand.contains(&folder);
+        let (idx, created) = match nodes.iter().position(|n| n.name == name) {
+            Some(idx) => (idx, nodes[idx].make_folder(folder)),
+            None => {
+                nodes.push(TreeNode::new_folder(name.to_string(), folder));
+                (nodes.len() - 1, true)
+            }
+        };
+
+        if remaining.is_empty() {
+            if created {
+                nodes[idx].loaded = false;
+            }
+            created
+        } else {
+            // Unloaded stubs stay closed; the folders above them may open
+            if created {
+                nodes[idx].expanded = expand;
+            }
+            self.insert_stub_parts(&mut nodes[idx].children, remaining, prefix)
+        }
+    }
+
     fn split_key<'a>(&self, key: &'a str) -> Vec<&'a str> {
         let mut parts = Vec::new();
         let mut start = 0;
 
         for (i, c) in key.char_indices() {
-            if self.delimiters.contains(&c) {
+            let is_delimiter = if self.per_level {
+                let level = parts.len().min(self.delimiters.len().saturating_sub(1));
+                self.delimiters.get(level) == Some(&c)
+            } else {
+                self.delimiters.contains(&c)
+            };
+            if is_delimiter {
                 if i > start {
                     parts.push(&key[start..i]);
                 }
@@ -144,7 +388,7 @@ impl TreeBuilder {
         parts
     }
 
-    fn sort_nodes(&self, nodes: &mut Vec<TreeNode>) {
+    pub fn sort_nodes(&self, nodes: &mut [TreeNode]) {
         nodes.sort_by(|a, b| {
             // Folders first, then by name
             match (&a.node_type, &b.node_type) {
@@ -159,3 +403,114 @@ impl TreeBuilder {
         }
     }
 }
+
+/// Prefix of `full_key` up to and including the delimiter that follows
+/// `part`, which must be a subslice of `full_key`.
+fn folder_prefix(full_key: &str, part: &str) -> String {
+    let end = part.as_ptr() as usize - full_key.as_ptr() as usize + part.len();
+    let delim_len = full_key[end..].chars().next().map_or(0, char::len_utf8);
+    full_key[..end + delim_len].to_string()
+}
+
+/// Collect the prefixes of expanded folders in `nodes`, at any depth.
+pub fn expanded_prefixes(nodes: &[TreeNode], out: &mut HashSet<String>) {
+    for node in nodes.iter().filter(|n| n.expanded) {
+        if let Some(prefix) = &node.prefix {
+            out.insert(prefix.clone());
+        }
+        expanded_prefixes(&node.children, out);
+    }
+}
+
+/// Expand every loaded folder whose prefix is in `prefixes`.
+pub fn expand_prefixes(nodes: &mut [TreeNode], prefixes: &HashSet<String>) {
+    for node in nodes {
+        if node.loaded
+            && !node.children.is_empty()
+            && node.prefix.as_ref().is_some_and(|p| prefixes.contains(p))
+        {
+            node.expanded = true;
+        }
+        expand_prefixes(&mut node.children, prefixes);
+    }
+}
+
+/// Expand the folders leading to `key` so that it shows once the tree is
+/// flattened. Returns false if `key` is not in `nodes`.
+pub fn expand_to_key(nodes: &mut [TreeNode], key: &str) -> bool {
+    for node in nodes {
+        if node.key() == Some(key) {
+            return true;
+        }
+        let above = node
+            .prefix
+            .as_ref()
+            .is_some_and(|p| key.starts_with(p.as_str()));
+        if above && expand_to_key(&mut node.children, key) {
+            node.expanded = true;
+            return true;
+        }
+    }
+    false
+}
+
+/// Case-insensitive fuzzy match: every character of `query` appears in
+/// `text` in order, though not necessarily contiguously.
+pub fn fuzzy_match(text: &str, query: &str) -> bool {
+    let mut text_chars = text.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| text_chars.any(|t| t == q))
+}
+
+/// Structured tree filter: whitespace-separated terms that must all match.
+/// `type:<name>` matches keys of that type; anything else is a glob over
+/// the full key name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyFilter {
+    /// The filter as typed
+    pub query: String,
+    types: Vec<RedisType>,
+    globs: Vec<String>,
+}
+
+impl KeyFilter {
+    /// Parse a filter; `None` for a blank query. Fails on unknown types.
+    pub fn parse(query: &str) -> anyhow::Result<Option<Self>> {
+        let mut types = Vec::new();
+        let mut globs = Vec::new();
+        for term in query.split_whitespace() {
+            match term.strip_prefix("type:") {
+                Some(name) => {
+                    let redis_type = RedisType::ALL
+                        .iter()
+                        .find(|t| t.as_str().eq_ignore_ascii_case(name))
+                        .ok_or_else(|| {
+                            let names: Vec<&str> =
+                                RedisType::ALL.iter().map(|t| t.as_str()).collect();
+                            anyhow::anyhow!(
+                                "unknown type '{}' (expected {})",
+                                name,
+                                names.join(", ")
+                            )
+                        })?;
+                    types.push(*redis_type);
+                }
+                None => globs.push(term.to_string()),
+            }
+        }
+        if types.is_empty() && globs.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            query: query.trim().to_string(),
+            types,
+            globs,
+        }))
+    }
+
+    pub fn matches(&self, key: &str, redis_type: RedisType) -> bool {
+        self.types.iter().all(|&t| t == redis_type) && self.globs.iter().all(|g| glob_match(g, key))
+    }
+}
@@ -0,0 +1,342 @@
+use crate::config::file::Profile;
+use crate::config::TlsOptions;
+use anyhow::{anyhow, bail, Context, Result};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use rustls::{ClientConfig, RootCertStore};
+use std::io::{BufReader, Read};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tokio_rustls::TlsConnector;
+
+/// How long to wait for `ssh` to authenticate and start forwarding.
+pub const TUNNEL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Jump host settings from a profile's `ssh_*` fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshOptions {
+    pub host: String,
+    pub user: Option<String>,
+    /// Private key passed to `ssh -i`
+    pub key: Option<PathBuf>,
+    pub port: Option<u16>,
+}
+
+impl SshOptions {
+    /// The profile's SSH settings; `None` unless `ssh_host` is set.
+    pub fn from_profile(profile: &Profile) -> Option<Self> {
+        Some(Self {
+            host: profile.ssh_host.clone()?,
+            user: profile.ssh_user.clone(),
+            key: profile.ssh_key.clone(),
+            port: profile.ssh_port,
+        })
+    }
+
+    /// `user@host`, or just the host.
+    pub fn destination(&self) -> String {
+        match &self.user {
+            Some(user) => format!("{}@{}", user, self.host),
+            None => self.host.clone(),
+        }
+    }
+
+    /// Arguments for an `ssh` process forwarding `local_port` to
+    /// `target_host:target_port`. Batch mode makes ssh fail instead of
+    /// prompting for a password the TUI could not show, and it exits if the
+    /// forward cannot be set up.
+    pub fn command_args(
+        &self,
+        local_port: u16,
+        target_host: &str,
+        target_port: u16,
+    ) -> Vec<String> {
+        let target_host = if target_host.contains(':') {
+            format!("[{}]", target_host)
+        } else {
+            target_host.to_string()
+        };
+        let mut args = vec![
+            "-N".to_string(),
+            "-L".to_string(),
+            format!("127.0.0.1:{}:{}:{}", local_port, target_host, target_port),
+            "-o".to_string(),
+            "ExitOnForwardFailure=yes".to_string(),
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            format!("ConnectTimeout={}", TUNNEL_TIMEOUT.as_secs()),
+        ];
+        if let Some(port) = self.port {
+            args.extend(["-p".to_string(), port.to_string()]);
+        }
+        if let Some(key) = &self.key {
+            args.extend(["-i".to_string(), key.display().to_string()]);
+        }
+        // A host starting with '-' must not be read as an option
+        args.push("--".to_string());
+        args.push(self.destination());
+        args
+    }
+}
+
+/// A `redis://` URL split around its host and port.
+struct UrlParts<'a> {
+    /// Scheme and user info, e.g. `redis://:secret@`
+    head: &'a str,
+    host: &'a str,
+    port: u16,
+    /// Path and query, e.g. `/2?protocol=resp3`
+    tail: &'a str,
+}
+
+fn split_url(url: &str) -> Result<UrlParts<'_>> {
+    let (scheme, rest) = url
+        .split_once("://")
+        .ok_or_else(|| anyhow!("invalid Redis URL"))?;
+    if !matches!(scheme, "redis" | "rediss") {
+        bail!("an SSH tunnel needs a TCP address, not a {}:// URL", scheme);
+    }
+    let end = rest.find(['/', '?']).unwrap_or(rest.len());
+    let authority = &rest[..end];
+    let host_start = authority.rfind('@').map_or(0, |i| i + 1);
+    let head = &url[..scheme.len() + 3 + host_start];
+    let hostport = &authority[host_start..];
+
+    let (host, port) = match hostport.strip_prefix('[') {
+        // IPv6 literal: [::1]:6379
+        Some(v6) => {
+            let (host, after) = v6
+                .split_once(']')
+                .ok_or_else(|| anyhow!("invalid IPv6 address in Redis URL"))?;
+            (host, after.strip_prefix(':'))
+        }
+        None => match hostport.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (hostport, None),
+        },
+    };
+    let port = match port {
+        Some(port) => port
+            .parse()
+            .map_err(|_| anyhow!("invalid port '{}' in Redis URL", port))?,
+        None => 6379,
+    };
+    if host.is_empty() {
+        bail!("Redis URL has no host");
+    }
+    Ok(UrlParts {
+        head,
+        host,
+        port,
+        tail: &rest[end..],
+    })
+}
+
+/// Host and port the tunnel should forward to, as seen from the jump host.
+pub fn tunnel_target(url: &str) -> Result<(String, u16)> {
+    let parts = split_url(url)?;
+    Ok((parts.host.to_string(), parts.port))
+}
+
+/// `url` pointed at the local end of a tunnel, keeping its credentials,
+/// database and options. With `relayed_tls`, a `rediss://` URL becomes
+/// `redis://`, as the [`TlsRelay`] behind the port speaks TLS for it.
+pub fn local_url(url: &str, local_port: u16, relayed_tls: bool) -> Result<String> {
+    let parts = split_url(url)?;
+    let head = match parts.head.strip_prefix("rediss://") {
+        Some(rest) if relayed_tls => format!("redis://{}", rest),
+        _ => parts.head.to_string(),
+    };
+    Ok(format!("{}127.0.0.1:{}{}", head, local_port, parts.tail))
+}
+
+/// A running `ssh -L` port forward. The ssh process is killed when this is
+/// dropped.
+pub struct SshTunnel {
+    child: Child,
+    local_port: u16,
+    label: String,
+    relay: Option<TlsRelay>,
+}
+
+impl SshTunnel {
+    /// Start `ssh` and wait until the local port accepts connections. Fails
+    /// with ssh's own error output if it exits first, or after
+    /// [`TUNNEL_TIMEOUT`].
+    pub fn open(ssh: &SshOptions, target_host: &str, target_port: u16) -> Result<Self> {
+        // Let the OS pick a free port, then hand it to ssh
+        let local_port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?
+            .local_addr()?
+            .port();
+        let child = Command::new("ssh")
+            .args(ssh.command_args(local_port, target_host, target_port))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("failed to run ssh for the tunnel")?;
+        let mut tunnel = Self {
+            child,
+            local_port,
+            label: ssh.host.clone(),
+            relay: None,
+        };
+
+        let started = Instant::now();
+        loop {
+            if let Some(status) = tunnel.child.try_wait()? {
+                let mut stderr = String::new();
+                if let Some(mut pipe) = tunnel.child.stderr.take() {
+                    let _ = pipe.read_to_string(&mut stderr);
+                }
+                bail!(
+                    "SSH tunnel via {} failed ({}): {}",
+                    ssh.destination(),
+                    status,
+                    stderr.trim()
+                );
+            }
+            if TcpStream::connect((Ipv4Addr::LOCALHOST, local_port)).is_ok() {
+                return Ok(tunnel);
+            }
+            if started.elapsed() >= TUNNEL_TIMEOUT {
+                bail!(
+                    "SSH tunnel via {} was not ready after {}s",
+                    ssh.destination(),
+                    TUNNEL_TIMEOUT.as_secs()
+                );
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    /// Where to connect: the tunnel, or the TLS relay in front of it.
+    pub fn local_port(&self) -> u16 {
+        self.relay
+            .as_ref()
+            .map_or(self.local_port, |r| r.local_port)
+    }
+
+    /// Put a [`TlsRelay`] for `server_name` in front of the tunnel. The
+    /// server's certificate is checked once here, so a bad one fails at
+    /// startup rather than as a dropped connection later.
+    pub async fn relay_tls(&mut self, server_name: &str, tls: &TlsOptions) -> Result<()> {
+        self.relay = Some(TlsRelay::start(self.local_port, server_name, tls).await?);
+        Ok(())
+    }
+
+    /// The jump host, for display.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Whether the ssh process is still running.
+    pub fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Plain connections on a local port, each carried over TLS to the tunnel.
+/// redis checks a `rediss://` server's certificate against the host it
+/// dials, which through a tunnel is 127.0.0.1; the relay checks it against
+/// the server's real name instead.
+struct TlsRelay {
+    local_port: u16,
+    task: JoinHandle<()>,
+}
+
+impl TlsRelay {
+    async fn start(tunnel_port: u16, server_name: &str, tls: &TlsOptions) -> Result<Self> {
+        let connector = TlsConnector::from(Arc::new(tls_client_config(tls)?));
+        let name = ServerName::try_from(server_name.to_string())
+            .with_context(|| format!("'{}' is not a valid TLS server name", server_name))?;
+
+        let upstream = tokio::net::TcpStream::connect((Ipv4Addr::LOCALHOST, tunnel_port)).await?;
+        connector
+            .connect(name.clone(), upstream)
+            .await
+            .with_context(|| {
+                format!(
+                    "TLS handshake with {} through the tunnel failed",
+                    server_name
+                )
+            })?;
+
+        let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let local_port = listener.local_addr()?.port();
+        let task = tokio::spawn(async move {
+            while let Ok((mut client, _)) = listener.accept().await {
+                let connector = connector.clone();
+                let name = name.clone();
+                tokio::spawn(async move {
+                    // On failure `client` is dropped, which redis reports
+                    // as a lost connection and retries
+                    let Ok(upstream) =
+                        tokio::net::TcpStream::connect((Ipv4Addr::LOCALHOST, tunnel_port)).await
+                    else {
+                        return;
+                    };
+                    let Ok(mut upstream) = connector.connect(name, upstream).await else {
+                        return;
+                    };
+                    let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+                });
+            }
+        });
+        Ok(Self { local_port, task })
+    }
+}
+
+impl Drop for TlsRelay {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// The TLS settings redis would use for a direct `rediss://` connection:
+/// the system roots or the configured CA, and the client certificate if
+/// one is given.
+fn tls_client_config(tls: &TlsOptions) -> Result<ClientConfig> {
+    let mut roots = RootCertStore::empty();
+    let certs = match &tls.ca {
+        Some(ca) => read_certs(ca)?,
+        None => rustls_native_certs::load_native_certs().context("loading system certificates")?,
+    };
+    for cert in certs {
+        roots.add(cert)?;
+    }
+    let builder = ClientConfig::builder().with_root_certificates(roots);
+    let config = match (&tls.cert, &tls.key) {
+        (Some(cert), Some(key)) => {
+            builder.with_client_auth_cert(read_certs(cert)?, read_key(key)?)?
+        }
+        (None, None) => builder.with_no_client_auth(),
+        _ => bail!("a TLS client certificate and key must be given together"),
+    };
+    Ok(config)
+}
+
+fn read_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let file = std::fs::File::open(path).with_context(|| format!("reading {}", path.display()))?;
+    rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<Result<_, _>>()
+        .with_context(|| format!("reading {}", path.display()))
+}
+
+fn read_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    let file = std::fs::File::open(path).with_context(|| format!("reading {}", path.display()))?;
+    rustls_pemfile::private_key(&mut BufReader::new(file))
+        .with_context(|| format!("reading {}", path.display()))?
+        .ok_or_else(|| anyhow!("no private key in {}", path.display()))
+}
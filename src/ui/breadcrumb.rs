@@ -0,0 +1,84 @@
+use crate::redis_client::key::display_key;
+use crate::ui::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const SEPARATOR: &str = " › ";
+
+/// Path from the root to the selected tree node, drawn above the value
+/// pane as `user › 1 › sessions › abc`.
+pub struct Breadcrumb<'a> {
+    segments: &'a [&'a str],
+    theme: &'a Theme,
+}
+
+impl<'a> Breadcrumb<'a> {
+    pub fn new(segments: &'a [&'a str], theme: &'a Theme) -> Self {
+        Self { segments, theme }
+    }
+
+    /// The breadcrumb fitted to `width` columns. When it is too long the
+    /// leading segments give way to `…`, so the selection itself stays
+    /// visible.
+    pub fn line(&self, width: usize) -> Line<'static> {
+        let names: Vec<String> = self.segments.iter().map(|s| display_key(s)).collect();
+        let Some(last) = names.len().checked_sub(1) else {
+            return Line::default();
+        };
+        let ellipsis = Span::styled("…", self.theme.border);
+        let elided = ellipsis.width() + SEPARATOR.width();
+        let fits = |first: usize| {
+            let shown: usize = names[first..].iter().map(|n| n.width()).sum::<usize>()
+                + SEPARATOR.width() * (last - first);
+            shown + if first > 0 { elided } else { 0 } <= width
+        };
+        let first = (0..last).find(|&first| fits(first)).unwrap_or(last);
+
+        let mut spans = Vec::new();
+        if first > 0 {
+            spans.push(ellipsis);
+            spans.push(Span::styled(SEPARATOR, self.theme.border));
+        }
+        for (i, name) in names.iter().enumerate().skip(first) {
+            if i > first {
+                spans.push(Span::styled(SEPARATOR, self.theme.border));
+            }
+            if i == last {
+                let room = width.saturating_sub(spans.iter().map(Span::width).sum());
+                spans.push(Span::styled(truncate_left(name, room), self.theme.title));
+            } else {
+                spans.push(Span::styled(name.clone(), self.theme.tree_folder));
+            }
+        }
+        Line::from(spans)
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(Paragraph::new(self.line(area.width as usize)), area);
+    }
+}
+
+/// `text` cut from the left to at most `width` columns, starting with `…`
+/// when anything was cut.
+fn truncate_left(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let mut kept = Vec::new();
+    let mut used = 0;
+    for c in text.chars().rev() {
+        let w = c.width().unwrap_or(0);
+        if used + w >= width {
+            break;
+        }
+        used += w;
+        kept.push(c);
+    }
+    std::iter::once('…').chain(kept.into_iter().rev()).collect()
+}
@@ -1,72 +1,412 @@
+use crate::action::Action;
 use crate::config::ProtectionLevel;
+use crate::redis_client::{keyspace_hit_ratio, RedisType, RedisValue, ServerInfo};
 use crate::ui::theme::Theme;
 use ratatui::layout::{Alignment, Constraint, Flex, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
 use ratatui::Frame;
+use similar::{ChangeTag, TextDiff};
+
+/// One element of a collection value, as addressed by edits and removals.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueElement {
+    ListIndex(usize),
+    SetMember(String),
+    /// A sorted set member; editing it changes its score
+    ZSetMember(String),
+    HashField(String),
+}
+
+impl ValueElement {
+    pub fn describe(&self) -> String {
+        match self {
+            ValueElement::ListIndex(i) => format!("element [{}]", i),
+            ValueElement::SetMember(m) => format!("member '{}'", m),
+            ValueElement::ZSetMember(m) => format!("score of '{}'", m),
+            ValueElement::HashField(f) => format!("field '{}'", f),
+        }
+    }
+}
+
+/// Input field with the cursor in the create-key dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreateKeyField {
+    Name,
+    Type,
+    Value,
+}
+
+impl CreateKeyField {
+    pub fn next(self) -> Self {
+        match self {
+            CreateKeyField::Name => CreateKeyField::Type,
+            CreateKeyField::Type => CreateKeyField::Value,
+            CreateKeyField::Value => CreateKeyField::Name,
+        }
+    }
+
+    pub fn previous(self) -> Self {
+        match self {
+            CreateKeyField::Name => CreateKeyField::Value,
+            CreateKeyField::Type => CreateKeyField::Name,
+            CreateKeyField::Value => CreateKeyField::Type,
+        }
+    }
+}
+
+/// Types the create-key dialog can create, in selector order.
+pub const CREATE_KEY_TYPES: [RedisType; 5] = [
+    RedisType::String,
+    RedisType::List,
+    RedisType::Set,
+    RedisType::Hash,
+    RedisType::ZSet,
+];
+
+/// The initial value of a new collection key from the create-key dialog,
+/// one element per line: `member` for lists and sets, `field: value` for
+/// hashes and `score: member` for sorted sets. Blank lines are skipped.
+pub fn parse_members(key_type: RedisType, input: &str) -> anyhow::Result<RedisValue> {
+    let lines: Vec<&str> = input.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.is_empty() {
+        anyhow::bail!("Add at least one member, one per line");
+    }
+    let owned = || lines.iter().map(|l| l.to_string()).collect();
+    let pairs = |what: &str| {
+        lines
+            .iter()
+            .map(|line| {
+                split_pair(line)
+                    .ok_or_else(|| anyhow::anyhow!("Expected '{}', got '{}'", what, line))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+    };
+    Ok(match key_type {
+        RedisType::List => RedisValue::List(owned()),
+        RedisType::Set => RedisValue::Set(owned()),
+        RedisType::Hash => RedisValue::Hash(
+            pairs("field: value")?
+                .into_iter()
+                .map(|(field, value)| (field.to_string(), value.to_string()))
+                .collect(),
+        ),
+        RedisType::ZSet => RedisValue::ZSet(
+            pairs("score: member")?
+                .into_iter()
+                .map(|(score, member)| {
+                    let score = score
+                        .trim()
+                        .parse::<f64>()
+                        .map_err(|_| anyhow::anyhow!("Invalid score '{}'", score.trim()))?;
+                    Ok((member.to_string(), score))
+                })
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        other => anyhow::bail!("Cannot create a {} key", other.as_str()),
+    })
+}
+
+/// Split `a: b` at its first `": "`, or failing that its first `:`, so a
+/// hash field may itself contain colons.
+fn split_pair(line: &str) -> Option<(&str, &str)> {
+    line.split_once(": ").or_else(|| line.split_once(':'))
+}
 
 pub enum Dialog {
     Help,
+    /// Asked before quitting with `confirm_quit` set
+    ConfirmQuit,
     Confirm {
         title: String,
         message: String,
+        /// Text the user must type before Enter confirms
         confirm_text: String,
+        input: String,
     },
     Protection {
+        key: String,
         namespace: String,
         level: ProtectionLevel,
+        /// Typed confirmation for the `Confirm` level
+        input: String,
+        /// Action re-run once the user proceeds
+        resume: Option<Action>,
     },
     DiffPreview {
         key: String,
+        /// Collection element being edited, or `None` for a whole string value
+        element: Option<ValueElement>,
         old_value: String,
         new_value: String,
+        /// The diff of the two, computed once when the preview opens
+        lines: Vec<Line<'static>>,
+        /// Wrap long lines, as the value pane does
+        wrap: bool,
+        /// First diff line shown
+        scroll: usize,
+        /// Bytes to write instead of `new_value`, for an imported file or
+        /// an edit that is not valid UTF-8
+        raw: Option<Vec<u8>>,
+        /// Writing a new key, which must not exist yet
+        create: bool,
+        /// Esc was pressed with unsaved changes; waiting for y/n
+        confirm_discard: bool,
+    },
+    Import {
+        key: String,
+        path_input: String,
+        /// Reading the value of a new key, from the create dialog
+        create: bool,
+    },
+    RemoveElement {
+        key: String,
+        element: ValueElement,
+    },
+    SetTtl {
+        key: String,
+        /// TTL when the dialog opened, in seconds (negative = no expiry)
+        current: i64,
+        input: String,
+    },
+    Rename {
+        old_key: String,
+        new_key_input: String,
     },
+    /// Name for a copy of `source`, made with DUMP and RESTORE
+    CopyKey {
+        source: String,
+        dest_input: String,
+    },
+    SelectDb {
+        current: u8,
+        input: String,
+    },
+    CreateKey {
+        key_type: RedisType,
+        name: String,
+        value: String,
+        field: CreateKeyField,
+    },
+    HashFieldPicker {
+        key: String,
+        fields: Vec<String>,
+        selected: usize,
+    },
+    ContextMenu {
+        title: String,
+        actions: Vec<Action>,
+        selected: usize,
+    },
+    CommandPalette {
+        /// Actions applicable to the selection, before filtering
+        actions: Vec<Action>,
+        input: String,
+        /// Index into the filtered actions
+        selected: usize,
+    },
+    /// Parsed `INFO`, drawn full-screen
+    ServerInfo {
+        info: ServerInfo,
+        /// First line shown
+        scroll: usize,
+    },
+}
+
+impl Dialog {
+    /// A preview of writing `new_value` over `old_value`, diffed up front
+    /// rather than on every frame.
+    pub fn diff_preview(
+        key: String,
+        element: Option<ValueElement>,
+        old_value: String,
+        new_value: String,
+        raw: Option<Vec<u8>>,
+        create: bool,
+        wrap: bool,
+    ) -> Self {
+        let lines = diff_lines(&old_value, &new_value);
+        Dialog::DiffPreview {
+            key,
+            element,
+            old_value,
+            new_value,
+            lines,
+            wrap,
+            scroll: 0,
+            raw,
+            create,
+            confirm_discard: false,
+        }
+    }
+
+    /// Whether closing this dialog would throw away an edit: a diff
+    /// preview whose new value differs from the old one.
+    pub fn has_unsaved_changes(&self) -> bool {
+        match self {
+            Dialog::DiffPreview {
+                old_value,
+                new_value,
+                raw,
+                ..
+            } => raw.is_some() || old_value != new_value,
+            _ => false,
+        }
+    }
 }
 
 pub fn render_dialog(frame: &mut Frame, dialog: &Dialog, theme: &Theme) {
-    let area = centered_rect(60, 50, frame.area());
+    let area = match dialog {
+        Dialog::ServerInfo { .. } => frame.area(),
+        _ => centered_rect(60, 50, frame.area()),
+    };
 
     // Clear background
     frame.render_widget(Clear, area);
 
     match dialog {
         Dialog::Help => render_help(frame, area, theme),
+        Dialog::ConfirmQuit => render_confirm_quit(frame, area, theme),
         Dialog::Confirm {
             title,
             message,
             confirm_text,
-        } => render_confirm(frame, area, title, message, confirm_text, theme),
-        Dialog::Protection { namespace, level } => {
-            render_protection(frame, area, namespace, *level, theme)
-        }
+            input,
+        } => render_confirm(frame, area, title, message, confirm_text, input, theme),
+        Dialog::Protection {
+            key,
+            namespace,
+            level,
+            input,
+            ..
+        } => render_protection(frame, area, key, namespace, *level, input, theme),
         Dialog::DiffPreview {
             key,
-            old_value,
-            new_value,
-        } => render_diff_preview(frame, area, key, old_value, new_value, theme),
+            element,
+            lines,
+            wrap,
+            scroll,
+            confirm_discard,
+            ..
+        } => {
+            let target = match element {
+                Some(e) => format!("{}, {}", key, e.describe()),
+                None => key.clone(),
+            };
+            render_diff_preview(
+                frame,
+                area,
+                &target,
+                lines,
+                *wrap,
+                *scroll,
+                *confirm_discard,
+                theme,
+            )
+        }
+        Dialog::RemoveElement { key, element } => {
+            render_remove_element(frame, area, key, element, theme)
+        }
+        Dialog::SetTtl {
+            key,
+            current,
+            input,
+        } => render_set_ttl(frame, area, key, *current, input, theme),
+        Dialog::Import {
+            key, path_input, ..
+        } => render_import(frame, area, key, path_input, theme),
+        Dialog::Rename {
+            old_key,
+            new_key_input,
+        } => render_new_name(frame, area, "Rename", old_key, new_key_input, theme),
+        Dialog::CopyKey { source, dest_input } => {
+            render_new_name(frame, area, "Copy", source, dest_input, theme)
+        }
+        Dialog::SelectDb { current, input } => {
+            render_select_db(frame, area, *current, input, theme)
+        }
+        Dialog::CreateKey {
+            key_type,
+            name,
+            value,
+            field,
+        } => render_create_key(frame, area, *key_type, name, value, *field, theme),
+        Dialog::HashFieldPicker {
+            key,
+            fields,
+            selected,
+        } => {
+            let title = format!("Edit field of {}", key);
+            let items = fields.iter().map(|f| ListItem::new(f.as_str())).collect();
+            render_list(frame, area, &title, items, *selected, theme)
+        }
+        Dialog::ContextMenu {
+            title,
+            actions,
+            selected,
+        } => render_context_menu(frame, area, title, actions, *selected, theme),
+        Dialog::CommandPalette {
+            actions,
+            input,
+            selected,
+        } => render_command_palette(frame, area, actions, input, *selected, theme),
+        Dialog::ServerInfo { info, scroll } => {
+            render_server_info(frame, area, info, *scroll, theme)
+        }
     }
 }
 
 fn render_help(frame: &mut Frame, area: Rect, theme: &Theme) {
     let help_text = vec![
-        Line::from(vec![
-            Span::styled("Navigation", Style::default().add_modifier(Modifier::BOLD)),
-        ]),
+        Line::from(vec![Span::styled(
+            "Navigation",
+            Style::default().add_modifier(Modifier::BOLD),
+        )]),
         Line::raw("  j/Down    Move down"),
         Line::raw("  k/Up      Move up"),
         Line::raw("  h/Left    Collapse/parent"),
         Line::raw("  l/Right   Expand/select"),
+        Line::raw("  E/C       Expand all / collapse all"),
+        Line::raw("  gg/G      Top / bottom (5G: row 5)"),
+        Line::raw("  10j       Move ten rows"),
         Line::raw("  Tab       Switch pane"),
-        Line::raw("  /         Search"),
+        Line::raw("  </>       Narrow/widen tree pane"),
+        Line::raw("  /         Search (value pane: find in value)"),
+        Line::raw("  n/N       Next/previous match (value pane)"),
+        Line::raw("  f         Set SCAN pattern"),
+        Line::raw("  *         Pattern from selected path"),
+        Line::raw("  F         Cycle type filter"),
+        Line::raw("  ^f        Filter: type:hash *:session:*"),
+        Line::raw("  o         Go to key by full name"),
         Line::raw(""),
-        Line::from(vec![
-            Span::styled("Actions", Style::default().add_modifier(Modifier::BOLD)),
-        ]),
+        Line::from(vec![Span::styled(
+            "Actions",
+            Style::default().add_modifier(Modifier::BOLD),
+        )]),
         Line::raw("  e         Edit value"),
+        Line::raw("  i         Import value from file"),
         Line::raw("  r         Refresh"),
-        Line::raw("  d         Delete"),
-        Line::raw("  y         Copy key"),
+        Line::raw("  p         Pin/unpin value pane"),
+        Line::raw("  b         Toggle base64 decode (value pane)"),
+        Line::raw("  L         Toggle line numbers"),
+        Line::raw("  w         Toggle wrap (off: h/l scroll sideways)"),
+        Line::raw("  S         Sort set/hash/zset members by name"),
+        Line::raw("  X         Load a truncated value in full"),
+        Line::raw("  e/d       Edit/remove element (value pane)"),
+        Line::raw("  d/dd      Delete key or folder (3dd: three rows)"),
+        Line::raw("  D         Delete folder, even one that is a key"),
+        Line::raw("  a         Create key (string or collection)"),
+        Line::raw("  t         Set/clear TTL"),
+        Line::raw("  n         Rename key"),
+        Line::raw("  c         Copy key (any type, keeps TTL)"),
+        Line::raw("  s         Switch database"),
+        Line::raw("  P         Monitor Pub/Sub channel"),
+        Line::raw("  I         Server info"),
+        Line::raw("  m         Action menu"),
+        Line::raw("  :/^p      Command palette"),
+        Line::raw("  y         Copy key/folder prefix"),
+        Line::raw("  Y         Copy value"),
         Line::raw("  q         Quit"),
         Line::raw(""),
         Line::styled("Press Esc to close", Style::default().fg(Color::DarkGray)),
@@ -91,14 +431,23 @@ fn render_confirm(
     title: &str,
     message: &str,
     confirm_text: &str,
-    _theme: &Theme,
+    input: &str,
+    theme: &Theme,
 ) {
     let lines = vec![
         Line::raw(""),
         Line::raw(message),
         Line::raw(""),
+        Line::from(vec![
+            Span::raw("> "),
+            Span::styled(format!("{}█", input), theme.title),
+        ]),
+        Line::raw(""),
         Line::styled(
-            format!("Type '{}' to confirm, Esc to cancel", confirm_text),
+            format!(
+                "Type '{}' and press Enter to confirm, Esc to cancel",
+                confirm_text
+            ),
             Style::default().fg(Color::DarkGray),
         ),
     ];
@@ -109,7 +458,11 @@ fn render_confirm(
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Color::Yellow))
                 .title(format!(" {} ", title))
-                .title_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                .title_style(
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
         )
         .alignment(Alignment::Center);
 
@@ -119,18 +472,33 @@ fn render_confirm(
 fn render_protection(
     frame: &mut Frame,
     area: Rect,
+    key: &str,
     namespace: &str,
     level: ProtectionLevel,
-    _theme: &Theme,
+    input: &str,
+    theme: &Theme,
 ) {
     let (color, level_str, action) = match level {
-        ProtectionLevel::Warn => (Color::Yellow, "WARN", "Press any key to continue, Esc to cancel"),
-        ProtectionLevel::Confirm => (Color::Red, "CONFIRM", "Type 'yes' to confirm, Esc to cancel"),
-        ProtectionLevel::Block => (Color::Red, "BLOCKED", "This operation is not allowed. Press Esc to close"),
+        ProtectionLevel::Warn => (
+            Color::Yellow,
+            "WARN",
+            "Press Enter or y to continue, any other key to cancel",
+        ),
+        ProtectionLevel::Confirm => (
+            Color::Red,
+            "CONFIRM",
+            "Type 'yes' and press Enter to continue, Esc to cancel",
+        ),
+        ProtectionLevel::Block => (
+            Color::Red,
+            "BLOCKED",
+            "This operation is not allowed. Press Esc to close",
+        ),
     };
 
-    let lines = vec![
+    let mut lines = vec![
         Line::raw(""),
+        Line::raw(key),
         Line::styled(
             format!("Protected namespace: {}", namespace),
             Style::default().fg(color),
@@ -140,8 +508,15 @@ fn render_protection(
             Style::default().fg(color).add_modifier(Modifier::BOLD),
         ),
         Line::raw(""),
-        Line::styled(action, Style::default().fg(Color::DarkGray)),
     ];
+    if level == ProtectionLevel::Confirm {
+        lines.push(Line::from(vec![
+            Span::raw("> "),
+            Span::styled(format!("{}█", input), theme.title),
+        ]));
+        lines.push(Line::raw(""));
+    }
+    lines.push(Line::styled(action, Style::default().fg(Color::DarkGray)));
 
     let paragraph = Paragraph::new(lines)
         .block(
@@ -156,74 +531,582 @@ fn render_protection(
     frame.render_widget(paragraph, area);
 }
 
+/// Line diff of `old_value` against `new_value`: unchanged lines stay
+/// plain, removed lines are red with `- `, added lines green with `+ `.
+/// Within a changed line the words that actually differ are reversed.
+pub fn diff_lines(old_value: &str, new_value: &str) -> Vec<Line<'static>> {
+    let diff = TextDiff::from_lines(old_value, new_value);
+    let mut lines = Vec::new();
+
+    for op in diff.ops() {
+        for change in diff.iter_inline_changes(op) {
+            let (prefix, style) = match change.tag() {
+                ChangeTag::Equal => ("  ", Style::default()),
+                ChangeTag::Delete => ("- ", Style::default().fg(Color::Red)),
+                ChangeTag::Insert => ("+ ", Style::default().fg(Color::Green)),
+            };
+            let mut spans = vec![Span::styled(prefix, style)];
+            for (emphasized, text) in change.iter_strings_lossy() {
+                let text = text.trim_end_matches(['\n', '\r']).to_string();
+                if emphasized {
+                    spans.push(Span::styled(text, style.add_modifier(Modifier::REVERSED)));
+                } else {
+                    spans.push(Span::styled(text, style));
+                }
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+
+    lines
+}
+
+#[allow(clippy::too_many_arguments)]
 fn render_diff_preview(
     frame: &mut Frame,
     area: Rect,
     key: &str,
-    old_value: &str,
-    new_value: &str,
+    lines: &[Line<'static>],
+    wrap: bool,
+    scroll: usize,
+    confirm_discard: bool,
     theme: &Theme,
 ) {
-    // Simple line-by-line diff
-    let old_lines: Vec<&str> = old_value.lines().collect();
-    let new_lines: Vec<&str> = new_value.lines().collect();
+    let total = lines.len();
+    let height = diff_page_height(area);
+    let start = scroll.min(total.saturating_sub(height));
+    let end = (start + height).min(total);
 
-    let mut diff_lines = Vec::new();
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .title(format!(" Confirm Changes to {} ", key))
+        .title_style(theme.title);
+    if total > height {
+        block = block.title(
+            Line::styled(format!(" {}–{} of {} ", start + 1, end, total), theme.title)
+                .right_aligned(),
+        );
+    }
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
 
-    let max_len = old_lines.len().max(new_lines.len());
-    for i in 0..max_len {
-        let old_line = old_lines.get(i).copied();
-        let new_line = new_lines.get(i).copied();
+    let [diff_area, footer_area] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(2)]).areas(inner);
+    let visible: Vec<Line> = lines[start..end].to_vec();
+    let mut diff = Paragraph::new(visible);
+    if wrap {
+        diff = diff.wrap(Wrap { trim: false });
+    }
+    frame.render_widget(diff, diff_area);
 
-        match (old_line, new_line) {
-            (Some(o), Some(n)) if o == n => {
-                diff_lines.push(Line::raw(format!("  {}", o)));
-            }
-            (Some(o), Some(n)) => {
-                diff_lines.push(Line::styled(
-                    format!("- {}", o),
-                    Style::default().fg(Color::Red),
-                ));
-                diff_lines.push(Line::styled(
-                    format!("+ {}", n),
-                    Style::default().fg(Color::Green),
-                ));
-            }
-            (Some(o), None) => {
-                diff_lines.push(Line::styled(
-                    format!("- {}", o),
-                    Style::default().fg(Color::Red),
+    let hint = if confirm_discard {
+        Line::styled(
+            "Discard your changes?    [y] Discard    [n/Esc] Keep reviewing",
+            Style::default().fg(Color::Yellow),
+        )
+    } else {
+        Line::styled(
+            "[j/k PgUp/PgDn] Scroll    [Enter] Write to Redis    [Esc] Cancel",
+            Style::default().fg(Color::DarkGray),
+        )
+    };
+    let footer = vec![Line::raw(""), hint];
+    frame.render_widget(Paragraph::new(footer), footer_area);
+}
+
+/// Number of diff lines the preview shows at once when drawn in `area`
+/// (the dialog rect): everything inside the border but the footer.
+fn diff_page_height(area: Rect) -> usize {
+    area.height.saturating_sub(4) as usize
+}
+
+/// Diff lines visible at once in the diff preview on a `screen`-sized
+/// terminal.
+pub fn diff_preview_height(screen: Rect) -> usize {
+    diff_page_height(centered_rect(60, 50, screen))
+}
+
+/// `INFO` sections listed first, in this order; the rest follow by name.
+const INFO_SECTIONS: &[&str] = &["Server", "Memory", "Clients", "Stats", "Replication"];
+
+/// Lines of the server info dialog: a few headline metrics, then every
+/// section's fields.
+pub fn server_info_lines(info: &ServerInfo) -> Vec<Line<'static>> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let metric = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let field = |section: &str, name: &str| {
+        info.get(section)
+            .and_then(|fields| fields.get(name))
+            .cloned()
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let hit_ratio = match keyspace_hit_ratio(info) {
+        Some(ratio) => format!("{:.1}%", ratio * 100.0),
+        None => "-".to_string(),
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw("  Used memory  "),
+            Span::styled(field("Memory", "used_memory_human"), metric),
+        ]),
+        Line::from(vec![
+            Span::raw("  Clients      "),
+            Span::styled(field("Clients", "connected_clients"), metric),
+        ]),
+        Line::from(vec![
+            Span::raw("  Hit ratio    "),
+            Span::styled(hit_ratio, metric),
+            Span::styled(
+                format!(
+                    " ({} hits, {} misses)",
+                    field("Stats", "keyspace_hits"),
+                    field("Stats", "keyspace_misses")
+                ),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]),
+    ];
+
+    let mut names: Vec<&String> = info.keys().filter(|name| !name.is_empty()).collect();
+    names.sort_by_key(|name| {
+        let rank = INFO_SECTIONS.iter().position(|s| s == name);
+        (rank.unwrap_or(INFO_SECTIONS.len()), name.to_string())
+    });
+    for name in names {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(name.clone(), bold));
+        for (key, value) in &info[name] {
+            lines.push(Line::raw(format!("  {:<32} {}", key, value)));
+        }
+    }
+    lines
+}
+
+fn render_server_info(
+    frame: &mut Frame,
+    area: Rect,
+    info: &ServerInfo,
+    scroll: usize,
+    theme: &Theme,
+) {
+    let lines = server_info_lines(info);
+    let total = lines.len();
+    let height = server_info_height(area);
+    let start = scroll.min(total.saturating_sub(height));
+    let end = (start + height).min(total);
+
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(theme.border)
+        .title(" Server Info ")
+        .title_style(theme.title);
+    if total > height {
+        block = block.title(
+            Line::styled(format!(" {}–{} of {} ", start + 1, end, total), theme.title)
+                .right_aligned(),
+        );
+    }
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [info_area, footer_area] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+    let visible: Vec<Line> = lines.into_iter().skip(start).take(height).collect();
+    frame.render_widget(Paragraph::new(visible), info_area);
+    frame.render_widget(
+        Paragraph::new(Line::styled(
+            "[j/k PgUp/PgDn] Scroll    [Esc] Close",
+            Style::default().fg(Color::DarkGray),
+        )),
+        footer_area,
+    );
+}
+
+/// Lines visible at once in the full-screen server info dialog on a
+/// `screen`-sized terminal: everything but the border and footer.
+pub fn server_info_height(screen: Rect) -> usize {
+    screen.height.saturating_sub(3) as usize
+}
+
+fn render_confirm_quit(frame: &mut Frame, area: Rect, theme: &Theme) {
+    let lines = vec![
+        Line::raw(""),
+        Line::raw("Quit redis-nav?"),
+        Line::raw(""),
+        Line::styled(
+            "[y/Enter] Quit    [n/Esc] Cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title(" Quit ")
+                .title_style(theme.title),
+        )
+        .alignment(Alignment::Center);
+
+    frame.render_widget(paragraph, area);
+}
+
+fn render_remove_element(
+    frame: &mut Frame,
+    area: Rect,
+    key: &str,
+    element: &ValueElement,
+    _theme: &Theme,
+) {
+    let lines = vec![
+        Line::raw(""),
+        Line::raw(format!("Remove {} from '{}'?", element.describe(), key)),
+        Line::raw(""),
+        Line::styled(
+            "[y/Enter] Remove    [n/Esc] Cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow))
+                .title(" Remove Element ")
+                .title_style(
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+        )
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+fn render_set_ttl(
+    frame: &mut Frame,
+    area: Rect,
+    key: &str,
+    current: i64,
+    input: &str,
+    theme: &Theme,
+) {
+    let current = if current < 0 {
+        "no expiry".to_string()
+    } else {
+        format!("{}s", current)
+    };
+
+    let lines = vec![
+        Line::raw(""),
+        Line::raw(format!("Current TTL: {}", current)),
+        Line::raw(""),
+        Line::from(vec![
+            Span::raw("Seconds (or 'persist'): "),
+            Span::styled(format!("{}█", input), theme.title),
+        ]),
+        Line::raw(""),
+        Line::styled(
+            "[Enter] Apply    [Esc] Cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title(format!(" Set TTL for {} ", key))
+                .title_style(theme.title),
+        )
+        .alignment(Alignment::Center);
+
+    frame.render_widget(paragraph, area);
+}
+
+fn render_import(frame: &mut Frame, area: Rect, key: &str, input: &str, theme: &Theme) {
+    let lines = vec![
+        Line::raw(""),
+        Line::raw(format!("Into: {}", key)),
+        Line::raw(""),
+        Line::from(vec![
+            Span::raw("File: "),
+            Span::styled(format!("{}█", input), theme.title),
+        ]),
+        Line::raw(""),
+        Line::styled(
+            "[Enter] Preview    [Esc] Cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title(" Import Value From File ")
+                .title_style(theme.title),
+        )
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Prompt for the new name of `old_key` when renaming or copying it;
+/// `verb` names the operation.
+fn render_new_name(
+    frame: &mut Frame,
+    area: Rect,
+    verb: &str,
+    old_key: &str,
+    input: &str,
+    theme: &Theme,
+) {
+    let lines = vec![
+        Line::raw(""),
+        Line::raw(format!("From: {}", old_key)),
+        Line::raw(""),
+        Line::from(vec![
+            Span::raw("To: "),
+            Span::styled(format!("{}█", input), theme.title),
+        ]),
+        Line::raw(""),
+        Line::styled(
+            format!("[Enter] {}    [Esc] Cancel", verb),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title(format!(" {} Key ", verb))
+                .title_style(theme.title),
+        )
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Member lines of a new collection shown at once in the create-key dialog.
+const MEMBER_LINES_SHOWN: usize = 6;
+
+/// One line of multi-line input, with the cursor at its end if `active`.
+fn input_line_plain(input: &str, active: bool, theme: &Theme) -> Line<'static> {
+    if active {
+        Line::styled(format!("{}█", input), theme.title)
+    } else {
+        Line::raw(input.to_string())
+    }
+}
+
+fn render_create_key(
+    frame: &mut Frame,
+    area: Rect,
+    key_type: RedisType,
+    name: &str,
+    value: &str,
+    field: CreateKeyField,
+    theme: &Theme,
+) {
+    let input_line = |label: &str, input: &str, active: bool| {
+        if active {
+            Line::from(vec![
+                Span::raw(format!("{}: ", label)),
+                Span::styled(format!("{}█", input), theme.title),
+            ])
+        } else {
+            Line::raw(format!("{}: {}", label, input))
+        }
+    };
+    let mut lines = vec![
+        Line::raw(""),
+        input_line("Key", name, field == CreateKeyField::Name),
+    ];
+    if field == CreateKeyField::Type {
+        lines.push(Line::from(vec![
+            Span::raw("Type: "),
+            Span::styled(format!("◀ {} ▶", key_type.as_str()), theme.title),
+        ]));
+    } else {
+        lines.push(Line::raw(format!("Type: {}", key_type.as_str())));
+    }
+
+    let active = field == CreateKeyField::Value;
+    let hint = match key_type {
+        RedisType::List | RedisType::Set => Some("Members, one per line:"),
+        RedisType::Hash => Some("Fields, one 'field: value' per line:"),
+        RedisType::ZSet => Some("Members, one 'score: member' per line:"),
+        _ => None,
+    };
+    match hint {
+        Some(hint) => {
+            lines.push(Line::raw(hint));
+            // Keep the line being typed in view
+            let members: Vec<&str> = value.split('\n').collect();
+            let shown = members.len().min(MEMBER_LINES_SHOWN);
+            if members.len() > shown {
+                lines.push(Line::styled(
+                    format!("… {} more", members.len() - shown),
+                    Style::default().fg(Color::DarkGray),
                 ));
             }
-            (None, Some(n)) => {
-                diff_lines.push(Line::styled(
-                    format!("+ {}", n),
-                    Style::default().fg(Color::Green),
-                ));
+            let last = members.len() - 1;
+            for (i, member) in members.iter().enumerate().skip(members.len() - shown) {
+                lines.push(input_line_plain(member, active && i == last, theme));
             }
-            (None, None) => {}
         }
+        None => lines.push(input_line("Value", value, active)),
     }
 
-    diff_lines.push(Line::raw(""));
-    diff_lines.push(Line::styled(
-        "[Enter] Write to Redis    [Esc] Cancel",
-        Style::default().fg(Color::DarkGray),
-    ));
+    let keys = match (field, hint) {
+        (CreateKeyField::Type, _) => "[←/→] Type    [Tab] Next field    [Esc] Cancel",
+        (_, Some(_)) => "[Tab] Next field    [Enter] New line, twice to create    [Esc] Cancel",
+        (_, None) => "[Tab] Next field    [Enter] Create    [^O] From file    [Esc] Cancel",
+    };
+    lines.push(Line::raw(""));
+    lines.push(Line::styled(keys, Style::default().fg(Color::DarkGray)));
 
-    let paragraph = Paragraph::new(diff_lines)
+    let paragraph = Paragraph::new(lines)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(theme.border)
-                .title(format!(" Confirm Changes to {} ", key))
+                .title(" Create Key ")
                 .title_style(theme.title),
         )
+        .alignment(Alignment::Center)
         .wrap(Wrap { trim: false });
 
     frame.render_widget(paragraph, area);
 }
 
+fn render_select_db(frame: &mut Frame, area: Rect, current: u8, input: &str, theme: &Theme) {
+    let lines = vec![
+        Line::raw(""),
+        Line::raw(format!("Current database: {}", current)),
+        Line::raw(""),
+        Line::from(vec![
+            Span::raw("Database (0-15): "),
+            Span::styled(format!("{}█", input), theme.title),
+        ]),
+        Line::raw(""),
+        Line::styled(
+            "[Enter] Switch    [Esc] Cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title(" Select Database ")
+                .title_style(theme.title),
+        )
+        .alignment(Alignment::Center);
+
+    frame.render_widget(paragraph, area);
+}
+
+fn render_context_menu(
+    frame: &mut Frame,
+    area: Rect,
+    title: &str,
+    actions: &[Action],
+    selected: usize,
+    theme: &Theme,
+) {
+    let items: Vec<ListItem> = actions
+        .iter()
+        .map(|action| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<4}", action.key_hint()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(action.label()),
+            ]))
+        })
+        .collect();
+
+    render_list(frame, area, title, items, selected, theme);
+}
+
+fn render_command_palette(
+    frame: &mut Frame,
+    area: Rect,
+    actions: &[Action],
+    input: &str,
+    selected: usize,
+    theme: &Theme,
+) {
+    let [input_area, list_area] =
+        Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(area);
+
+    let prompt = Paragraph::new(format!("{}█", input)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(" Command ")
+            .title_style(theme.title),
+    );
+    frame.render_widget(prompt, input_area);
+
+    let matches = Action::filter(actions, input);
+    if matches.is_empty() {
+        let empty = Paragraph::new("No matching actions")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border),
+            );
+        frame.render_widget(empty, list_area);
+        return;
+    }
+    let title = format!("{} actions", matches.len());
+    render_context_menu(frame, list_area, &title, &matches, selected, theme);
+}
+
+fn render_list(
+    frame: &mut Frame,
+    area: Rect,
+    title: &str,
+    items: Vec<ListItem>,
+    selected: usize,
+    theme: &Theme,
+) {
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title(format!(" {} ", title))
+                .title_style(theme.title),
+        )
+        .highlight_style(theme.tree_selected)
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let [area] = Layout::horizontal([Constraint::Percentage(percent_x)])
         .flex(Flex::Center)
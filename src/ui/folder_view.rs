@@ -0,0 +1,63 @@
+use crate::redis_client::key::display_key;
+use crate::tree::FolderStats;
+use crate::ui::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Frame;
+
+/// Counts for the selected folder, drawn in place of the value pane so a
+/// folder never shows the previous key's value.
+pub struct FolderView<'a> {
+    prefix: &'a str,
+    stats: &'a FolderStats,
+    theme: &'a Theme,
+}
+
+impl<'a> FolderView<'a> {
+    pub fn new(prefix: &'a str, stats: &'a FolderStats, theme: &'a Theme) -> Self {
+        Self {
+            prefix,
+            stats,
+            theme,
+        }
+    }
+
+    pub fn lines(&self) -> Vec<Line<'static>> {
+        let stats = self.stats;
+        // Unscanned subfolders may hold more keys
+        let more = if stats.complete { "" } else { "+" };
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Children: ", self.theme.tree_folder),
+                Span::raw(stats.children.to_string()),
+            ]),
+            Line::from(vec![
+                Span::styled("Keys:     ", self.theme.tree_folder),
+                Span::raw(format!("{}{}", stats.keys, more)),
+            ]),
+        ];
+        if !stats.types.is_empty() {
+            lines.push(Line::raw(""));
+        }
+        for (redis_type, count) in &stats.types {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:<8}", redis_type.as_str()), self.theme.tree_key),
+                Span::raw(count.to_string()),
+            ]));
+        }
+        lines
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let paragraph = Paragraph::new(self.lines()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.theme.border)
+                .title(format!(" Folder: {} ", display_key(self.prefix)))
+                .title_style(self.theme.title),
+        );
+
+        frame.render_widget(paragraph, area);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::config::mask_url;
+use crate::ui::info_bar::format_ttl;
+use crate::ui::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+use std::path::Path;
+use std::time::Duration;
+
+/// One-line connection summary shown above the panes.
+pub struct Header<'a> {
+    url: &'a str,
+    db: u8,
+    readonly: bool,
+    /// Connected to a replica, which is why the session is read-only
+    replica: bool,
+    profile: Option<&'a str>,
+    /// Config file the settings came from
+    config_file: Option<&'a Path>,
+    /// SSH jump host and whether the tunnel through it is still up
+    tunnel: Option<(&'a str, bool)>,
+    /// Time since the last auto-refresh scan finished
+    refreshed: Option<Duration>,
+    theme: &'a Theme,
+}
+
+impl<'a> Header<'a> {
+    pub fn new(
+        url: &'a str,
+        db: u8,
+        readonly: bool,
+        profile: Option<&'a str>,
+        theme: &'a Theme,
+    ) -> Self {
+        Self {
+            url,
+            db,
+            readonly,
+            replica: false,
+            profile,
+            config_file: None,
+            tunnel: None,
+            refreshed: None,
+            theme,
+        }
+    }
+
+    pub fn replica(mut self, replica: bool) -> Self {
+        self.replica = replica;
+        self
+    }
+
+    pub fn config_file(mut self, config_file: Option<&'a Path>) -> Self {
+        self.config_file = config_file;
+        self
+    }
+
+    pub fn tunnel(mut self, tunnel: Option<(&'a str, bool)>) -> Self {
+        self.tunnel = tunnel;
+        self
+    }
+
+    pub fn refreshed(mut self, refreshed: Option<Duration>) -> Self {
+        self.refreshed = refreshed;
+        self
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let separator = Span::styled(" │ ", self.theme.border);
+
+        let mode = if self.readonly {
+            Span::styled(
+                if self.replica { "RO [replica]" } else { "RO" },
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::styled("RW", Style::default().fg(Color::Green))
+        };
+
+        let mut spans = vec![
+            Span::styled(" redis-nav", self.theme.title),
+            separator.clone(),
+            Span::raw(mask_url(self.url)),
+            separator.clone(),
+            Span::raw(format!("db {}", self.db)),
+            separator.clone(),
+            mode,
+        ];
+        if let Some(profile) = self.profile {
+            spans.push(separator.clone());
+            spans.push(Span::raw(format!("profile: {}", profile)));
+        }
+        if let Some(elapsed) = self.refreshed {
+            spans.push(separator.clone());
+            spans.push(Span::raw(refresh_label(elapsed)));
+        }
+        if let Some((host, up)) = self.tunnel {
+            spans.push(separator.clone());
+            if up {
+                spans.push(Span::raw(format!("ssh: {}", host)));
+            } else {
+                spans.push(Span::styled(
+                    format!("ssh: {} (closed)", host),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+        }
+
+        // Last, so a narrow terminal cuts it off first
+        if let Some(path) = self.config_file {
+            spans.push(separator);
+            spans.push(Span::raw(format!("config: {}", path.display())));
+        }
+
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+}
+
+/// How long ago the tree was last refreshed, e.g. `⟳ 45s ago`.
+pub fn refresh_label(elapsed: Duration) -> String {
+    format!("⟳ {} ago", format_ttl(elapsed.as_secs() as i64))
+}
@@ -0,0 +1,41 @@
+use crate::config::IconSet;
+use crate::redis_client::RedisType;
+
+impl IconSet {
+    /// Marker before a folder's name, with its trailing space.
+    pub fn folder(self, expanded: bool, has_children: bool) -> &'static str {
+        match (self, expanded, has_children) {
+            (IconSet::Ascii, true, _) => "[-] ",
+            (IconSet::Ascii, false, true) => "[+] ",
+            (IconSet::Ascii, false, false) => "[ ] ",
+            (IconSet::Nerd, true, _) => "\u{f07c} ",
+            (IconSet::Nerd, false, true) => "\u{f07b} ",
+            (IconSet::Nerd, false, false) => "\u{f114} ",
+        }
+    }
+
+    /// Marker before a key's name, with its trailing space. ASCII keys are
+    /// indented to line up with folder names instead.
+    pub fn key(self, redis_type: Option<RedisType>) -> &'static str {
+        match self {
+            IconSet::Ascii => "    ",
+            IconSet::Nerd => self.type_icon(redis_type.unwrap_or(RedisType::Unknown)),
+        }
+    }
+
+    /// Glyph for `redis_type` with its trailing space, or nothing in ASCII.
+    pub fn type_icon(self, redis_type: RedisType) -> &'static str {
+        if self == IconSet::Ascii {
+            return "";
+        }
+        match redis_type {
+            RedisType::String => "\u{f031} ",
+            RedisType::List => "\u{f03a} ",
+            RedisType::Set => "\u{f1b3} ",
+            RedisType::ZSet => "\u{f161} ",
+            RedisType::Hash => "\u{f292} ",
+            RedisType::Stream => "\u{f09e} ",
+            RedisType::Unknown => "\u{f128} ",
+        }
+    }
+}
@@ -1,24 +1,35 @@
-use crate::redis_client::RedisType;
+use crate::config::IconSet;
+use crate::redis_client::{RedisType, ValuePage, ValueSize};
 use crate::ui::theme::Theme;
 use ratatui::layout::Rect;
-use ratatui::style::Style;
+use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
+use std::time::{Duration, SystemTime};
 
 pub struct InfoBar<'a> {
     key_type: Option<RedisType>,
     ttl: Option<i64>,
-    size: Option<usize>,
+    size: Option<ValueSize>,
+    page: Option<ValuePage>,
+    memory: Option<i64>,
+    encoding: Option<&'a str>,
+    /// Wall-clock time the TTL runs out
+    expires_at: Option<SystemTime>,
+    show_chars: bool,
     theme: &'a Theme,
     readonly: bool,
+    icons: IconSet,
 }
 
 impl<'a> InfoBar<'a> {
     pub fn new(
         key_type: Option<RedisType>,
         ttl: Option<i64>,
-        size: Option<usize>,
+        size: Option<ValueSize>,
+        page: Option<ValuePage>,
+        show_chars: bool,
         theme: &'a Theme,
         readonly: bool,
     ) -> Self {
@@ -26,8 +37,45 @@ impl<'a> InfoBar<'a> {
             key_type,
             ttl,
             size,
+            page,
+            memory: None,
+            encoding: None,
+            expires_at: None,
+            show_chars,
             theme,
             readonly,
+            icons: IconSet::Ascii,
+        }
+    }
+
+    /// Show a glyph from `icons` before the type name.
+    pub fn icons(mut self, icons: IconSet) -> Self {
+        self.icons = icons;
+        self
+    }
+
+    /// Server-reported memory usage in bytes (`MEMORY USAGE`).
+    pub fn memory(mut self, memory: Option<i64>) -> Self {
+        self.memory = memory;
+        self
+    }
+
+    /// Server-reported internal encoding (`OBJECT ENCODING`).
+    pub fn encoding(mut self, encoding: Option<&'a str>) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// When the TTL runs out, shown next to the countdown.
+    pub fn expires_at(mut self, expires_at: Option<SystemTime>) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+
+    fn type_icon(&self) -> &'static str {
+        match self.key_type {
+            Some(redis_type) => self.icons.type_icon(redis_type),
+            None => "",
         }
     }
 
@@ -42,22 +90,15 @@ impl<'a> InfoBar<'a> {
             Some(RedisType::Unknown) | None => "-",
         };
 
-        let ttl_span = match self.ttl {
-            Some(ttl) if ttl < 0 => Span::styled("no expiry", self.theme.ttl_normal),
-            Some(ttl) if ttl < 60 => {
-                Span::styled(format!("{}s", ttl), self.theme.ttl_critical)
-            }
-            Some(ttl) if ttl < 3600 => {
-                Span::styled(format!("{}m", ttl / 60), self.theme.ttl_warning)
-            }
-            Some(ttl) => Span::styled(format!("{}h", ttl / 3600), self.theme.ttl_normal),
-            None => Span::raw("-"),
-        };
+        let ttl_span = ttl_span(self.ttl, self.expires_at, self.theme);
 
         let size_str = match self.size {
-            Some(s) if s > 1024 * 1024 => format!("{:.1} MB", s as f64 / 1024.0 / 1024.0),
-            Some(s) if s > 1024 => format!("{:.1} KB", s as f64 / 1024.0),
-            Some(s) => format!("{} B", s),
+            Some(size) => format_size(size, self.show_chars),
+            None => "-".to_string(),
+        };
+
+        let memory_str = match self.memory {
+            Some(bytes) => format_bytes(bytes.max(0) as usize),
             None => "-".to_string(),
         };
 
@@ -67,16 +108,40 @@ impl<'a> InfoBar<'a> {
             Span::styled(" [e]dit", Style::default())
         };
 
-        let line = Line::from(vec![
+        let mut spans = vec![
             Span::raw(" Type: "),
-            Span::styled(type_str, Style::default()),
+            Span::styled(
+                format!("{}{}", self.type_icon(), type_str),
+                Style::default(),
+            ),
             Span::raw(" | TTL: "),
             ttl_span,
             Span::raw(" | Size: "),
             Span::raw(size_str),
-            Span::raw(" |"),
-            edit_hint,
-        ]);
+            Span::raw(" | Memory: "),
+            Span::raw(memory_str),
+        ];
+        if let Some(encoding) = self.encoding {
+            spans.push(Span::raw(format!(" | Enc: {}", encoding)));
+        }
+        if let (Some(RedisType::Stream), Some(page)) = (self.key_type, self.page) {
+            spans.push(Span::raw(format!(
+                " | Entries: {}",
+                format_count(page.total)
+            )));
+        }
+        if let (Some(RedisType::Hash), Some(page)) = (self.key_type, self.page) {
+            spans.push(Span::raw(format!(
+                " | Fields: {}",
+                format_count(page.total)
+            )));
+        }
+        if let Some(page) = self.page.filter(|p| p.len < p.total) {
+            spans.push(Span::raw(format!(" | {}", format_page(page))));
+        }
+        spans.push(Span::raw(" |"));
+        spans.push(edit_hint);
+        let line = Line::from(spans);
 
         let paragraph = Paragraph::new(line).block(
             Block::default()
@@ -87,3 +152,138 @@ impl<'a> InfoBar<'a> {
         frame.render_widget(paragraph, area);
     }
 }
+
+/// A round-trip time for the status bar, e.g. `0.42ms`, `3.1ms`, `120ms`.
+pub fn format_latency(latency: Duration) -> String {
+    let ms = latency.as_secs_f64() * 1000.0;
+    if ms < 1.0 {
+        format!("{:.2}ms", ms)
+    } else if ms < 10.0 {
+        format!("{:.1}ms", ms)
+    } else {
+        format!("{:.0}ms", ms)
+    }
+}
+
+/// Green under 1ms, yellow under 10ms, red beyond.
+pub fn latency_color(latency: Duration) -> Color {
+    if latency < Duration::from_millis(1) {
+        Color::Green
+    } else if latency < Duration::from_millis(10) {
+        Color::Yellow
+    } else {
+        Color::Red
+    }
+}
+
+/// `TTL` reply for a key that does not exist; `-1` means no expiry.
+pub const TTL_MISSING: i64 = -2;
+
+/// The info bar's TTL field: the countdown colored by how soon it runs
+/// out, `no expiry` for -1, and `missing` for a key deleted since it was
+/// read (-2).
+pub fn ttl_span(ttl: Option<i64>, expires_at: Option<SystemTime>, theme: &Theme) -> Span<'static> {
+    let text = |ttl| match expires_at {
+        Some(at) => format_expiry(ttl, at),
+        None => format_ttl(ttl),
+    };
+    match ttl {
+        Some(TTL_MISSING) => Span::styled("missing", theme.ttl_critical),
+        Some(ttl) if ttl < 0 => Span::styled("no expiry", theme.ttl_normal),
+        Some(0) => Span::styled("expired", theme.ttl_critical),
+        Some(ttl) if ttl < 60 => Span::styled(text(ttl), theme.ttl_critical),
+        Some(ttl) if ttl < 3600 => Span::styled(text(ttl), theme.ttl_warning),
+        Some(ttl) => Span::styled(text(ttl), theme.ttl_normal),
+        None => Span::raw("-"),
+    }
+}
+
+/// A positive TTL with the wall-clock time it runs out, e.g.
+/// `expires 2024-06-01 14:22:10 UTC (in 5m 00s)`.
+pub fn format_expiry(ttl: i64, expires_at: SystemTime) -> String {
+    format!(
+        "expires {} (in {})",
+        format_utc(expires_at),
+        format_ttl(ttl)
+    )
+}
+
+/// `time` as `YYYY-MM-DD HH:MM:SS UTC`.
+pub fn format_utc(time: SystemTime) -> String {
+    let time = time::OffsetDateTime::from(time);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        time.year(),
+        u8::from(time.month()),
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second()
+    )
+}
+
+/// A positive TTL in seconds as its two largest units, e.g. `45s`,
+/// `4m 05s`, `2h 30m`, `3d 04h`.
+pub fn format_ttl(ttl: i64) -> String {
+    match ttl {
+        ..60 => format!("{}s", ttl),
+        60..3600 => format!("{}m {:02}s", ttl / 60, ttl % 60),
+        3600..86400 => format!("{}h {:02}m", ttl / 3600, ttl % 3600 / 60),
+        _ => format!("{}d {:02}h", ttl / 86400, ttl % 86400 / 3600),
+    }
+}
+
+/// Seconds left of a `ttl` read `elapsed` ago, bottoming out at 0 once
+/// it has run out. Negative TTLs (no expiry, missing key) stay as they are.
+pub fn remaining_ttl(ttl: i64, elapsed: Duration) -> i64 {
+    if ttl < 0 {
+        return ttl;
+    }
+    let elapsed = i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX);
+    ttl.saturating_sub(elapsed).max(0)
+}
+
+/// Human-readable byte size, optionally followed by the character count
+/// for text values (e.g. `1.2 KB / 1100 chars`).
+pub fn format_size(size: ValueSize, show_chars: bool) -> String {
+    let bytes = format_bytes(size.bytes);
+    match size.chars {
+        Some(chars) if show_chars => format!("{} / {} chars", bytes, chars),
+        _ => bytes,
+    }
+}
+
+/// Human-readable byte count, e.g. `512 B`, `1.2 KB`, `3.4 MB`.
+pub fn format_bytes(s: usize) -> String {
+    if s > 1024 * 1024 {
+        format!("{:.1} MB", s as f64 / 1024.0 / 1024.0)
+    } else if s > 1024 {
+        format!("{:.1} KB", s as f64 / 1024.0)
+    } else {
+        format!("{} B", s)
+    }
+}
+
+/// Describe the loaded window of a paged value, e.g.
+/// `showing 0–200 of 1,240,000`.
+pub fn format_page(page: ValuePage) -> String {
+    format!(
+        "showing {}–{} of {}",
+        format_count(page.offset),
+        format_count(page.offset + page.len),
+        format_count(page.total)
+    )
+}
+
+/// Format a count with thousands separators.
+pub fn format_count(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
@@ -1,37 +1,70 @@
+use crate::config::clamp_tree_percent;
 use ratatui::layout::{Constraint, Layout, Rect};
 
+/// How much `<` and `>` change the tree pane's share.
+pub const TREE_PERCENT_STEP: u16 = 5;
+
+#[derive(Debug, Clone, Copy)]
 pub struct AppLayout {
+    pub header_area: Rect,
     pub tree_area: Rect,
+    /// Path of the selected node, above the value pane
+    pub breadcrumb_area: Rect,
     pub value_area: Rect,
     pub info_area: Rect,
     pub status_area: Rect,
 }
 
 impl AppLayout {
-    pub fn new(area: Rect) -> Self {
-        let [main_area, status_area] = Layout::vertical([
+    /// Split `area`, giving the tree pane `tree_percent` of the width
+    /// (clamped to the allowed range).
+    pub fn new(area: Rect, tree_percent: u16) -> Self {
+        let [header_area, main_area, status_area] = Layout::vertical([
+            Constraint::Length(1),
             Constraint::Fill(1),
             Constraint::Length(1),
         ])
         .areas(area);
 
+        let tree_percent = clamp_tree_percent(tree_percent);
         let [tree_area, right_area] = Layout::horizontal([
-            Constraint::Percentage(30),
-            Constraint::Percentage(70),
+            Constraint::Percentage(tree_percent),
+            Constraint::Percentage(100 - tree_percent),
         ])
         .areas(main_area);
 
-        let [value_area, info_area] = Layout::vertical([
+        let [breadcrumb_area, value_area, info_area] = Layout::vertical([
+            Constraint::Length(1),
             Constraint::Fill(1),
             Constraint::Length(3),
         ])
         .areas(right_area);
 
         Self {
+            header_area,
             tree_area,
+            breadcrumb_area,
             value_area,
             info_area,
             status_area,
         }
     }
 }
+
+/// `scroll` limited so a pane `height` rows tall showing `rows` rows of
+/// content ends at the last row rather than past it.
+pub fn clamp_scroll(scroll: u16, rows: usize, height: usize) -> u16 {
+    let max = rows.saturating_sub(height);
+    scroll.min(u16::try_from(max).unwrap_or(u16::MAX))
+}
+
+/// List offset that keeps row `selected` on screen in a list `height`
+/// rows tall, moving `offset` as little as possible.
+pub fn visible_offset(offset: usize, selected: Option<usize>, height: usize) -> usize {
+    let height = height.max(1);
+    match selected {
+        Some(row) if row < offset => row,
+        Some(row) if row >= offset + height => row + 1 - height,
+        _ => offset,
+    }
+}
@@ -1,12 +1,52 @@
+pub mod breadcrumb;
 pub mod dialogs;
+pub mod folder_view;
+pub mod header;
+pub mod icons;
 pub mod info_bar;
 pub mod layout;
+pub mod monitor_view;
 pub mod theme;
 pub mod tree_view;
 pub mod value_view;
 
+use crate::ui::theme::Theme;
+use ratatui::layout::{Margin, Rect};
+use ratatui::widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState};
 use ratatui::Frame;
 
 pub trait Component {
     fn render(&self, frame: &mut Frame, area: ratatui::layout::Rect);
 }
+
+/// Draw a scrollbar over the right border of a bordered pane showing
+/// `content_length` rows from row `position`. Nothing is drawn when all
+/// rows fit.
+pub fn render_scrollbar(
+    frame: &mut Frame,
+    area: Rect,
+    content_length: usize,
+    position: usize,
+    theme: &Theme,
+) {
+    let viewport = area.height.saturating_sub(2) as usize;
+    if viewport == 0 || content_length <= viewport {
+        return;
+    }
+    // One scroll position per possible first visible row
+    let mut state = ScrollbarState::new(content_length - viewport + 1)
+        .position(position)
+        .viewport_content_length(viewport);
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .style(theme.border);
+    frame.render_stateful_widget(
+        scrollbar,
+        area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut state,
+    );
+}
@@ -0,0 +1,67 @@
+use crate::ui::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Frame;
+use std::collections::VecDeque;
+
+/// Live Pub/Sub messages, drawn in place of the value pane and following
+/// the newest message.
+pub struct MonitorView<'a> {
+    target: &'a str,
+    messages: &'a VecDeque<(String, String)>,
+    theme: &'a Theme,
+}
+
+impl<'a> MonitorView<'a> {
+    pub fn new(
+        target: &'a str,
+        messages: &'a VecDeque<(String, String)>,
+        theme: &'a Theme,
+    ) -> Self {
+        Self {
+            target,
+            messages,
+            theme,
+        }
+    }
+
+    /// The last `height` messages as `channel: payload` lines.
+    pub fn lines(&self, height: usize) -> Vec<Line<'static>> {
+        let skip = self.messages.len().saturating_sub(height);
+        self.messages
+            .iter()
+            .skip(skip)
+            .map(|(channel, payload)| {
+                Line::from(vec![
+                    Span::styled(channel.clone(), self.theme.tree_folder),
+                    Span::raw(": "),
+                    Span::raw(payload.clone()),
+                ])
+            })
+            .collect()
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let height = area.height.saturating_sub(2) as usize;
+        let lines = if self.messages.is_empty() {
+            vec![Line::raw("Waiting for messages... (Esc to stop)")]
+        } else {
+            self.lines(height)
+        };
+
+        let paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.theme.border)
+                .title(format!(
+                    " Pub/Sub: {} ({} messages) ",
+                    self.target,
+                    self.messages.len()
+                ))
+                .title_style(self.theme.title),
+        );
+
+        frame.render_widget(paragraph, area);
+    }
+}
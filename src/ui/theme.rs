@@ -1,5 +1,9 @@
+use crate::config::ThemeConfig;
 use ratatui::style::{Color, Modifier, Style};
 
+/// Names accepted by `defaults.theme`.
+pub const THEME_NAMES: &[&str] = &["dark", "light", "solarized"];
+
 pub struct Theme {
     pub tree_selected: Style,
     pub tree_folder: Style,
@@ -22,9 +26,7 @@ impl Default for Theme {
             tree_key: Style::default().fg(Color::White),
             ttl_normal: Style::default().fg(Color::Green),
             ttl_warning: Style::default().fg(Color::Yellow),
-            ttl_critical: Style::default()
-                .fg(Color::Red)
-                .add_modifier(Modifier::BOLD),
+            ttl_critical: Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
             border: Style::default().fg(Color::DarkGray),
             title: Style::default()
                 .fg(Color::White)
@@ -32,3 +34,114 @@ impl Default for Theme {
         }
     }
 }
+
+impl Theme {
+    /// A built-in theme by name, see [`THEME_NAMES`].
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::default()),
+            "light" => Some(Self::light()),
+            "solarized" => Some(Self::solarized()),
+            _ => None,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            tree_selected: Style::default()
+                .fg(Color::White)
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+            tree_folder: Style::default().fg(Color::Blue),
+            tree_key: Style::default().fg(Color::Black),
+            ttl_normal: Style::default().fg(Color::Green),
+            ttl_warning: Style::default().fg(Color::Rgb(0xb0, 0x70, 0x00)),
+            ttl_critical: Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            border: Style::default().fg(Color::Gray),
+            title: Style::default()
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        }
+    }
+
+    pub fn solarized() -> Self {
+        Self {
+            tree_selected: Style::default()
+                .fg(Color::Rgb(0x00, 0x2b, 0x36))
+                .bg(Color::Rgb(0x26, 0x8b, 0xd2))
+                .add_modifier(Modifier::BOLD),
+            tree_folder: Style::default().fg(Color::Rgb(0x26, 0x8b, 0xd2)),
+            tree_key: Style::default().fg(Color::Rgb(0x83, 0x94, 0x96)),
+            ttl_normal: Style::default().fg(Color::Rgb(0x85, 0x99, 0x00)),
+            ttl_warning: Style::default().fg(Color::Rgb(0xb5, 0x89, 0x00)),
+            ttl_critical: Style::default()
+                .fg(Color::Rgb(0xdc, 0x32, 0x2f))
+                .add_modifier(Modifier::BOLD),
+            border: Style::default().fg(Color::Rgb(0x58, 0x6e, 0x75)),
+            title: Style::default()
+                .fg(Color::Rgb(0x93, 0xa1, 0xa1))
+                .add_modifier(Modifier::BOLD),
+        }
+    }
+
+    /// Start from the named theme and apply the `[theme]` color overrides.
+    /// Unknown names and colors keep the default; see [`config_warnings`].
+    pub fn from_config(config: &ThemeConfig) -> Self {
+        let mut theme = config
+            .name
+            .as_deref()
+            .and_then(Self::named)
+            .unwrap_or_default();
+
+        for (role, value) in config.roles() {
+            let Some(color) = value.and_then(parse_color) else {
+                continue;
+            };
+            let style = match role {
+                "tree_selected" => {
+                    // The selection is a highlight bar, so the color is its background
+                    theme.tree_selected = theme.tree_selected.bg(color);
+                    continue;
+                }
+                "tree_folder" => &mut theme.tree_folder,
+                "tree_key" => &mut theme.tree_key,
+                "ttl_normal" => &mut theme.ttl_normal,
+                "ttl_warning" => &mut theme.ttl_warning,
+                "ttl_critical" => &mut theme.ttl_critical,
+                "border" => &mut theme.border,
+                "title" => &mut theme.title,
+                _ => continue,
+            };
+            *style = style.fg(color);
+        }
+
+        theme
+    }
+}
+
+/// Parse a color name (`red`, `lightblue`, `darkgray`), an ANSI index
+/// (`208`) or a hex value (`#rrggbb`).
+pub fn parse_color(value: &str) -> Option<Color> {
+    value.trim().parse().ok()
+}
+
+/// Problems in a theme config that [`Theme::from_config`] silently skips;
+/// startup fails on any of them, as on the rest of an invalid config file.
+pub fn config_warnings(config: &ThemeConfig) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if let Some(ref name) = config.name {
+        if Theme::named(name).is_none() {
+            warnings.push(format!(
+                "unknown theme '{}', expected one of: {}",
+                name,
+                THEME_NAMES.join(", ")
+            ));
+        }
+    }
+    for (role, value) in config.roles() {
+        if let Some(value) = value.filter(|v| parse_color(v).is_none()) {
+            warnings.push(format!("unknown color '{}' for theme.{}", value, role));
+        }
+    }
+    warnings
+}
@@ -1,21 +1,39 @@
-use crate::tree::TreeNode;
+use crate::config::IconSet;
+use crate::redis_client::key::display_key;
+use crate::redis_client::RedisType;
+use crate::tree::{expand_prefixes, expanded_prefixes, fuzzy_match, KeyFilter, TreeNode};
+use crate::ui::render_scrollbar;
 use crate::ui::theme::Theme;
-use ratatui::layout::Rect;
-use ratatui::style::Style;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
 use ratatui::Frame;
+use std::collections::{HashMap, HashSet};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 pub struct TreeView<'a> {
     #[allow(dead_code)]
     nodes: &'a [TreeNode],
     state: &'a mut TreeViewState,
     theme: &'a Theme,
+    placeholder: Option<Vec<String>>,
+    icons: IconSet,
+    /// Keys whose type lookup is still outstanding
+    pending: Option<&'a HashMap<String, usize>>,
 }
 
 pub struct TreeViewState {
     pub list_state: ListState,
     pub flattened: Vec<FlatNode>,
+    /// Search query; when set, only matching nodes and their ancestors are
+    /// flattened
+    pub filter: Option<String>,
+    /// Type and glob filter; when set, only matching keys and their
+    /// ancestors are flattened
+    pub key_filter: Option<KeyFilter>,
+    /// Number of keys and folders directly matching the filters
+    pub match_count: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -24,9 +42,91 @@ pub struct FlatNode {
     pub node_index: Vec<usize>, // Path to node in tree
     pub name: String,
     pub is_folder: bool,
+    /// A folder that is also a key of its own
+    pub has_own_key: bool,
     pub expanded: bool,
     pub child_count: usize,
+    /// False for a lazily loaded folder that has not been scanned yet
+    pub loaded: bool,
+    /// Key selected by this row: the leaf's key or a folder's own key
     pub full_key: Option<String>,
+    /// Type of `full_key`; `Unknown` while it is still being fetched
+    pub key_type: Option<RedisType>,
+    pub prefix: Option<String>,
+}
+
+impl FlatNode {
+    fn new(node: &TreeNode, depth: usize, node_index: Vec<usize>, expanded: bool) -> Self {
+        Self {
+            depth,
+            node_index,
+            name: node.name.clone(),
+            is_folder: node.is_folder(),
+            has_own_key: node.own_key.is_some(),
+            expanded,
+            child_count: node.child_count(),
+            loaded: node.loaded,
+            full_key: node.key().map(str::to_string),
+            key_type: node.key_type(),
+            prefix: node.prefix.clone(),
+        }
+    }
+
+    /// Whether this row stands for `key`: a key, or a folder's prefix.
+    fn selects(&self, key: &str) -> bool {
+        self.full_key.as_deref() == Some(key)
+            || (self.is_folder && self.prefix.as_deref() == Some(key))
+    }
+}
+
+/// Expanded folders and the selected row, remembered across a rebuild of
+/// the tree.
+#[derive(Debug, Default)]
+pub struct TreeSnapshot {
+    expanded: HashSet<String>,
+    /// Selected key (or folder prefix) and its row index
+    selected: Option<(String, usize)>,
+}
+
+impl TreeSnapshot {
+    /// A snapshot of a saved session: the prefixes of the folders to
+    /// expand and the key (or folder prefix) to select.
+    pub fn from_session(expanded: Vec<String>, selected: Option<String>) -> Self {
+        Self {
+            expanded: expanded.into_iter().collect(),
+            selected: selected.map(|key| (key, 0)),
+        }
+    }
+
+    /// Expanded folder prefixes, sorted.
+    pub fn expanded(&self) -> Vec<String> {
+        let mut expanded: Vec<String> = self.expanded.iter().cloned().collect();
+        expanded.sort();
+        expanded
+    }
+
+    /// The selected key or folder prefix.
+    pub fn selected(&self) -> Option<&str> {
+        self.selected.as_ref().map(|(key, _)| key.as_str())
+    }
+
+    /// Expanded folder prefixes, for [`TreeBuilder::expanding`].
+    ///
+    /// [`TreeBuilder::expanding`]: crate::tree::TreeBuilder::expanding
+    pub fn expanded_prefixes(&self) -> &HashSet<String> {
+        &self.expanded
+    }
+
+    /// Stop restoring the selection, e.g. once the user has moved.
+    pub fn forget_selection(&mut self) {
+        self.selected = None;
+    }
+}
+
+impl Default for TreeViewState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TreeViewState {
@@ -34,12 +134,22 @@ impl TreeViewState {
         Self {
             list_state: ListState::default(),
             flattened: Vec::new(),
+            filter: None,
+            key_filter: None,
+            match_count: 0,
         }
     }
 
     pub fn flatten(&mut self, nodes: &[TreeNode]) {
         self.flattened.clear();
-        self.flatten_recursive(nodes, 0, &mut vec![]);
+        self.match_count = 0;
+        let query = self.filter.clone().filter(|q| !q.is_empty());
+        let key_filter = self.key_filter.clone();
+        if query.is_none() && key_filter.is_none() {
+            self.flatten_recursive(nodes, 0, &mut vec![]);
+        } else {
+            self.flatten_filtered(nodes, 0, &mut vec![], query.as_deref(), key_filter.as_ref());
+        }
 
         if !self.flattened.is_empty() && self.list_state.selected().is_none() {
             self.list_state.select(Some(0));
@@ -50,15 +160,8 @@ impl TreeViewState {
         for (i, node) in nodes.iter().enumerate() {
             path.push(i);
 
-            self.flattened.push(FlatNode {
-                depth,
-                node_index: path.clone(),
-                name: node.name.clone(),
-                is_folder: node.is_folder(),
-                expanded: node.expanded,
-                child_count: node.child_count(),
-                full_key: node.full_key.clone(),
-            });
+            self.flattened
+                .push(FlatNode::new(node, depth, path.clone(), node.expanded));
 
             if node.expanded {
                 self.flatten_recursive(&node.children, depth + 1, path);
@@ -68,20 +171,230 @@ impl TreeViewState {
         }
     }
 
+    /// Flatten only nodes matching `query` and `key_filter` plus the
+    /// folders leading to them. A folder matching `query` is shown expanded
+    /// with all of its children, or with those passing `key_filter`.
+    /// Returns whether anything was added.
+    fn flatten_filtered(
+        &mut self,
+        nodes: &[TreeNode],
+        depth: usize,
+        path: &mut Vec<usize>,
+        query: Option<&str>,
+        key_filter: Option<&KeyFilter>,
+    ) -> bool {
+        let start = self.flattened.len();
+
+        for (i, node) in nodes.iter().enumerate() {
+            path.push(i);
+
+            let location = node.key().or(node.prefix.as_deref());
+            let query_match = query.is_none_or(|q| location.is_some_and(|l| fuzzy_match(l, q)));
+            let key_match = key_filter.is_none_or(|f| {
+                node.key()
+                    .zip(node.key_type())
+                    .is_some_and(|(key, t)| f.matches(key, t))
+            });
+            let is_match = query_match && key_match;
+            if is_match {
+                self.match_count += 1;
+            }
+
+            if is_match && key_filter.is_none() {
+                let show_children = !node.children.is_empty();
+                self.flattened
+                    .push(FlatNode::new(node, depth, path.clone(), show_children));
+                if show_children {
+                    self.flatten_recursive(&node.children, depth + 1, path);
+                }
+            } else if is_match || !node.children.is_empty() {
+                // Keep a folder only if it matches or something below it does
+                let idx = self.flattened.len();
+                self.flattened
+                    .push(FlatNode::new(node, depth, path.clone(), true));
+                let child_query = if query_match { None } else { query };
+                let shown = !node.children.is_empty()
+                    && self.flatten_filtered(
+                        &node.children,
+                        depth + 1,
+                        path,
+                        child_query,
+                        key_filter,
+                    );
+                if !shown {
+                    if is_match {
+                        self.flattened[idx].expanded = false;
+                    } else {
+                        self.flattened.truncate(idx);
+                    }
+                }
+            }
+
+            path.pop();
+        }
+
+        self.flattened.len() > start
+    }
+
+    /// Remember what is expanded and selected in `nodes`.
+    pub fn snapshot(&self, nodes: &[TreeNode]) -> TreeSnapshot {
+        let mut expanded = HashSet::new();
+        expanded_prefixes(nodes, &mut expanded);
+        TreeSnapshot {
+            expanded,
+            selected: self.selection(),
+        }
+    }
+
+    /// The selected key (or folder prefix) and its row index.
+    pub fn selection(&self) -> Option<(String, usize)> {
+        self.selected_key()
+            .or_else(|| self.selected_path())
+            .zip(self.list_state.selected())
+            .map(|(key, index)| (key.to_string(), index))
+    }
+
+    /// Re-expand the folders of `snapshot` in rebuilt `nodes`, flatten, and
+    /// select the previously selected row again.
+    pub fn restore(&mut self, nodes: &mut [TreeNode], snapshot: &TreeSnapshot) {
+        expand_prefixes(nodes, &snapshot.expanded);
+        self.flatten(nodes);
+        if let Some((key, index)) = &snapshot.selected {
+            self.reselect_key(key, *index);
+        }
+    }
+
+    /// Select `snapshot`'s row if it has been loaded. With `fallback`, a
+    /// row that is gone selects its old index instead.
+    pub fn restore_selection(&mut self, snapshot: &TreeSnapshot, fallback: bool) {
+        let Some((key, index)) = &snapshot.selected else {
+            return;
+        };
+        if fallback || self.flattened.iter().any(|n| n.selects(key)) {
+            self.reselect_key(key, *index);
+        }
+    }
+
+    /// Select the row for `key` (a key, or a folder's prefix). If it is gone,
+    /// select row `fallback` clamped into range instead.
+    pub fn reselect_key(&mut self, key: &str, fallback: usize) {
+        let found = self.flattened.iter().position(|n| n.selects(key));
+        let index = found.or_else(|| Some(fallback.min(self.flattened.len().checked_sub(1)?)));
+        self.list_state.select(index);
+    }
+
+    /// Index of the flattened node drawn at terminal row `y` when the tree
+    /// is rendered into `area`, accounting for the border and list scroll.
+    pub fn index_at(&self, area: Rect, y: u16) -> Option<usize> {
+        let top = area.y + 1;
+        let bottom = area.bottom().saturating_sub(1);
+        if y < top || y >= bottom {
+            return None;
+        }
+        let idx = self.list_state.offset() + (y - top) as usize;
+        (idx < self.flattened.len()).then_some(idx)
+    }
+
     pub fn selected_key(&self) -> Option<&str> {
         self.list_state
             .selected()
             .and_then(|i| self.flattened.get(i))
             .and_then(|n| n.full_key.as_deref())
     }
+
+    /// The selected node's location in the keyspace: the key prefix for
+    /// folders, the full key for leaves.
+    pub fn selected_path(&self) -> Option<&str> {
+        let node = self
+            .list_state
+            .selected()
+            .and_then(|i| self.flattened.get(i))?;
+        if node.is_folder {
+            node.prefix.as_deref()
+        } else {
+            node.full_key.as_deref()
+        }
+    }
+
+    /// The folder prefix the selection lives in, e.g. `user:1:` for both the
+    /// `user:1` folder and its `user:1:name` key. Leaves find their folder
+    /// through `node_index`; top-level keys have none.
+    pub fn selected_path_prefix(&self) -> Option<&str> {
+        let node = self
+            .list_state
+            .selected()
+            .and_then(|i| self.flattened.get(i))?;
+        if node.is_folder {
+            return node.prefix.as_deref();
+        }
+        let parent = node.node_index.split_last()?.1;
+        if parent.is_empty() {
+            return None;
+        }
+        self.flattened
+            .iter()
+            .find(|n| n.node_index == parent)
+            .and_then(|n| n.prefix.as_deref())
+    }
 }
 
 impl<'a> TreeView<'a> {
-    pub fn new(nodes: &'a [TreeNode], state: &'a mut TreeViewState, theme: &'a Theme) -> Self {
-        Self { nodes, state, theme }
+    pub fn new(
+        nodes: &'a [TreeNode],
+        state: &'a mut TreeViewState,
+        theme: &'a Theme,
+        placeholder: Option<Vec<String>>,
+    ) -> Self {
+        Self {
+            nodes,
+            state,
+            theme,
+            placeholder,
+            icons: IconSet::Ascii,
+            pending: None,
+        }
+    }
+
+    /// Draw folder and key markers from `icons`.
+    pub fn icons(mut self, icons: IconSet) -> Self {
+        self.icons = icons;
+        self
+    }
+
+    /// Mark only keys in `pending` as loading; others typed `Unknown` are
+    /// shown as is. Without it every `Unknown` key is treated as loading.
+    pub fn pending(mut self, pending: &'a HashMap<String, usize>) -> Self {
+        self.pending = Some(pending);
+        self
     }
 
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.border)
+            .title(" Keys ")
+            .title_style(self.theme.title);
+
+        if let Some(ref placeholder) = self.placeholder {
+            let inner = block.inner(area);
+            frame.render_widget(block, area);
+
+            let lines: Vec<Line> = placeholder.iter().map(|l| Line::raw(l.clone())).collect();
+            let top = inner.height.saturating_sub(lines.len() as u16) / 2;
+            let text_area = Rect {
+                y: inner.y + top,
+                height: inner.height - top,
+                ..inner
+            };
+            let paragraph = Paragraph::new(lines)
+                .style(Style::default().fg(Color::DarkGray))
+                .alignment(Alignment::Center);
+            frame.render_widget(paragraph, text_area);
+            return;
+        }
+
+        // Inside the borders and the "> " highlight symbol
+        let width = area.width.saturating_sub(4) as usize;
         let items: Vec<ListItem> = self
             .state
             .flattened
@@ -89,15 +402,10 @@ impl<'a> TreeView<'a> {
             .map(|node| {
                 let indent = "  ".repeat(node.depth);
                 let icon = if node.is_folder {
-                    if node.expanded {
-                        "[-] "
-                    } else if node.child_count > 0 {
-                        "[+] "
-                    } else {
-                        "[ ] "
-                    }
+                    let has_children = node.child_count > 0 || !node.loaded;
+                    self.icons.folder(node.expanded, has_children)
                 } else {
-                    "    "
+                    self.icons.key(node.key_type)
                 };
 
                 let suffix = if node.is_folder && node.child_count > 0 {
@@ -106,32 +414,81 @@ impl<'a> TreeView<'a> {
                     String::new()
                 };
 
+                let pending = node.key_type == Some(RedisType::Unknown)
+                    && match (self.pending, &node.full_key) {
+                        (Some(pending), Some(key)) => pending.contains_key(key),
+                        _ => true,
+                    };
                 let style = if node.is_folder {
                     self.theme.tree_folder
+                } else if pending {
+                    Style::default()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::ITALIC)
                 } else {
                     self.theme.tree_key
                 };
 
-                ListItem::new(Line::from(vec![
+                let marker = if node.has_own_key { " •" } else { "" };
+                let loading = if pending { " (loading type…)" } else { "" };
+                // The name gives way to everything else on the row; the
+                // full name shows in the value pane title when selected
+                let fixed =
+                    indent.len() + icon.width() + marker.width() + suffix.len() + loading.width();
+                let name = truncate_to_width(&display_key(&node.name), width.saturating_sub(fixed));
+
+                let mut spans = vec![
                     Span::raw(indent),
                     Span::styled(icon, style),
-                    Span::styled(node.name.clone(), style),
-                    Span::styled(suffix, Style::default()),
-                ]))
+                    Span::styled(name, style),
+                ];
+                if node.has_own_key {
+                    // The folder name is itself a key
+                    spans.push(Span::styled(marker, self.theme.tree_key));
+                }
+                spans.push(Span::styled(suffix, Style::default()));
+                if pending {
+                    spans.push(Span::styled(loading, Style::default().fg(Color::DarkGray)));
+                }
+                ListItem::new(Line::from(spans))
             })
             .collect();
 
         let list = List::new(items)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_style(self.theme.border)
-                    .title(" Keys ")
-                    .title_style(self.theme.title),
-            )
+            .block(block)
             .highlight_style(self.theme.tree_selected)
             .highlight_symbol("> ");
 
         frame.render_stateful_widget(list, area, &mut self.state.list_state);
+        render_scrollbar(
+            frame,
+            area,
+            self.state.flattened.len(),
+            self.state.list_state.offset(),
+            self.theme,
+        );
+    }
+}
+
+/// `text` cut to at most `width` terminal columns, ending in `…` when
+/// anything was cut. Wide characters such as CJK count as two columns.
+pub fn truncate_to_width(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w >= width {
+            break;
+        }
+        used += w;
+        truncated.push(c);
     }
+    truncated.push('…');
+    truncated
 }
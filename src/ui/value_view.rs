@@ -1,16 +1,133 @@
-use crate::format::{detect_format, format_as_hex, highlight_json, pretty_json, DetectedFormat};
+use crate::format::{
+    decode_base64, decode_msgpack, decode_timestamp, decompress, detect_format, format_as_hex,
+    highlight_json, highlight_xml, highlight_yaml, pretty_html, pretty_json, pretty_xml,
+    pretty_yaml, DetectedFormat,
+};
+use crate::redis_client::key::display_key;
 use crate::redis_client::RedisValue;
+use crate::ui::info_bar::format_count;
+use crate::ui::render_scrollbar;
 use crate::ui::theme::Theme;
 use ratatui::layout::Rect;
-use ratatui::text::Line;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 use ratatui::Frame;
+use std::ops::Range;
+
+/// A string value's lines as rendered for its format, and the format label.
+pub type StringLines = (Vec<Line<'static>>, String);
+
+/// A text search within the rendered value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValueSearch {
+    pub query: String,
+    pub case_sensitive: bool,
+    /// Position of the current match among the matching lines
+    pub current: usize,
+}
+
+impl ValueSearch {
+    /// Byte ranges of the query within `text`. Case-insensitive search only
+    /// folds ASCII letters, so the ranges are valid offsets into `text`.
+    pub fn find_in(&self, text: &str) -> Vec<Range<usize>> {
+        if self.query.is_empty() {
+            return Vec::new();
+        }
+        let (haystack, needle) = if self.case_sensitive {
+            (text.to_string(), self.query.clone())
+        } else {
+            (text.to_ascii_lowercase(), self.query.to_ascii_lowercase())
+        };
+        haystack
+            .match_indices(&needle)
+            .map(|(i, _)| i..i + needle.len())
+            .collect()
+    }
+
+    /// Indexes of the lines holding at least one match.
+    pub fn matching_lines(&self, lines: &[Line]) -> Vec<usize> {
+        lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| !self.find_in(&line.to_string()).is_empty())
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Patch `style` onto the parts of `line` covered by `ranges`, given as byte
+/// offsets into the line's text, splitting spans where a range starts or
+/// ends inside them.
+pub fn highlight_ranges(
+    line: Line<'static>,
+    ranges: &[Range<usize>],
+    style: Style,
+) -> Line<'static> {
+    if ranges.is_empty() {
+        return line;
+    }
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    for span in &line.spans {
+        let text = span.content.as_ref();
+        let end = pos + text.len();
+        let mut bounds: Vec<usize> = ranges
+            .iter()
+            .flat_map(|r| [r.start, r.end])
+            .filter(|&b| b > pos && b < end)
+            .collect();
+        bounds.push(end);
+        bounds.sort_unstable();
+        bounds.dedup();
+
+        let mut cut = pos;
+        for bound in bounds {
+            let inside = ranges.iter().any(|r| r.start <= cut && cut < r.end);
+            let piece_style = if inside {
+                span.style.patch(style)
+            } else {
+                span.style
+            };
+            spans.push(Span::styled(
+                text[cut - pos..bound - pos].to_string(),
+                piece_style,
+            ));
+            cut = bound;
+        }
+        pos = end;
+    }
+    Line {
+        spans,
+        style: line.style,
+        alignment: line.alignment,
+    }
+}
 
 pub struct ValueView<'a> {
     value: Option<&'a RedisValue>,
     key: Option<&'a str>,
     theme: &'a Theme,
     scroll: u16,
+    pinned: bool,
+    base64_decoded: bool,
+    selected_element: Option<usize>,
+    offset: usize,
+    search: Option<&'a ValueSearch>,
+    line_numbers: bool,
+    /// Full byte length of a string value loaded only in part
+    truncated: Option<usize>,
+    /// Wrap long lines; when off, `hscroll` columns are scrolled past
+    wrap: bool,
+    hscroll: u16,
+    /// Decimal places for sorted set scores; `None` shows them exactly
+    score_precision: Option<usize>,
+    /// Size of the whole collection when only a page of it is loaded
+    total: Option<usize>,
+    /// Members were sorted by name rather than kept in server order
+    sorted: bool,
+    /// The string value already rendered by [`render_string`]
+    rendered: Option<&'a StringLines>,
 }
 
 impl<'a> ValueView<'a> {
@@ -19,79 +136,416 @@ impl<'a> ValueView<'a> {
         key: Option<&'a str>,
         theme: &'a Theme,
         scroll: u16,
+        pinned: bool,
+        base64_decoded: bool,
+        selected_element: Option<usize>,
     ) -> Self {
         Self {
             value,
             key,
             theme,
             scroll,
+            pinned,
+            base64_decoded,
+            selected_element,
+            offset: 0,
+            search: None,
+            line_numbers: false,
+            truncated: None,
+            wrap: true,
+            hscroll: 0,
+            score_precision: None,
+            total: None,
+            sorted: false,
+            rendered: None,
         }
     }
 
-    pub fn render(&self, frame: &mut Frame, area: Rect) {
-        let (lines, format_name) = match self.value {
-            Some(RedisValue::String(s)) => {
-                let format = detect_format(s.as_bytes());
-                let lines = match format {
-                    DetectedFormat::Json => {
-                        if let Ok(pretty) = pretty_json(s) {
-                            highlight_json(&pretty)
-                        } else {
-                            vec![Line::raw(s.clone())]
-                        }
-                    }
-                    DetectedFormat::Binary => format_as_hex(s.as_bytes()),
-                    _ => s.lines().map(|l| Line::raw(l.to_string())).collect(),
-                };
-                (lines, format_label(format))
-            }
+    /// Index of the first element shown, for a paged collection value.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Highlight the matches of an active search.
+    pub fn search(mut self, search: Option<&'a ValueSearch>) -> Self {
+        self.search = search;
+        self
+    }
+
+    /// Mark the string value as the first part of a `total`-byte value.
+    pub fn truncated(mut self, total: Option<usize>) -> Self {
+        self.truncated = total;
+        self
+    }
+
+    /// Wrap long lines (the default), or cut them off and scroll
+    /// sideways by `hscroll` columns.
+    pub fn wrap(mut self, wrap: bool, hscroll: u16) -> Self {
+        self.wrap = wrap;
+        self.hscroll = hscroll;
+        self
+    }
+
+    /// Show a line number gutter, except for hex dumps (which show offsets).
+    pub fn line_numbers(mut self, enabled: bool) -> Self {
+        self.line_numbers = enabled;
+        self
+    }
+
+    /// Show sorted set scores with this many decimals instead of exactly.
+    pub fn score_precision(mut self, precision: Option<usize>) -> Self {
+        self.score_precision = precision;
+        self
+    }
+
+    /// Count a paged collection's members out of `total` in the title, and
+    /// say whether they were sorted by name.
+    pub fn members(mut self, total: Option<usize>, sorted: bool) -> Self {
+        self.total = total;
+        self.sorted = sorted;
+        self
+    }
+
+    /// Show a string value as already rendered by [`render_string`],
+    /// instead of decoding it again.
+    pub fn rendered(mut self, rendered: Option<&'a StringLines>) -> Self {
+        self.rendered = rendered;
+        self
+    }
+
+    /// The value's lines as rendered, and a label for its format.
+    pub fn lines(&self) -> (Vec<Line<'static>>, String) {
+        match self.value {
+            Some(RedisValue::String(s)) => self.render_string(s.as_bytes()),
+            Some(RedisValue::Bytes(b)) => self.render_string(b),
             Some(RedisValue::List(items)) => {
                 let lines: Vec<Line> = items
                     .iter()
                     .enumerate()
-                    .map(|(i, item)| Line::raw(format!("[{}] {}", i, item)))
+                    .map(|(i, item)| Line::raw(format!("[{}] {}", self.offset + i, item)))
                     .collect();
-                (lines, "LIST")
+                (lines, "LIST".to_string())
             }
             Some(RedisValue::Set(items)) => {
                 let lines: Vec<Line> = items.iter().map(|item| Line::raw(item.clone())).collect();
-                (lines, "SET")
+                (lines, "SET".to_string())
             }
             Some(RedisValue::ZSet(items)) => {
                 let lines: Vec<Line> = items
                     .iter()
-                    .map(|(member, score)| Line::raw(format!("{:.2}: {}", score, member)))
+                    .map(|(member, score)| {
+                        let score = format_score(*score, self.score_precision);
+                        Line::raw(format!("{}: {}", score, member))
+                    })
                     .collect();
-                (lines, "ZSET")
+                (lines, "ZSET".to_string())
             }
             Some(RedisValue::Hash(items)) => {
                 let lines: Vec<Line> = items
                     .iter()
                     .map(|(k, v)| Line::raw(format!("{}: {}", k, v)))
                     .collect();
-                (lines, "HASH")
+                (lines, "HASH".to_string())
+            }
+            Some(RedisValue::Stream(entries)) => {
+                let mut lines = Vec::new();
+                for (id, fields) in entries {
+                    lines.push(Line::styled(id.clone(), self.theme.title));
+                    for (field, value) in fields {
+                        lines.push(Line::raw(format!("    {}: {}", field, value)));
+                    }
+                }
+                (lines, "STREAM".to_string())
             }
-            _ => (vec![Line::raw("Select a key to view its value")], ""),
+            _ => (
+                vec![Line::raw("Select a key to view its value")],
+                String::new(),
+            ),
+        }
+    }
+
+    /// The rendered value as plain text, or `None` with no value loaded.
+    pub fn text(&self) -> Option<String> {
+        self.value?;
+        let (lines, _) = self.lines();
+        let text: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        Some(text.join("\n"))
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let (lines, title) = self.display();
+        let rows = self.rows(&lines, area.width.saturating_sub(2));
+
+        let mut paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.theme.border)
+                .title(title)
+                .title_style(self.theme.title),
+        );
+        paragraph = if self.wrap {
+            paragraph
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0))
+        } else {
+            paragraph.scroll((self.scroll, self.hscroll))
         };
 
-        let title = match self.key {
-            Some(k) if !format_name.is_empty() => format!(" {} ({}) ", k, format_name),
-            Some(k) => format!(" {} ", k),
+        frame.render_widget(paragraph, area);
+        render_scrollbar(frame, area, rows, self.scroll as usize, self.theme);
+    }
+
+    /// Rows the value takes on screen when wrapped to `width` columns,
+    /// including the line number gutter and timestamp line.
+    pub fn line_count(&self, width: u16) -> usize {
+        self.rows(&self.display().0, width)
+    }
+
+    /// First screen row of `line` when wrapped to `width` columns, the
+    /// scroll offset that brings it to the top.
+    pub fn row_of_line(&self, line: usize, width: u16) -> usize {
+        let lines = self.display().0;
+        self.rows(&lines[..line.min(lines.len())], width)
+    }
+
+    /// Width of the longest line as drawn, the limit for scrolling
+    /// sideways.
+    pub fn max_width(&self) -> usize {
+        self.display().0.iter().map(Line::width).max().unwrap_or(0)
+    }
+
+    fn rows(&self, lines: &[Line], width: u16) -> usize {
+        if self.wrap {
+            wrapped_rows(lines, width)
+        } else {
+            lines.len()
+        }
+    }
+
+    /// Lines as drawn, with selection, search highlights, gutter and
+    /// timestamp applied, plus the pane title.
+    fn display(&self) -> (Vec<Line<'static>>, String) {
+        let (mut lines, format_name) = self.lines();
+
+        if let Some(line) = self.selected_element.and_then(|i| lines.get_mut(i)) {
+            line.style = self.theme.tree_selected;
+        }
+
+        let mut search_status = None;
+        if let Some(search) = self.search.filter(|s| !s.query.is_empty()) {
+            let matches = search.matching_lines(&lines);
+            let current = matches.get(search.current).copied();
+            for &i in &matches {
+                let ranges = search.find_in(&lines[i].to_string());
+                let mut style = Style::default().add_modifier(Modifier::REVERSED);
+                if Some(i) == current {
+                    style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                }
+                lines[i] = highlight_ranges(std::mem::take(&mut lines[i]), &ranges, style);
+            }
+            search_status = Some(match current {
+                Some(_) => format!(
+                    "/{} {}/{} ",
+                    search.query,
+                    search.current + 1,
+                    matches.len()
+                ),
+                None => format!("/{} no matches ", search.query),
+            });
+        }
+
+        if self.line_numbers && self.value.is_some() && !is_hex_dump(&format_name) {
+            add_line_numbers(&mut lines, self.theme.border);
+        }
+
+        // Shown only on screen, so copying the value still gives the number
+        if let Some(time) = self.timestamp() {
+            lines.push(Line::styled(format!("→ {}", time), self.theme.border));
+        }
+        if let Some(total) = self.truncated {
+            // Just the length; `size` would detect the format again
+            let shown = match self.value {
+                Some(RedisValue::String(s)) => s.len(),
+                Some(RedisValue::Bytes(b)) => b.len(),
+                _ => 0,
+            };
+            lines.push(Line::styled(
+                format!(
+                    "… (truncated: {} of {} bytes shown, press X to load full)",
+                    shown, total
+                ),
+                self.theme.ttl_warning,
+            ));
+        }
+
+        let mut title = match self.key {
+            Some(k) if !format_name.is_empty() => match self.member_summary() {
+                Some(summary) => format!(" {} ({}, {}) ", display_key(k), format_name, summary),
+                None => format!(" {} ({}) ", display_key(k), format_name),
+            },
+            Some(k) => format!(" {} ", display_key(k)),
             None => " Value ".to_string(),
         };
+        if self.pinned {
+            title.push_str("📌 pinned ");
+        }
+        if let Some(status) = search_status {
+            title.push_str(&status);
+        }
+        (lines, title)
+    }
+
+    /// `1,204 members` for a set, sorted set or hash, plus its order when
+    /// that is not the server's usual one.
+    fn member_summary(&self) -> Option<String> {
+        let (count, noun, order) = match self.value? {
+            RedisValue::Set(items) => (items.len(), "members", "sorted"),
+            RedisValue::ZSet(items) => (items.len(), "members", "by member"),
+            RedisValue::Hash(items) => (items.len(), "fields", "sorted"),
+            _ => return None,
+        };
+        let count = format_count(self.total.unwrap_or(count));
+        if self.sorted {
+            Some(format!("{} {}, {}", count, noun, order))
+        } else {
+            Some(format!("{} {}", count, noun))
+        }
+    }
+
+    /// The string value decoded as a Unix timestamp, if it is one.
+    fn timestamp(&self) -> Option<String> {
+        match self.value {
+            Some(RedisValue::String(s)) if !self.base64_decoded => decode_timestamp(s),
+            _ => None,
+        }
+    }
 
-        let paragraph = Paragraph::new(lines)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_style(self.theme.border)
-                    .title(title)
-                    .title_style(self.theme.title),
-            )
-            .wrap(Wrap { trim: false })
-            .scroll((self.scroll, 0));
+    fn render_string(&self, bytes: &[u8]) -> StringLines {
+        match self.rendered {
+            Some(rendered) => rendered.clone(),
+            None => render_string(bytes, self.base64_decoded),
+        }
+    }
+}
 
-        frame.render_widget(paragraph, area);
+/// Render a string value by its format, base64-decoding it first if asked
+/// to. Decompressing and pretty-printing can be slow on large values, so
+/// the app does this once per loaded value and passes the result to
+/// [`ValueView::rendered`].
+pub fn render_string(bytes: &[u8], base64_decoded: bool) -> StringLines {
+    if base64_decoded {
+        if let Some(decoded) = decode_base64(bytes) {
+            let (lines, label) = render_bytes(&decoded);
+            return (lines, format!("BASE64→{}", label));
+        }
+    }
+    render_bytes(bytes)
+}
+
+/// Render a string value by its detected format, transparently
+/// decompressing compressed payloads. Returns the lines and a format label
+/// such as `JSON` or `GZIP→JSON`.
+fn render_bytes(bytes: &[u8]) -> (Vec<Line<'static>>, String) {
+    let format = detect_format(bytes);
+
+    if let DetectedFormat::Compressed(compression) = format {
+        if let Some(decompressed) = decompress(bytes) {
+            let inner = detect_format(&decompressed);
+            let lines = render_format(&decompressed, inner);
+            let label = format!("{}→{}", compression.label(), format_label(inner));
+            return (lines, label);
+        }
+        // Corrupt or truncated payload: show the raw bytes
+        return (
+            format_as_hex(bytes),
+            format!("{} (corrupt)", compression.label()),
+        );
+    }
+
+    (
+        render_format(bytes, format),
+        format_label(format).to_string(),
+    )
+}
+
+fn render_format(bytes: &[u8], format: DetectedFormat) -> Vec<Line<'static>> {
+    match format {
+        DetectedFormat::Binary | DetectedFormat::Compressed(_) => format_as_hex(bytes),
+        DetectedFormat::Json => {
+            let s = String::from_utf8_lossy(bytes);
+            if let Ok(pretty) = pretty_json(&s) {
+                highlight_json(&pretty)
+            } else {
+                vec![Line::raw(s.into_owned())]
+            }
+        }
+        DetectedFormat::MessagePack => match decode_msgpack(bytes)
+            .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        {
+            Some(pretty) => highlight_json(&pretty),
+            None => format_as_hex(bytes),
+        },
+        DetectedFormat::Yaml => {
+            let s = String::from_utf8_lossy(bytes);
+            match pretty_yaml(&s) {
+                Ok(pretty) => highlight_yaml(&pretty),
+                Err(_) => highlight_yaml(&s),
+            }
+        }
+        DetectedFormat::Xml => {
+            let s = String::from_utf8_lossy(bytes);
+            match pretty_xml(&s) {
+                Ok(pretty) => highlight_xml(&pretty),
+                Err(_) => s.lines().map(|l| Line::raw(l.to_string())).collect(),
+            }
+        }
+        DetectedFormat::Html => {
+            // Highlighting only tokenizes, so HTML the reader rejects is
+            // still colored as written
+            let s = String::from_utf8_lossy(bytes);
+            match pretty_html(&s) {
+                Ok(pretty) => highlight_xml(&pretty),
+                Err(_) => highlight_xml(&s),
+            }
+        }
+        _ => String::from_utf8_lossy(bytes)
+            .lines()
+            .map(|l| Line::raw(l.to_string()))
+            .collect(),
+    }
+}
+
+/// Screen rows `lines` fill when wrapped at `width` columns. Wrapping at
+/// word boundaries can take a little more; this is close enough for a
+/// scrollbar.
+fn wrapped_rows(lines: &[Line], width: u16) -> usize {
+    let width = usize::from(width.max(1));
+    lines.iter().map(|l| l.width().div_ceil(width).max(1)).sum()
+}
+
+/// Whether a format label from [`ValueView::lines`] denotes a hex dump.
+fn is_hex_dump(label: &str) -> bool {
+    label.ends_with(format_label(DetectedFormat::Binary)) || label.ends_with("(corrupt)")
+}
+
+/// A sorted set score: with `precision` decimals if given, otherwise the
+/// shortest form that reads back as the same number (`3`, `0.001`,
+/// `1700000000.5`).
+pub fn format_score(score: f64, precision: Option<usize>) -> String {
+    match precision {
+        Some(precision) => format!("{:.*}", precision, score),
+        None => score.to_string(),
+    }
+}
+
+/// Prefix each line with its 1-based number, right-aligned to the width of
+/// the largest number.
+pub fn add_line_numbers(lines: &mut [Line<'static>], style: Style) {
+    let width = lines.len().to_string().len();
+    for (i, line) in lines.iter_mut().enumerate() {
+        let number = Span::styled(format!("{:>width$} ", i + 1), style);
+        line.spans.insert(0, number);
     }
 }
 
@@ -100,7 +554,10 @@ fn format_label(format: DetectedFormat) -> &'static str {
         DetectedFormat::Json => "JSON",
         DetectedFormat::Xml => "XML",
         DetectedFormat::Html => "HTML",
+        DetectedFormat::Yaml => "YAML",
+        DetectedFormat::MessagePack => "MSGPACK",
         DetectedFormat::Binary => "BINARY",
         DetectedFormat::PlainText => "TEXT",
+        DetectedFormat::Compressed(compression) => compression.label(),
     }
 }
@@ -0,0 +1,28 @@
+use redis_nav::ui::breadcrumb::Breadcrumb;
+use redis_nav::ui::theme::Theme;
+
+fn render(segments: &[&str], width: usize) -> String {
+    let theme = Theme::default();
+    Breadcrumb::new(segments, &theme).line(width).to_string()
+}
+
+#[test]
+fn test_breadcrumb_joins_segments() {
+    let segments = ["user", "1", "sessions", "abc"];
+    assert_eq!(render(&segments, 80), "user › 1 › sessions › abc");
+    assert_eq!(render(&[], 80), "");
+}
+
+#[test]
+fn test_breadcrumb_truncates_from_the_left() {
+    let segments = ["user", "1", "sessions", "abc"];
+    assert_eq!(render(&segments, 20), "… › sessions › abc");
+    assert_eq!(render(&segments, 10), "… › abc");
+    // Even the last segment alone is too long
+    assert_eq!(render(&["a-very-long-key-name"], 8), "…ey-name");
+}
+
+#[test]
+fn test_breadcrumb_shows_raw_bytes_escaped() {
+    assert_eq!(render(&["bin", "a\u{10FF01}"], 80), "bin › a\\x01");
+}
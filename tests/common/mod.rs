@@ -0,0 +1,52 @@
+use redis_nav::export::{ExportFormat, ExportWriter};
+use redis_nav::redis_client::RedisValue;
+
+/// Keys of every type, with a TTL on the first, as exported by the export
+/// tests and read back by the import tests.
+pub fn fixture() -> Vec<(String, RedisValue, Option<i64>)> {
+    vec![
+        (
+            "user:1".to_string(),
+            RedisValue::String("alice".to_string()),
+            Some(60),
+        ),
+        (
+            "queue".to_string(),
+            RedisValue::List(vec!["a".to_string(), "b c".to_string()]),
+            None,
+        ),
+        (
+            "user:1:profile".to_string(),
+            RedisValue::Hash(vec![("name".to_string(), "Alice, A.".to_string())]),
+            None,
+        ),
+        ("raw".to_string(), RedisValue::Bytes(vec![0xff, 0x00]), None),
+        (
+            "leaders".to_string(),
+            RedisValue::ZSet(vec![
+                ("alice".to_string(), 1.5),
+                ("floor".to_string(), f64::NEG_INFINITY),
+            ]),
+            None,
+        ),
+        (
+            "events".to_string(),
+            RedisValue::Stream(vec![(
+                "1-0".to_string(),
+                vec![("event".to_string(), "login".to_string())],
+            )]),
+            None,
+        ),
+    ]
+}
+
+/// [`fixture`] written out in `format`.
+pub fn export(format: ExportFormat) -> String {
+    let mut buf = Vec::new();
+    let mut writer = ExportWriter::new(&mut buf, format).unwrap();
+    for (key, value, ttl) in fixture() {
+        writer.write_entry(&key, &value, ttl).unwrap();
+    }
+    writer.finish().unwrap();
+    String::from_utf8(buf).unwrap()
+}
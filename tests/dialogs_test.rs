@@ -0,0 +1,135 @@
+use ratatui::style::{Color, Modifier};
+use ratatui::text::Line;
+use redis_nav::redis_client::parse_info;
+use redis_nav::ui::dialogs::{diff_lines, server_info_lines, Dialog};
+
+fn text(line: &Line) -> String {
+    line.spans.iter().map(|s| s.content.as_ref()).collect()
+}
+
+#[test]
+fn test_insertion_keeps_following_lines_unchanged() {
+    let old = "{\n  \"a\": 1,\n  \"b\": 2\n}\n";
+    let new = "{\n  \"z\": 0,\n  \"a\": 1,\n  \"b\": 2\n}\n";
+    let lines = diff_lines(old, new);
+
+    let rendered: Vec<String> = lines.iter().map(text).collect();
+    assert_eq!(
+        rendered,
+        vec![
+            "  {",
+            "+   \"z\": 0,",
+            "    \"a\": 1,",
+            "    \"b\": 2",
+            "  }"
+        ]
+    );
+    assert_eq!(lines[1].spans[0].style.fg, Some(Color::Green));
+    assert_eq!(lines[2].spans[0].style.fg, None);
+}
+
+#[test]
+fn test_modified_line_highlights_changed_words() {
+    let lines = diff_lines("name: alice smith\n", "name: alice jones\n");
+
+    let rendered: Vec<String> = lines.iter().map(text).collect();
+    assert_eq!(rendered, vec!["- name: alice smith", "+ name: alice jones"]);
+
+    let emphasized: Vec<&str> = lines[1]
+        .spans
+        .iter()
+        .filter(|s| s.style.add_modifier.contains(Modifier::REVERSED))
+        .map(|s| s.content.as_ref())
+        .collect();
+    assert_eq!(emphasized, vec!["jones"]);
+}
+
+#[test]
+fn test_diff_preview_height() {
+    use ratatui::layout::Rect;
+    use redis_nav::ui::dialogs::diff_preview_height;
+
+    // Half the screen height, minus the border and the two footer rows
+    assert_eq!(diff_preview_height(Rect::new(0, 0, 100, 40)), 16);
+    assert_eq!(diff_preview_height(Rect::new(0, 0, 100, 6)), 0);
+}
+
+#[test]
+fn test_diff_preview_unsaved_changes() {
+    let preview = |old: &str, new: &str, raw: Option<Vec<u8>>| {
+        Dialog::diff_preview(
+            "k".to_string(),
+            None,
+            old.to_string(),
+            new.to_string(),
+            raw,
+            false,
+            true,
+        )
+    };
+
+    assert!(preview("a", "b", None).has_unsaved_changes());
+    assert!(!preview("a", "a", None).has_unsaved_changes());
+    // Imported bytes always count, even if their text form matches
+    assert!(preview("a", "a", Some(b"a".to_vec())).has_unsaved_changes());
+    assert!(!Dialog::Help.has_unsaved_changes());
+}
+
+#[test]
+fn test_server_info_lines() {
+    let info = parse_info(
+        "# Keyspace\r\ndb0:keys=3\r\n# Clients\r\nconnected_clients:4\r\n\
+         # Memory\r\nused_memory_human:1.50M\r\n# Server\r\nredis_version:7.2.4\r\n",
+    );
+    let lines: Vec<String> = server_info_lines(&info).iter().map(text).collect();
+
+    // Headline metrics first; no lookups yet, so no hit ratio
+    assert_eq!(lines[0], "  Used memory  1.50M");
+    assert_eq!(lines[1], "  Clients      4");
+    assert!(lines[2].starts_with("  Hit ratio    -"));
+
+    // Known sections in triage order, others after
+    let headings: Vec<&str> = lines
+        .iter()
+        .filter(|l| !l.is_empty() && !l.starts_with(' '))
+        .map(String::as_str)
+        .collect();
+    assert_eq!(headings, vec!["Server", "Memory", "Clients", "Keyspace"]);
+}
+
+#[test]
+fn test_parse_new_collection_members() {
+    use redis_nav::redis_client::{RedisType, RedisValue};
+    use redis_nav::ui::dialogs::parse_members;
+
+    let list = parse_members(RedisType::List, "a\nb\n\nb\n").unwrap();
+    assert!(matches!(list, RedisValue::List(items) if items == ["a", "b", "b"]));
+
+    // Hash fields may contain colons; the value starts after ": "
+    let hash = parse_members(RedisType::Hash, "name: Ada\nuser:1: x\nflag:on").unwrap();
+    let RedisValue::Hash(fields) = hash else {
+        panic!("not a hash");
+    };
+    assert_eq!(
+        fields,
+        [
+            ("name".to_string(), "Ada".to_string()),
+            ("user:1".to_string(), "x".to_string()),
+            ("flag".to_string(), "on".to_string()),
+        ]
+    );
+
+    let zset = parse_members(RedisType::ZSet, "1.5: alice\n-2: bob").unwrap();
+    let RedisValue::ZSet(members) = zset else {
+        panic!("not a sorted set");
+    };
+    assert_eq!(
+        members,
+        [("alice".to_string(), 1.5), ("bob".to_string(), -2.0)]
+    );
+
+    assert!(parse_members(RedisType::Set, "\n\n").is_err());
+    assert!(parse_members(RedisType::Hash, "no separator").is_err());
+    let err = parse_members(RedisType::ZSet, "high: alice").unwrap_err();
+    assert!(err.to_string().contains("Invalid score 'high'"));
+}
@@ -0,0 +1,25 @@
+use redis_nav::editor::split_command;
+
+#[test]
+fn test_split_command() {
+    let split = |s| split_command(s).unwrap();
+    assert_eq!(split("vi"), vec!["vi"]);
+    assert_eq!(split("  code   --wait "), vec!["code", "--wait"]);
+    assert_eq!(
+        split(r#""/opt/My Editor/edit" -n 'two words'"#),
+        vec!["/opt/My Editor/edit", "-n", "two words"]
+    );
+    assert_eq!(
+        split(r#"emacs -nw --eval "(setq x \"y\")""#),
+        vec!["emacs", "-nw", "--eval", "(setq x \"y\")"]
+    );
+    assert_eq!(split("''"), vec![""]);
+}
+
+#[test]
+fn test_split_command_errors() {
+    assert!(split_command("").is_err());
+    assert!(split_command("   ").is_err());
+    assert!(split_command("code 'unterminated").is_err());
+    assert!(split_command("code \"unterminated").is_err());
+}
@@ -0,0 +1,162 @@
+use redis_nav::export::{redis_cli_repr, ExportFormat, ExportWriter};
+use redis_nav::import::parse_export;
+use redis_nav::redis_client::key::key_from_bytes;
+use redis_nav::redis_client::RedisValue;
+use std::path::Path;
+
+mod common;
+
+use common::export;
+
+#[test]
+fn test_export_json_array() {
+    let out = export(ExportFormat::Json);
+    let parsed: serde_json::Value = serde_json::from_str(&out).unwrap();
+    let entries = parsed.as_array().unwrap();
+    assert_eq!(entries.len(), 6);
+    assert_eq!(entries[0]["key"], "user:1");
+    assert_eq!(entries[0]["type"], "string");
+    assert_eq!(entries[2]["value"]["name"], "Alice, A.");
+}
+
+#[test]
+fn test_export_ndjson_one_object_per_line() {
+    let out = export(ExportFormat::Ndjson);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 6);
+    for line in lines {
+        let obj: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert!(obj.get("key").is_some());
+    }
+}
+
+#[test]
+fn test_export_csv_string_keys_only() {
+    let out = export(ExportFormat::Csv);
+    assert_eq!(out, "key,value\nuser:1,alice\n");
+}
+
+#[test]
+fn test_export_commands() {
+    let out = export(ExportFormat::Commands);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines[0], "SET user:1 alice");
+    assert_eq!(lines[1], "EXPIRE user:1 60");
+    assert_eq!(lines[2], "RPUSH queue a \"b c\"");
+    assert_eq!(lines[3], "HSET user:1:profile name \"Alice, A.\"");
+    assert_eq!(lines[5], "ZADD leaders 1.5 alice -inf floor");
+}
+
+#[test]
+fn test_export_json_empty() {
+    let buf: Vec<u8> = Vec::new();
+    let writer = ExportWriter::new(buf, ExportFormat::Json).unwrap();
+    assert_eq!(writer.finish().unwrap(), 0);
+}
+
+#[test]
+fn test_redis_cli_repr_escapes() {
+    assert_eq!(redis_cli_repr("plain"), "plain");
+    assert_eq!(redis_cli_repr(""), "\"\"");
+    assert_eq!(redis_cli_repr("a\"b\n"), "\"a\\\"b\\n\"");
+}
+
+#[test]
+fn test_export_ttl() {
+    let value = RedisValue::String("abc".to_string());
+    let mut buf = Vec::new();
+    let mut writer = ExportWriter::new(&mut buf, ExportFormat::Ndjson).unwrap();
+    writer.write_entry("session", &value, Some(60)).unwrap();
+    writer.write_entry("config", &value, None).unwrap();
+    writer.finish().unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    let lines: Vec<serde_json::Value> = out
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines[0]["ttl"], 60);
+    assert!(lines[1].get("ttl").is_none());
+
+    let mut buf = Vec::new();
+    let mut writer = ExportWriter::new(&mut buf, ExportFormat::Commands).unwrap();
+    writer.write_entry("session", &value, Some(60)).unwrap();
+    writer.finish().unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "SET session abc\nEXPIRE session 60\n"
+    );
+}
+
+#[test]
+fn test_export_stream() {
+    let stream = RedisValue::Stream(vec![
+        (
+            "1-0".to_string(),
+            vec![("event".to_string(), "login".to_string())],
+        ),
+        (
+            "2-0".to_string(),
+            vec![("event".to_string(), "log out".to_string())],
+        ),
+    ]);
+    let export_one = |format, value: &RedisValue| {
+        let mut buf = Vec::new();
+        let mut writer = ExportWriter::new(&mut buf, format).unwrap();
+        let written = writer.write_entry("events", value, None).unwrap();
+        writer.finish().unwrap();
+        (written, String::from_utf8(buf).unwrap())
+    };
+
+    let (_, out) = export_one(ExportFormat::Ndjson, &stream);
+    let obj: serde_json::Value = serde_json::from_str(out.trim()).unwrap();
+    assert_eq!(obj["type"], "stream");
+    assert_eq!(obj["value"][1]["id"], "2-0");
+    assert_eq!(obj["value"][1]["fields"]["event"], "log out");
+
+    let (_, out) = export_one(ExportFormat::Commands, &stream);
+    assert_eq!(
+        out,
+        "XADD events 1-0 event login\nXADD events 2-0 event \"log out\"\n"
+    );
+
+    // An emptied stream has nothing to replay
+    let (written, _) = export_one(ExportFormat::Commands, &RedisValue::Stream(Vec::new()));
+    assert!(!written);
+}
+
+#[test]
+fn test_export_format_from_path() {
+    let format = |p: &str| ExportFormat::from_path(Path::new(p));
+    assert_eq!(format("keys.json"), Some(ExportFormat::Json));
+    assert_eq!(format("keys.JSONL"), Some(ExportFormat::Ndjson));
+    assert_eq!(format("dump.csv"), Some(ExportFormat::Csv));
+    assert_eq!(format("restore.redis"), Some(ExportFormat::Commands));
+    assert_eq!(format("backup"), None);
+    assert_eq!(format("backup.tar"), None);
+}
+
+#[test]
+fn test_export_binary_key() {
+    let key = key_from_bytes(b"bin:\xff");
+    let value = RedisValue::String("v".to_string());
+    let export_one = |format| {
+        let mut buf = Vec::new();
+        let mut writer = ExportWriter::new(&mut buf, format).unwrap();
+        let written = writer.write_entry(&key, &value, None).unwrap();
+        writer.finish().unwrap();
+        (written, String::from_utf8(buf).unwrap())
+    };
+
+    let (_, out) = export_one(ExportFormat::Ndjson);
+    let obj: serde_json::Value = serde_json::from_str(out.trim()).unwrap();
+    assert_eq!(obj["key"], "YmluOv8=");
+    assert_eq!(obj["key_encoding"], "base64");
+    // Imports back to the same key
+    assert_eq!(parse_export(&out).unwrap()[0].key, key);
+
+    let (_, out) = export_one(ExportFormat::Commands);
+    assert_eq!(out, "SET \"bin:\\xff\" v\n");
+
+    let (written, _) = export_one(ExportFormat::Csv);
+    assert!(!written);
+}
@@ -0,0 +1,28 @@
+use redis_nav::redis_client::RedisType;
+use redis_nav::tree::FolderStats;
+use redis_nav::ui::folder_view::FolderView;
+use redis_nav::ui::theme::Theme;
+
+#[test]
+fn test_lines_list_counts_by_type() {
+    let theme = Theme::default();
+    let stats = FolderStats {
+        children: 2,
+        keys: 5,
+        types: vec![(RedisType::Hash, 3), (RedisType::String, 2)],
+        complete: false,
+    };
+    let view = FolderView::new("user:", &stats, &theme);
+
+    let lines: Vec<String> = view.lines().iter().map(|l| l.to_string()).collect();
+    assert_eq!(
+        lines,
+        vec![
+            "Children: 2",
+            "Keys:     5+",
+            "",
+            "  hash    3",
+            "  string  2"
+        ]
+    );
+}
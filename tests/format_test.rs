@@ -35,3 +35,194 @@ fn test_detect_binary_png() {
     let png_header = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
     assert_eq!(detect_format(&png_header), DetectedFormat::Binary);
 }
+
+#[test]
+fn test_detect_and_decompress_gzip_json() {
+    use flate2::write::GzEncoder;
+    use redis_nav::format::{decompress, Compression};
+    use std::io::Write;
+
+    let json = br#"{"cached": true}"#;
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(json).unwrap();
+    let gz = encoder.finish().unwrap();
+
+    assert_eq!(
+        detect_format(&gz),
+        DetectedFormat::Compressed(Compression::Gzip)
+    );
+    let decompressed = decompress(&gz).unwrap();
+    assert_eq!(decompressed, json);
+    assert_eq!(detect_format(&decompressed), DetectedFormat::Json);
+}
+
+#[test]
+fn test_detect_and_decompress_zlib_and_zstd() {
+    use flate2::write::ZlibEncoder;
+    use redis_nav::format::{decompress, Compression};
+    use std::io::Write;
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(b"hello zlib").unwrap();
+    let zlib = encoder.finish().unwrap();
+    assert_eq!(
+        detect_format(&zlib),
+        DetectedFormat::Compressed(Compression::Zlib)
+    );
+    assert_eq!(decompress(&zlib).unwrap(), b"hello zlib");
+
+    let zst = zstd::encode_all(&b"hello zstd"[..], 0).unwrap();
+    assert_eq!(
+        detect_format(&zst),
+        DetectedFormat::Compressed(Compression::Zstd)
+    );
+    assert_eq!(decompress(&zst).unwrap(), b"hello zstd");
+}
+
+#[test]
+fn test_text_starting_with_x_is_not_zlib() {
+    // 0x78 0x5e is "x^", a valid zlib header but also plain text
+    assert_eq!(detect_format(b"x^2 + y^2"), DetectedFormat::PlainText);
+}
+
+#[test]
+fn test_detect_yaml() {
+    let doc = b"---\nname: redis-nav\nports:\n  - 6379\n";
+    assert_eq!(detect_format(doc), DetectedFormat::Yaml);
+
+    let config = b"host: localhost\nport: 6379\ntags:\n  - cache\n";
+    assert_eq!(detect_format(config), DetectedFormat::Yaml);
+}
+
+#[test]
+fn test_text_with_colon_is_not_yaml() {
+    assert_eq!(detect_format(b"Note: hello"), DetectedFormat::PlainText);
+    assert_eq!(
+        detect_format(b"Dear team,\nReminder: standup at 10\nThanks"),
+        DetectedFormat::PlainText
+    );
+}
+
+#[test]
+fn test_pretty_and_highlight_yaml() {
+    use redis_nav::format::{highlight_yaml, pretty_yaml};
+
+    let pretty = pretty_yaml("a: 1\nb: [x, y]").unwrap();
+    assert!(pretty.contains("- x"));
+
+    let lines = highlight_yaml(&pretty);
+    assert_eq!(lines.len(), pretty.lines().count());
+    assert_eq!(lines[0].spans[0].content, "a");
+}
+
+#[test]
+fn test_decode_base64() {
+    use redis_nav::format::decode_base64;
+
+    assert_eq!(decode_base64(b"aGVsbG8=").unwrap(), b"hello");
+    assert_eq!(decode_base64(b"aGVsbG8\n").unwrap(), b"hello");
+    assert_eq!(decode_base64(b"_-8").unwrap(), [0xff, 0xef]);
+
+    let json = decode_base64(b"eyJhIjoxfQ==").unwrap();
+    assert_eq!(detect_format(&json), DetectedFormat::Json);
+
+    assert!(decode_base64(b"not base64!").is_none());
+    assert!(decode_base64(b"").is_none());
+}
+
+#[test]
+fn test_decode_timestamp() {
+    use redis_nav::format::decode_timestamp;
+
+    assert_eq!(
+        decode_timestamp("1700000000").as_deref(),
+        Some("2023-11-14T22:13:20Z")
+    );
+    assert_eq!(
+        decode_timestamp("1700000000123").as_deref(),
+        Some("2023-11-14T22:13:20.123Z")
+    );
+    assert_eq!(
+        decode_timestamp(" 1700000000\n").as_deref(),
+        Some("2023-11-14T22:13:20Z")
+    );
+
+    // Not timestamps: small numbers, non-integers, text
+    assert_eq!(decode_timestamp("42"), None);
+    assert_eq!(decode_timestamp("1700000000.5"), None);
+    assert_eq!(decode_timestamp("-1700000000"), None);
+    assert_eq!(decode_timestamp("ts 1700000000"), None);
+    assert_eq!(decode_timestamp("99999999999999999999"), None);
+}
+
+#[test]
+fn test_detect_msgpack() {
+    use redis_nav::format::decode_msgpack;
+
+    // {"a": 1, "b": [true, nil]}
+    let packed = [0x82, 0xa1, b'a', 0x01, 0xa1, b'b', 0x92, 0xc3, 0xc0];
+    assert_eq!(detect_format(&packed), DetectedFormat::MessagePack);
+    assert_eq!(
+        decode_msgpack(&packed).unwrap(),
+        serde_json::json!({"a": 1, "b": [true, null]})
+    );
+
+    // Trailing bytes or a bare scalar are not enough
+    let mut trailing = packed.to_vec();
+    trailing.push(0xff);
+    assert_eq!(detect_format(&trailing), DetectedFormat::Binary);
+    assert_eq!(
+        detect_format(&[0xcf, 0, 0, 0, 0, 0, 0, 0, 0x2a]),
+        DetectedFormat::Binary
+    );
+}
+
+#[test]
+fn test_pretty_xml() {
+    use redis_nav::format::pretty_xml;
+
+    let pretty = pretty_xml(r#"<?xml version="1.0"?><a><b id="1">x</b><c/></a>"#).unwrap();
+    assert_eq!(
+        pretty,
+        "<?xml version=\"1.0\"?>\n<a>\n  <b id=\"1\">x</b>\n  <c/>\n</a>"
+    );
+
+    assert!(pretty_xml("<a><b></a>").is_err());
+    assert!(pretty_xml("<a><b>").is_err());
+}
+
+#[test]
+fn test_pretty_html() {
+    use redis_nav::format::pretty_html;
+
+    let pretty = pretty_html("<html><body>a<br>b<img src=\"x\"></body></html>").unwrap();
+    assert_eq!(
+        pretty,
+        "<html>\n  <body>a<br/>b<img src=\"x\"/>\n  </body>\n</html>"
+    );
+    // Unclosed and mismatched tags are kept as written
+    assert!(pretty_html("<div><p>one<p>two</div>").is_ok());
+}
+
+#[test]
+fn test_highlight_xml() {
+    use ratatui::style::Color;
+    use redis_nav::format::highlight_xml;
+
+    let lines = highlight_xml("<!-- note -->\n<a href=\"/x\">link</a>");
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].spans[0].style.fg, Some(Color::DarkGray));
+
+    let spans: Vec<(&str, Option<Color>)> = lines[1]
+        .spans
+        .iter()
+        .map(|s| (s.content.as_ref(), s.style.fg))
+        .collect();
+    assert!(spans.contains(&("a", Some(Color::Blue))));
+    assert!(spans.contains(&("href", Some(Color::Cyan))));
+    assert!(spans.contains(&("\"/x\"", Some(Color::Green))));
+    assert!(spans.contains(&("link", None)));
+
+    // Unterminated markup still yields its lines
+    assert_eq!(highlight_xml("<a b=\"c\n d\">").len(), 2);
+}
@@ -0,0 +1,20 @@
+use redis_nav::glob::glob_match;
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("*:session:*", "app:session:42"));
+    assert!(!glob_match("*:session:*", "app:sessions"));
+    assert!(glob_match("user:?", "user:1"));
+    assert!(!glob_match("user:?", "user:10"));
+    assert!(glob_match("user:[0-9]*", "user:7:name"));
+    assert!(!glob_match("user:[^0-9]*", "user:7"));
+    assert!(glob_match("a\\*b", "a*b"));
+    assert!(!glob_match("a\\*b", "axb"));
+    assert!(glob_match("[abc", "[abc"));
+    assert!(glob_match("*a*b", "xaxxb"));
+    assert!(!glob_match("*a*b", "xaxxbc"));
+
+    // Many stars against a near miss finishes promptly
+    let pattern = "*a".repeat(30) + "b";
+    assert!(!glob_match(&pattern, &"a".repeat(60)));
+}
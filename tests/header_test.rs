@@ -0,0 +1,11 @@
+use redis_nav::ui::header::refresh_label;
+use std::time::Duration;
+
+#[test]
+fn test_refresh_label() {
+    assert_eq!(refresh_label(Duration::from_secs(45)), "⟳ 45s ago");
+    assert_eq!(
+        refresh_label(Duration::from_millis(125_500)),
+        "⟳ 2m 05s ago"
+    );
+}
@@ -0,0 +1,71 @@
+use redis_nav::config::{MatchMode, ProtectedNamespace, ProtectionLevel};
+use redis_nav::export::ExportFormat;
+use redis_nav::import::{parse_export, plan_import, ImportAction, OnConflict};
+use redis_nav::redis_client::RedisValue;
+
+mod common;
+
+use common::export;
+
+#[test]
+fn test_import_round_trips_export() {
+    for format in [ExportFormat::Json, ExportFormat::Ndjson] {
+        let entries = parse_export(&export(format)).unwrap();
+        assert_eq!(entries.len(), 6);
+
+        assert_eq!(entries[0].key, "user:1");
+        assert!(matches!(&entries[0].value, RedisValue::String(s) if s == "alice"));
+        assert_eq!(entries[0].ttl, Some(60));
+        assert!(matches!(&entries[2].value, RedisValue::Hash(f) if f[0].1 == "Alice, A."));
+        assert!(matches!(&entries[3].value, RedisValue::Bytes(b) if *b == [0xff, 0x00]));
+        assert_eq!(entries[3].ttl, None);
+        let RedisValue::ZSet(members) = &entries[4].value else {
+            panic!("expected a sorted set");
+        };
+        assert_eq!(members[0], ("alice".to_string(), 1.5));
+        // Infinite scores survive JSON, which has no number for them
+        assert_eq!(members[1], ("floor".to_string(), f64::NEG_INFINITY));
+        let RedisValue::Stream(stream) = &entries[5].value else {
+            panic!("expected a stream");
+        };
+        assert_eq!(stream[0].0, "1-0");
+    }
+}
+
+#[test]
+fn test_import_reports_bad_lines() {
+    let input = "{\"key\": \"a\", \"type\": \"string\", \"value\": \"x\"}\n\
+                 {\"key\": \"b\", \"type\": \"widget\", \"value\": 1}\n";
+    let err = parse_export(input).unwrap_err();
+    assert!(format!("{:#}", err).contains("line 2"));
+    assert!(format!("{:#}", err).contains("unknown type 'widget'"));
+}
+
+#[test]
+fn test_plan_import() {
+    assert_eq!(
+        plan_import(false, OnConflict::Skip, None),
+        ImportAction::Create
+    );
+    assert_eq!(
+        plan_import(true, OnConflict::Overwrite, None),
+        ImportAction::Overwrite
+    );
+    assert!(matches!(
+        plan_import(true, OnConflict::Skip, None),
+        ImportAction::Skip(_)
+    ));
+
+    let ns = |level| ProtectedNamespace::new("billing:", MatchMode::Prefix, level).unwrap();
+    // Nobody can confirm a headless import
+    let confirm = ns(ProtectionLevel::Confirm);
+    assert!(matches!(
+        plan_import(false, OnConflict::Overwrite, Some(&confirm)),
+        ImportAction::Skip(_)
+    ));
+    let warn = ns(ProtectionLevel::Warn);
+    assert_eq!(
+        plan_import(false, OnConflict::Skip, Some(&warn)),
+        ImportAction::Create
+    );
+}
@@ -0,0 +1,116 @@
+use ratatui::style::Color;
+use redis_nav::redis_client::{RedisValue, ValueSize};
+use redis_nav::ui::info_bar::{format_latency, format_size, latency_color};
+use std::time::Duration;
+
+#[test]
+fn test_multibyte_string_size() {
+    let value = RedisValue::String("héllo wörld".to_string());
+    let size = value.size().unwrap();
+
+    assert_eq!(size.bytes, 13);
+    assert_eq!(size.chars, Some(11));
+    assert_eq!(format_size(size, true), "13 B / 11 chars");
+    assert_eq!(format_size(size, false), "13 B");
+}
+
+#[test]
+fn test_binary_size_has_no_char_count() {
+    let size = ValueSize {
+        bytes: 2048,
+        chars: None,
+    };
+    assert_eq!(format_size(size, true), "2.0 KB");
+}
+
+#[test]
+fn test_format_page() {
+    use redis_nav::redis_client::ValuePage;
+    use redis_nav::ui::info_bar::{format_count, format_page};
+
+    assert_eq!(format_count(0), "0");
+    assert_eq!(format_count(999), "999");
+    assert_eq!(format_count(1_240_000), "1,240,000");
+
+    let page = ValuePage {
+        offset: 0,
+        len: 100,
+        total: 1_240_000,
+    };
+    assert_eq!(format_page(page), "showing 0–100 of 1,240,000");
+}
+
+#[test]
+fn test_format_bytes() {
+    use redis_nav::ui::info_bar::format_bytes;
+
+    assert_eq!(format_bytes(56), "56 B");
+    assert_eq!(format_bytes(4096), "4.0 KB");
+    assert_eq!(format_bytes(3 * 1024 * 1024 + 1), "3.0 MB");
+}
+
+#[test]
+fn test_format_ttl() {
+    use redis_nav::ui::info_bar::format_ttl;
+
+    assert_eq!(format_ttl(45), "45s");
+    assert_eq!(format_ttl(245), "4m 05s");
+    assert_eq!(format_ttl(9000), "2h 30m");
+    assert_eq!(format_ttl(273600), "3d 04h");
+}
+
+#[test]
+fn test_remaining_ttl_counts_down() {
+    use redis_nav::ui::info_bar::remaining_ttl;
+
+    assert_eq!(remaining_ttl(30, Duration::from_millis(1500)), 29);
+    assert_eq!(remaining_ttl(30, Duration::from_secs(45)), 0);
+    // No expiry never counts down
+    assert_eq!(remaining_ttl(-1, Duration::from_secs(45)), -1);
+}
+
+#[test]
+fn test_format_latency() {
+    assert_eq!(format_latency(Duration::from_micros(420)), "0.42ms");
+    assert_eq!(format_latency(Duration::from_micros(3140)), "3.1ms");
+    assert_eq!(format_latency(Duration::from_millis(120)), "120ms");
+}
+
+#[test]
+fn test_latency_color() {
+    assert_eq!(latency_color(Duration::from_micros(300)), Color::Green);
+    assert_eq!(latency_color(Duration::from_millis(4)), Color::Yellow);
+    assert_eq!(latency_color(Duration::from_millis(10)), Color::Red);
+}
+
+#[test]
+fn test_format_expiry() {
+    use redis_nav::ui::info_bar::{format_expiry, format_utc};
+    use std::time::UNIX_EPOCH;
+
+    let at = UNIX_EPOCH + Duration::from_secs(1_717_251_730);
+    assert_eq!(format_utc(at), "2024-06-01 14:22:10 UTC");
+    assert_eq!(
+        format_expiry(300, at),
+        "expires 2024-06-01 14:22:10 UTC (in 5m 00s)"
+    );
+}
+
+#[test]
+fn test_ttl_span_distinguishes_missing_keys() {
+    use redis_nav::ui::info_bar::ttl_span;
+    use redis_nav::ui::theme::Theme;
+
+    let theme = Theme::default();
+    let no_expiry = ttl_span(Some(-1), None, &theme);
+    assert_eq!(no_expiry.content, "no expiry");
+    assert_eq!(no_expiry.style, theme.ttl_normal);
+
+    let missing = ttl_span(Some(-2), None, &theme);
+    assert_eq!(missing.content, "missing");
+    assert_eq!(missing.style, theme.ttl_critical);
+
+    assert_eq!(ttl_span(Some(0), None, &theme).content, "expired");
+    assert_eq!(ttl_span(Some(90), None, &theme).style, theme.ttl_warning);
+    assert_eq!(ttl_span(None, None, &theme).content, "-");
+}
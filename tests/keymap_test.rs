@@ -0,0 +1,219 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use redis_nav::action::Action;
+use redis_nav::config::file::ConfigFile;
+use redis_nav::keymap::{motion_target, parse_motion, KeyCombo, Keymap, Motion};
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+    KeyEvent::new(code, modifiers)
+}
+
+#[test]
+fn test_parse_key_combo() {
+    let parse = |s| KeyCombo::parse(s).unwrap();
+    assert_eq!(
+        parse("j"),
+        KeyCombo::new(KeyCode::Char('j'), KeyModifiers::NONE)
+    );
+    assert_eq!(
+        parse("ctrl+n"),
+        KeyCombo::new(KeyCode::Char('n'), KeyModifiers::CONTROL)
+    );
+    assert_eq!(parse("Ctrl+Enter"), parse("ctrl+enter"));
+    assert_eq!(parse("shift+g"), parse("G"));
+    assert_eq!(parse("shift+tab"), parse("backtab"));
+    assert_eq!(
+        parse("+"),
+        KeyCombo::new(KeyCode::Char('+'), KeyModifiers::NONE)
+    );
+    assert_eq!(
+        parse("alt++"),
+        KeyCombo::new(KeyCode::Char('+'), KeyModifiers::ALT)
+    );
+    assert_eq!(
+        parse("f5"),
+        KeyCombo::new(KeyCode::F(5), KeyModifiers::NONE)
+    );
+
+    assert!(KeyCombo::parse("hyper+j").is_err());
+    assert!(KeyCombo::parse("ctrl+").is_err());
+    assert!(KeyCombo::parse("f13").is_err());
+    assert!(KeyCombo::parse("nope").is_err());
+}
+
+#[test]
+fn test_default_keymap() {
+    let keymap = Keymap::default();
+    assert_eq!(
+        keymap.action_for(&key(KeyCode::Char('j'), KeyModifiers::NONE)),
+        Some(Action::MoveDown)
+    );
+    // Terminals report uppercase letters with SHIFT set
+    assert_eq!(
+        keymap.action_for(&key(KeyCode::Char('G'), KeyModifiers::SHIFT)),
+        Some(Action::Bottom)
+    );
+    assert_eq!(
+        keymap.action_for(&key(KeyCode::Char('D'), KeyModifiers::SHIFT)),
+        Some(Action::DeleteFolder)
+    );
+    assert_eq!(
+        keymap.action_for(&key(KeyCode::Char('d'), KeyModifiers::CONTROL)),
+        Some(Action::PageDown)
+    );
+    assert_eq!(
+        keymap.action_for(&key(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+        Some(Action::Quit)
+    );
+    assert_eq!(
+        keymap.action_for(&key(KeyCode::Char('a'), KeyModifiers::NONE)),
+        Some(Action::CreateKey)
+    );
+    assert_eq!(
+        keymap.action_for(&key(KeyCode::Char('x'), KeyModifiers::NONE)),
+        None
+    );
+}
+
+#[test]
+fn test_keybinding_overrides() {
+    let config: ConfigFile = toml::from_str(
+        r#"
+        [keybindings]
+        move_down = ["ctrl+n", "down"]
+        delete = "x"
+        "#,
+    )
+    .unwrap();
+    let keymap = Keymap::with_overrides(&config.keybindings).unwrap();
+
+    assert_eq!(
+        keymap.action_for(&key(KeyCode::Char('n'), KeyModifiers::CONTROL)),
+        Some(Action::MoveDown)
+    );
+    assert_eq!(
+        keymap.action_for(&key(KeyCode::Char('x'), KeyModifiers::NONE)),
+        Some(Action::Delete)
+    );
+    // Overridden actions lose their default keys; others keep them
+    assert_eq!(
+        keymap.action_for(&key(KeyCode::Char('j'), KeyModifiers::NONE)),
+        None
+    );
+    assert_eq!(
+        keymap.action_for(&key(KeyCode::Char('d'), KeyModifiers::NONE)),
+        None
+    );
+    assert_eq!(
+        keymap.action_for(&key(KeyCode::Char('k'), KeyModifiers::NONE)),
+        Some(Action::MoveUp)
+    );
+}
+
+#[test]
+fn test_keybinding_errors() {
+    let load = |toml: &str| {
+        let config: ConfigFile = toml::from_str(toml).unwrap();
+        Keymap::with_overrides(&config.keybindings)
+    };
+
+    let err = load("[keybindings]\nmove_dwn = \"j\"").unwrap_err();
+    assert!(err.to_string().contains("unknown action 'move_dwn'"));
+
+    let err = load("[keybindings]\nquit = \"ctrl+qq\"").unwrap_err();
+    assert!(err.to_string().contains("quit"));
+
+    let err = load("[keybindings]\nquit = \"x\"\ndelete = \"x\"").unwrap_err();
+    assert!(err.to_string().contains("bound to both"));
+}
+
+#[test]
+fn test_action_names_round_trip() {
+    for action in Action::ALL {
+        assert_eq!(Action::from_name(action.name()), Some(*action));
+    }
+}
+
+#[test]
+fn test_command_palette_actions() {
+    let all = Action::palette(true, false);
+    assert!(all.contains(&Action::Rename));
+    assert!(!all.contains(&Action::MoveDown));
+    assert!(!all.contains(&Action::CommandPalette));
+
+    // Deleting a folder removes the keys under it
+    assert!(Action::palette(false, false).contains(&Action::Delete));
+
+    // Folder selected, read-only: no key or write actions
+    let folder = Action::palette(false, true);
+    assert!(folder.contains(&Action::Rescan));
+    assert!(!folder.contains(&Action::Rename));
+    assert!(!folder.contains(&Action::CreateKey));
+
+    assert_eq!(Action::filter(&all, "set_ttl"), vec![Action::SetTtl]);
+    // Config names match too
+    assert_eq!(Action::filter(&all, "select_db"), vec![Action::SelectDb]);
+    assert_eq!(Action::filter(&all, ""), all);
+}
+
+#[test]
+fn test_parse_motion() {
+    let run = |action, count| Motion::Run { action, count };
+    assert_eq!(parse_motion("5G"), run(Action::Bottom, Some(5)));
+    assert_eq!(parse_motion("gg"), run(Action::Top, None));
+    assert_eq!(parse_motion("3dd"), run(Action::Delete, Some(3)));
+    assert_eq!(parse_motion("10j"), run(Action::MoveDown, Some(10)));
+    assert_eq!(parse_motion("k"), run(Action::MoveUp, None));
+
+    // Incomplete: a lone g or d falls back to its single-key action
+    assert_eq!(
+        parse_motion("g"),
+        Motion::Pending {
+            fallback: Some(Action::Top)
+        }
+    );
+    assert_eq!(
+        parse_motion("d"),
+        Motion::Pending {
+            fallback: Some(Action::Delete)
+        }
+    );
+    assert_eq!(parse_motion("12"), Motion::Pending { fallback: None });
+    assert_eq!(parse_motion("3d"), Motion::Pending { fallback: None });
+
+    assert_eq!(parse_motion("gx"), Motion::Invalid);
+    assert_eq!(parse_motion("5x"), Motion::Invalid);
+    assert_eq!(parse_motion("05j"), Motion::Invalid);
+}
+
+#[test]
+fn test_motion_char_follows_bindings() {
+    let keymap = Keymap::default();
+    let motion = |code| keymap.motion_char(&key(code, KeyModifiers::NONE));
+    assert_eq!(motion(KeyCode::Char('j')), Some('j'));
+    assert_eq!(motion(KeyCode::Down), Some('j'));
+    assert_eq!(motion(KeyCode::Char('5')), Some('5'));
+    assert_eq!(motion(KeyCode::Char('q')), None);
+
+    let config: ConfigFile = toml::from_str(
+        r#"
+        [keybindings]
+        move_down = "n"
+        delete = "x"
+        "#,
+    )
+    .unwrap();
+    let keymap = Keymap::with_overrides(&config.keybindings).unwrap();
+    let motion = |code| keymap.motion_char(&key(code, KeyModifiers::NONE));
+    assert_eq!(motion(KeyCode::Char('n')), Some('j'));
+    assert_eq!(motion(KeyCode::Char('j')), None);
+    assert_eq!(motion(KeyCode::Char('x')), Some('d'));
+}
+
+#[test]
+fn test_motion_target() {
+    assert_eq!(motion_target(Action::MoveDown, 10, Some(2), 8), Some(7));
+    assert_eq!(motion_target(Action::MoveUp, 3, Some(2), 8), Some(0));
+    assert_eq!(motion_target(Action::Bottom, 5, Some(0), 8), Some(4));
+    assert_eq!(motion_target(Action::Top, 1, Some(6), 8), Some(0));
+    assert_eq!(motion_target(Action::MoveDown, 1, None, 0), None);
+}
@@ -0,0 +1,36 @@
+use ratatui::layout::Rect;
+use redis_nav::config::{MAX_TREE_PERCENT, MIN_TREE_PERCENT};
+use redis_nav::ui::layout::{clamp_scroll, visible_offset, AppLayout};
+
+#[test]
+fn test_tree_width_follows_ratio() {
+    let area = Rect::new(0, 0, 200, 50);
+    assert_eq!(AppLayout::new(area, 30).tree_area.width, 60);
+    assert_eq!(AppLayout::new(area, 50).value_area.width, 100);
+}
+
+#[test]
+fn test_tree_width_is_clamped() {
+    let area = Rect::new(0, 0, 100, 50);
+    assert_eq!(AppLayout::new(area, 0).tree_area.width, MIN_TREE_PERCENT);
+    assert_eq!(AppLayout::new(area, 100).tree_area.width, MAX_TREE_PERCENT);
+}
+
+#[test]
+fn test_clamp_scroll_after_resize() {
+    // 100 rows in a 20-row pane scroll to row 80 at most
+    assert_eq!(clamp_scroll(95, 100, 20), 80);
+    assert_eq!(clamp_scroll(30, 100, 20), 30);
+    // Growing the pane past the content brings it back to the top
+    assert_eq!(clamp_scroll(30, 10, 20), 0);
+}
+
+#[test]
+fn test_visible_offset_keeps_selection_on_screen() {
+    // Shrinking to 5 rows scrolls down just far enough to show row 12
+    assert_eq!(visible_offset(0, Some(12), 5), 8);
+    assert_eq!(visible_offset(15, Some(12), 5), 12);
+    assert_eq!(visible_offset(10, Some(12), 5), 10);
+    assert_eq!(visible_offset(10, None, 5), 10);
+    assert_eq!(visible_offset(0, Some(3), 0), 3);
+}
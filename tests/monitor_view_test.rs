@@ -0,0 +1,16 @@
+use redis_nav::ui::monitor_view::MonitorView;
+use redis_nav::ui::theme::Theme;
+use std::collections::VecDeque;
+
+#[test]
+fn test_lines_follow_newest_messages() {
+    let theme = Theme::default();
+    let messages: VecDeque<(String, String)> = (0..5)
+        .map(|i| ("events".to_string(), format!("msg {}", i)))
+        .collect();
+    let view = MonitorView::new("events", &messages, &theme);
+
+    let lines: Vec<String> = view.lines(2).iter().map(|l| l.to_string()).collect();
+    assert_eq!(lines, vec!["events: msg 3", "events: msg 4"]);
+    assert_eq!(view.lines(10).len(), 5);
+}
@@ -0,0 +1,247 @@
+use redis_nav::redis_client::{getex_cmd, parse_role, GetExExpiry, ServerRole};
+
+fn args(cmd: &redis::Cmd) -> Vec<String> {
+    cmd.args_iter()
+        .map(|arg| match arg {
+            redis::Arg::Simple(bytes) => String::from_utf8_lossy(bytes).to_string(),
+            redis::Arg::Cursor => "<cursor>".to_string(),
+        })
+        .collect()
+}
+
+#[test]
+fn test_getex_keep() {
+    assert_eq!(args(&getex_cmd("k", GetExExpiry::Keep)), vec!["GETEX", "k"]);
+}
+
+#[test]
+fn test_getex_ex() {
+    assert_eq!(
+        args(&getex_cmd("k", GetExExpiry::Ex(60))),
+        vec!["GETEX", "k", "EX", "60"]
+    );
+}
+
+#[test]
+fn test_getex_persist() {
+    assert_eq!(
+        args(&getex_cmd("k", GetExExpiry::Persist)),
+        vec!["GETEX", "k", "PERSIST"]
+    );
+}
+
+#[test]
+fn test_load_tls_certificates() {
+    use redis_nav::config::TlsOptions;
+    use redis_nav::redis_client::load_tls_certificates;
+
+    assert!(load_tls_certificates(&TlsOptions::default())
+        .unwrap()
+        .is_none());
+
+    let dir = tempfile::tempdir().unwrap();
+    let ca = dir.path().join("ca.pem");
+    std::fs::write(&ca, "ca").unwrap();
+    let certs = load_tls_certificates(&TlsOptions {
+        ca: Some(ca.clone()),
+        ..Default::default()
+    })
+    .unwrap()
+    .unwrap();
+    assert_eq!(certs.root_cert.as_deref(), Some(&b"ca"[..]));
+    assert!(certs.client_tls.is_none());
+
+    // A client certificate without its key is rejected
+    let err = load_tls_certificates(&TlsOptions {
+        cert: Some(ca),
+        ..Default::default()
+    });
+    assert!(err.is_err());
+}
+
+#[test]
+fn test_backoff_delay() {
+    use redis_nav::redis_client::backoff_delay;
+    use std::time::Duration;
+
+    assert_eq!(backoff_delay(1), Duration::from_millis(500));
+    assert_eq!(backoff_delay(2), Duration::from_secs(1));
+    assert_eq!(backoff_delay(4), Duration::from_secs(4));
+    assert_eq!(backoff_delay(5), Duration::from_secs(8));
+    assert_eq!(backoff_delay(50), Duration::from_secs(8));
+}
+
+#[tokio::test]
+async fn test_connect_with_retry_gives_up() {
+    use redis_nav::config::AppConfig;
+    use redis_nav::redis_client::RedisClient;
+
+    let mut config = AppConfig::default().connection;
+    // Nothing listens on port 1, so every attempt is refused
+    config.url = "redis://127.0.0.1:1".to_string();
+    config.connect_attempts = 2;
+
+    let mut attempts = Vec::new();
+    let result = RedisClient::connect_with_retry(&config, |attempt, max| {
+        attempts.push((attempt, max));
+    })
+    .await;
+
+    let err = result.err().expect("connection should fail");
+    assert!(format!("{:#}", err).contains("giving up after 2 attempts"));
+    assert_eq!(attempts, vec![(1, 2), (2, 2)]);
+}
+
+#[test]
+fn test_glob_escape() {
+    use redis_nav::redis_client::glob_escape;
+
+    assert_eq!(glob_escape("user:"), "user:");
+    assert_eq!(glob_escape("a*b?[c]\\"), "a\\*b\\?\\[c\\]\\\\");
+}
+
+#[test]
+fn test_parse_role() {
+    let master = "# Replication\r\nrole:master\r\nconnected_slaves:0\r\n";
+    assert_eq!(parse_role(master), Some(ServerRole::Master));
+    let replica = "# Replication\r\nrole:slave\r\nmaster_host:10.0.0.1\r\n";
+    assert_eq!(parse_role(replica), Some(ServerRole::Replica));
+    assert_eq!(parse_role("# Replication\r\n"), None);
+}
+
+#[test]
+fn test_is_wrong_type() {
+    use redis_nav::redis_client::is_wrong_type;
+
+    let server_error = |reply: &[u8]| {
+        redis::parse_redis_value(reply)
+            .unwrap()
+            .extract_error()
+            .unwrap_err()
+    };
+    let wrong_type =
+        server_error(b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n");
+    assert!(is_wrong_type(&wrong_type));
+    assert!(!is_wrong_type(&server_error(b"-ERR unknown command\r\n")));
+}
+
+#[test]
+fn test_error_message_spells_out_acl_denials() {
+    use redis_nav::redis_client::error_message;
+
+    let server_error = |reply: &[u8]| -> anyhow::Error {
+        redis::parse_redis_value(reply)
+            .unwrap()
+            .extract_error()
+            .unwrap_err()
+            .into()
+    };
+    let denied =
+        server_error(b"-NOPERM User reader has no permissions to run the 'del' command\r\n");
+    assert_eq!(
+        error_message(&denied),
+        "permission denied: User reader has no permissions to run the 'del' command"
+    );
+    let other = server_error(b"-ERR syntax error\r\n");
+    assert_eq!(error_message(&other), other.to_string());
+}
+
+#[test]
+fn test_parse_version() {
+    use redis_nav::redis_client::parse_version;
+
+    let info = "# Server\r\nredis_version:7.2.4\r\nredis_mode:standalone\r\n";
+    assert_eq!(parse_version(info), Some((7, 2)));
+    assert_eq!(parse_version("redis_version:3.2.12\r\n"), Some((3, 2)));
+    assert_eq!(parse_version("# Server\r\n"), None);
+}
+
+#[test]
+fn test_parse_info() {
+    use redis_nav::redis_client::{keyspace_hit_ratio, parse_info};
+
+    let info = "# Server\r\nredis_version:7.2.4\r\n\r\n# Stats\r\n\
+                keyspace_hits:90\r\nkeyspace_misses:10\r\n\r\n# Keyspace\r\n\
+                db0:keys=3,expires=0,avg_ttl=0\r\n";
+    let sections = parse_info(info);
+    assert_eq!(
+        sections.keys().collect::<Vec<_>>(),
+        vec!["Keyspace", "Server", "Stats"]
+    );
+    assert_eq!(sections["Server"]["redis_version"], "7.2.4");
+    // Only the first colon separates field from value
+    assert_eq!(sections["Keyspace"]["db0"], "keys=3,expires=0,avg_ttl=0");
+    assert_eq!(keyspace_hit_ratio(&sections), Some(0.9));
+
+    let idle = parse_info("# Stats\r\nkeyspace_hits:0\r\nkeyspace_misses:0\r\n");
+    assert_eq!(keyspace_hit_ratio(&idle), None);
+}
+
+#[test]
+fn test_binary_key_round_trip() {
+    use redis_nav::redis_client::key::{display_key, is_binary_key, key_from_bytes, key_to_bytes};
+
+    let raw = b"user:\xff\x00:caf\xc3\xa9";
+    let key = key_from_bytes(raw);
+    assert_eq!(key_to_bytes(&key), raw);
+    assert!(is_binary_key(&key));
+    // Valid UTF-8 survives; the stray byte and the NUL are escaped
+    assert_eq!(display_key(&key), "user:\\xff\\x00:café");
+
+    let plain = key_from_bytes(b"session:42");
+    assert_eq!(plain, "session:42");
+    assert!(!is_binary_key(&plain));
+    assert_eq!(key_to_bytes("naïve"), "naïve".as_bytes());
+
+    // A real U+10FF41 must not come back as the byte 0x41
+    let raw = "tag:\u{10FF41}".as_bytes();
+    let key = key_from_bytes(raw);
+    assert_eq!(key_to_bytes(&key), raw);
+    assert_eq!(display_key(&key), "tag:\\xf4\\x8f\\xbd\\x81");
+}
+
+#[test]
+fn test_parse_typed_key() {
+    use redis_nav::redis_client::key::{escape_key, key_from_bytes, key_to_bytes, parse_key};
+
+    assert_eq!(key_to_bytes(&parse_key("bin:\\xff\\x00")), b"bin:\xff\x00");
+    // Escaped UTF-8 reads as the character it spells
+    assert_eq!(parse_key("caf\\xc3\\xa9"), "café");
+    assert_eq!(parse_key("a\\\\b"), "a\\b");
+    // A backslash that starts no escape is kept
+    assert_eq!(parse_key("a\\xzz\\"), "a\\xzz\\");
+
+    for raw in [&b"user:\xff\x01:caf\xc3\xa9"[..], b"path\\x41", b"plain"] {
+        let key = key_from_bytes(raw);
+        assert_eq!(parse_key(&escape_key(&key)), key);
+    }
+}
+
+#[test]
+fn test_sort_members() {
+    use redis_nav::redis_client::RedisValue;
+
+    let mut set = RedisValue::Set(vec!["b".into(), "c".into(), "a".into()]);
+    set.sort_members();
+    assert!(matches!(set, RedisValue::Set(items) if items == ["a", "b", "c"]));
+
+    // Sorted sets switch from score order to member order
+    let mut zset = RedisValue::ZSet(vec![("z".into(), 1.0), ("a".into(), 2.0)]);
+    zset.sort_members();
+    let RedisValue::ZSet(items) = zset else {
+        panic!("not a sorted set");
+    };
+    assert_eq!(items, [("a".to_string(), 2.0), ("z".to_string(), 1.0)]);
+
+    let mut hash = RedisValue::Hash(vec![("y".into(), "1".into()), ("x".into(), "2".into())]);
+    hash.sort_members();
+    let RedisValue::Hash(items) = hash else {
+        panic!("not a hash");
+    };
+    assert_eq!(items[0].0, "x");
+
+    // Lists are positional and keep their order
+    let mut list = RedisValue::List(vec!["b".into(), "a".into()]);
+    list.sort_members();
+    assert!(matches!(list, RedisValue::List(items) if items == ["b", "a"]));
+}
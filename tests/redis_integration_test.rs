@@ -0,0 +1,577 @@
+//! Tests against a live server. Start one with `docker compose up -d` and
+//! run `cargo test -- --ignored` (override the URL with `REDIS_URL`).
+
+use redis_nav::redis_client::{RedisClient, RedisType, ServerRole};
+
+fn redis_url() -> String {
+    std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6380".to_string())
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_get_types_preserves_order() {
+    let url = redis_url();
+    let client = redis::Client::open(url.as_str()).unwrap();
+    let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+
+    let _: () = redis::pipe()
+        .cmd("SET")
+        .arg("test:types:s")
+        .arg("v")
+        .ignore()
+        .cmd("RPUSH")
+        .arg("test:types:l")
+        .arg("v")
+        .ignore()
+        .cmd("HSET")
+        .arg("test:types:h")
+        .arg("f")
+        .arg("v")
+        .ignore()
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+
+    // Enough keys to span more than one pipeline chunk
+    let mut keys = Vec::new();
+    let mut expected = Vec::new();
+    for i in 0..1200 {
+        let (key, t) = match i % 4 {
+            0 => ("test:types:s", RedisType::String),
+            1 => ("test:types:l", RedisType::List),
+            2 => ("test:types:h", RedisType::Hash),
+            _ => ("test:types:missing", RedisType::Unknown),
+        };
+        keys.push(key.to_string());
+        expected.push(t);
+    }
+
+    let mut nav_client = RedisClient::connect(&url, 0, &Default::default())
+        .await
+        .unwrap();
+    let types = nav_client.get_types(&keys).await.unwrap();
+    assert_eq!(types, expected);
+
+    let _: () = redis::cmd("DEL")
+        .arg("test:types:s")
+        .arg("test:types:l")
+        .arg("test:types:h")
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_list_element_edits() {
+    use redis_nav::redis_client::RedisValue;
+
+    let url = redis_url();
+    let mut client = RedisClient::connect(&url, 0, &Default::default())
+        .await
+        .unwrap();
+    let key = "test:elements:list";
+    client.delete(key).await.unwrap();
+
+    let client_conn = redis::Client::open(url.as_str()).unwrap();
+    let mut conn = client_conn
+        .get_multiplexed_async_connection()
+        .await
+        .unwrap();
+    let _: () = redis::cmd("RPUSH")
+        .arg(key)
+        .arg(&["a", "b", "a"])
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+
+    client.lset(key, 1, "B").await.unwrap();
+    // Removing the second "a" must leave the first one in place
+    client.lremove_index(key, 2).await.unwrap();
+
+    match client.get_value(key).await.unwrap() {
+        RedisValue::List(items) => assert_eq!(items, vec!["a", "B"]),
+        other => panic!("expected a list, got {:?}", other),
+    }
+
+    // A list element that looks like a placeholder is left alone too
+    client
+        .rpush(key, &["__redis_nav_removed__".to_string(), "c".to_string()])
+        .await
+        .unwrap();
+    client.lremove_index(key, 3).await.unwrap();
+    match client.get_value(key).await.unwrap() {
+        RedisValue::List(items) => assert_eq!(items, vec!["a", "B", "__redis_nav_removed__"]),
+        other => panic!("expected a list, got {:?}", other),
+    }
+    client.delete(key).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_get_value_page() {
+    use redis_nav::redis_client::{RedisValue, ValuePage};
+
+    let url = redis_url();
+    let mut client = RedisClient::connect(&url, 0, &Default::default())
+        .await
+        .unwrap();
+    let key = "test:paging:list";
+    client.delete(key).await.unwrap();
+
+    let client_conn = redis::Client::open(url.as_str()).unwrap();
+    let mut conn = client_conn
+        .get_multiplexed_async_connection()
+        .await
+        .unwrap();
+    let items: Vec<String> = (0..250).map(|i| i.to_string()).collect();
+    let _: () = redis::cmd("RPUSH")
+        .arg(key)
+        .arg(&items)
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+
+    let (value, page) = client.get_value_page(key, 200, 100).await.unwrap();
+    assert_eq!(
+        page,
+        Some(ValuePage {
+            offset: 200,
+            len: 50,
+            total: 250
+        })
+    );
+    match value {
+        RedisValue::List(items) => assert_eq!(items.first().map(String::as_str), Some("200")),
+        other => panic!("expected a list, got {:?}", other),
+    }
+
+    // A stale type is looked up again after the WRONGTYPE reply
+    let (value, _) = client
+        .get_value_page_as(key, redis_nav::redis_client::RedisType::Hash, 0, 10)
+        .await
+        .unwrap();
+    assert!(matches!(value, RedisValue::List(ref items) if items.len() == 10));
+    client.delete(key).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_get_hash_page() {
+    use redis_nav::redis_client::RedisValue;
+
+    let url = redis_url();
+    let mut client = RedisClient::connect(&url, 0, &Default::default())
+        .await
+        .unwrap();
+    let key = "test:paging:hash";
+    client.delete(key).await.unwrap();
+    for i in 0..300 {
+        client
+            .hset(key, &format!("field{}", i), &i.to_string())
+            .await
+            .unwrap();
+    }
+
+    let (value, page) = client.get_value_page(key, 0, 100).await.unwrap();
+    let page = page.expect("hash is paged");
+    assert_eq!(page.total, 300);
+    assert_eq!(page.len, 100);
+    let first = match value {
+        RedisValue::Hash(fields) => fields,
+        other => panic!("expected a hash, got {:?}", other),
+    };
+    assert_eq!(first.len(), 100);
+
+    // Pages don't overlap
+    let (value, _) = client.get_value_page(key, 100, 100).await.unwrap();
+    let RedisValue::Hash(second) = value else {
+        panic!("expected a hash");
+    };
+    assert!(first
+        .iter()
+        .all(|(f, _)| second.iter().all(|(g, _)| g != f)));
+    client.delete(key).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_get_value_missing_or_retyped() {
+    use redis_nav::redis_client::RedisValue;
+
+    let url = redis_url();
+    let mut client = RedisClient::connect(&url, 0, &Default::default())
+        .await
+        .unwrap();
+    let key = "test:vanished";
+    client.delete(key).await.unwrap();
+
+    // Deleted after the scan listed it
+    assert!(matches!(
+        client.get_value(key).await.unwrap(),
+        RedisValue::None
+    ));
+    let (value, page) = client.get_value_page(key, 0, 100).await.unwrap();
+    assert!(matches!(value, RedisValue::None));
+    assert_eq!(page, None);
+
+    // Scanned as a string, now a hash
+    client.set_string(key, "v").await.unwrap();
+    client.delete(key).await.unwrap();
+    client.hset(key, "f", "v").await.unwrap();
+    match client.get_value(key).await.unwrap() {
+        RedisValue::Hash(fields) => assert_eq!(fields, vec![("f".into(), "v".into())]),
+        other => panic!("expected a hash, got {:?}", other),
+    }
+    client.delete(key).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_delete_prefix() {
+    let url = redis_url();
+    let mut client = RedisClient::connect(&url, 0, &Default::default())
+        .await
+        .unwrap();
+    for i in 0..250 {
+        client
+            .set_string(&format!("test:purge:{}", i), "x")
+            .await
+            .unwrap();
+    }
+    client.set_string("test:purgeable", "x").await.unwrap();
+
+    // Keys the caller protects survive
+    let removed = client
+        .delete_prefix("test:purge:", 100, true, |key| key.ends_with(":7"))
+        .await
+        .unwrap();
+    assert_eq!(removed, (249, 1));
+    assert_eq!(
+        client.scan_keys("test:purge:*", 100).await.unwrap(),
+        vec!["test:purge:7".to_string()]
+    );
+    client.delete("test:purge:7").await.unwrap();
+    // Keys merely sharing the text prefix are kept
+    assert_eq!(
+        client
+            .delete_prefix("test:purgeable", 100, false, |_| false)
+            .await
+            .unwrap(),
+        (1, 0)
+    );
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_unlink() {
+    let url = redis_url();
+    let mut client = RedisClient::connect(&url, 0, &Default::default())
+        .await
+        .unwrap();
+    assert!(client.server_version().await.unwrap() >= (4, 0));
+
+    let keys = vec!["test:unlink:a".to_string(), "test:unlink:b".to_string()];
+    for key in &keys {
+        client.set_string(key, "x").await.unwrap();
+    }
+    assert_eq!(client.unlink(&keys).await.unwrap(), 2);
+    assert_eq!(client.delete_keys(&keys, false).await.unwrap(), 0);
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_memory_usage() {
+    let url = redis_url();
+    let mut client = RedisClient::connect(&url, 0, &Default::default())
+        .await
+        .unwrap();
+    let key = "test:memory:string";
+    client.set_string(key, &"x".repeat(1000)).await.unwrap();
+
+    let bytes = client.memory_usage(key).await.unwrap();
+    assert!(bytes.is_some_and(|b| b >= 1000));
+
+    client.delete(key).await.unwrap();
+    assert_eq!(client.memory_usage(key).await.unwrap(), None);
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_object_encoding() {
+    let url = redis_url();
+    let mut client = RedisClient::connect(&url, 0, &Default::default())
+        .await
+        .unwrap();
+    let key = "test:encoding:int";
+    client.set_string(key, "12345").await.unwrap();
+
+    assert_eq!(client.object_encoding(key).await.unwrap(), "int");
+
+    client.delete(key).await.unwrap();
+    assert!(client.object_encoding(key).await.is_err());
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_stream_value_page() {
+    use redis_nav::redis_client::{RedisValue, ValuePage};
+
+    let url = redis_url();
+    let mut client = RedisClient::connect(&url, 0, &Default::default())
+        .await
+        .unwrap();
+    let key = "test:stream:events";
+    client.delete(key).await.unwrap();
+
+    let client_conn = redis::Client::open(url.as_str()).unwrap();
+    let mut conn = client_conn
+        .get_multiplexed_async_connection()
+        .await
+        .unwrap();
+    for (id, event) in [("1-0", "signup"), ("2-0", "login"), ("3-0", "logout")] {
+        let _: String = redis::cmd("XADD")
+            .arg(key)
+            .arg(id)
+            .arg("event")
+            .arg(event)
+            .query_async(&mut conn)
+            .await
+            .unwrap();
+    }
+
+    let (value, page) = client.get_value_page(key, 1, 5).await.unwrap();
+    assert_eq!(
+        page,
+        Some(ValuePage {
+            offset: 1,
+            len: 2,
+            total: 3
+        })
+    );
+    match value {
+        RedisValue::Stream(entries) => {
+            let ids: Vec<&str> = entries.iter().map(|(id, _)| id.as_str()).collect();
+            assert_eq!(ids, vec!["2-0", "1-0"]);
+            assert_eq!(
+                entries[0].1,
+                vec![("event".to_string(), "login".to_string())]
+            );
+        }
+        other => panic!("expected a stream, got {:?}", other),
+    }
+    client.delete(key).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_set_bytes_round_trip() {
+    use redis_nav::redis_client::RedisValue;
+
+    let mut client = RedisClient::connect(&redis_url(), 0, &Default::default())
+        .await
+        .unwrap();
+    let key = "test:import:binary";
+    let bytes = vec![0x89, b'P', b'N', b'G', 0x00, 0xff, 0xfe];
+
+    client.set_bytes(key, &bytes).await.unwrap();
+    match client.get_value(key).await.unwrap() {
+        RedisValue::Bytes(b) => assert_eq!(b, bytes),
+        other => panic!("expected bytes, got {:?}", other),
+    }
+    client.delete(key).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_dbsize_counts_keys() {
+    let mut client = RedisClient::connect(&redis_url(), 0, &Default::default())
+        .await
+        .unwrap();
+    let key = "test:dbsize:probe";
+
+    client.set_string(key, "1").await.unwrap();
+    assert!(client.dbsize().await.unwrap() >= 1);
+    client.delete(key).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_string_prefix_ends_on_a_character() {
+    let mut client = RedisClient::connect(&redis_url(), 0, &Default::default())
+        .await
+        .unwrap();
+    let key = "test:size_guard:prefix";
+
+    // "é" is two bytes, so a 2-byte prefix of "aé" would split it
+    client.set_string(key, "aébc").await.unwrap();
+    assert_eq!(client.strlen(key).await.unwrap(), 5);
+    assert_eq!(client.get_range(key, 0, 1).await.unwrap(), b"a\xc3");
+    assert_eq!(client.get_string_prefix(key, 2).await.unwrap(), b"a");
+    assert_eq!(
+        client.get_string_prefix(key, 3).await.unwrap(),
+        "aé".as_bytes()
+    );
+    client.delete(key).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_standalone_server_is_master() {
+    let mut client = RedisClient::connect(&redis_url(), 0, &Default::default())
+        .await
+        .unwrap();
+    assert_eq!(client.server_role().await.unwrap(), ServerRole::Master);
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_ping_latency() {
+    let mut client = RedisClient::connect(&redis_url(), 0, &Default::default())
+        .await
+        .unwrap();
+    let latency = client.ping().await.unwrap();
+    assert!(latency < std::time::Duration::from_secs(5));
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_binary_key() {
+    use redis_nav::redis_client::key::{key_from_bytes, key_to_bytes};
+    use redis_nav::redis_client::RedisValue;
+
+    let mut client = RedisClient::connect(&redis_url(), 0, &Default::default())
+        .await
+        .unwrap();
+    let key = key_from_bytes(b"redis_nav_test:bin:\xfe\xff");
+    client.set_string(&key, "raw").await.unwrap();
+
+    let scanned = client.scan_keys("redis_nav_test:bin:*", 100).await.unwrap();
+    assert_eq!(scanned, vec![key.clone()]);
+    assert_eq!(key_to_bytes(&scanned[0]), b"redis_nav_test:bin:\xfe\xff");
+    assert!(matches!(
+        client.get_value(&key).await.unwrap(),
+        RedisValue::String(ref s) if s == "raw"
+    ));
+    client.delete(&key).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_copy_key_keeps_type_and_ttl() {
+    use redis_nav::redis_client::is_busy_key;
+
+    let url = redis_url();
+    let mut client = RedisClient::connect(&url, 0, &Default::default())
+        .await
+        .unwrap();
+    let (source, dest) = ("test:copy:src", "test:copy:dest");
+    client.delete(dest).await.unwrap();
+    client
+        .sadd(source, &["a".to_string(), "b".to_string()])
+        .await
+        .unwrap();
+    client.set_ttl(source, 300).await.unwrap();
+
+    client.copy_key(source, dest, false).await.unwrap();
+    assert_eq!(client.get_type(dest).await.unwrap(), RedisType::Set);
+    let ttl = client.get_ttl(dest).await.unwrap();
+    assert!(ttl > 0 && ttl <= 300);
+
+    // An existing destination needs REPLACE
+    let err = client.copy_key(source, dest, false).await.unwrap_err();
+    assert!(is_busy_key(&err));
+    client.copy_key(source, dest, true).await.unwrap();
+
+    client.delete(source).await.unwrap();
+    client.delete(dest).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_write_value_replaces_key_with_ttl() {
+    use redis_nav::redis_client::RedisValue;
+
+    let url = redis_url();
+    let mut client = RedisClient::connect(&url, 0, &Default::default())
+        .await
+        .unwrap();
+    let key = "test:import:hash";
+    client.set_string(key, "old").await.unwrap();
+
+    let value = RedisValue::Hash(vec![("name".to_string(), "alice".to_string())]);
+    client.write_value(key, &value, Some(300)).await.unwrap();
+    assert_eq!(client.get_type(key).await.unwrap(), RedisType::Hash);
+    let ttl = client.get_ttl(key).await.unwrap();
+    assert!(ttl > 0 && ttl <= 300);
+
+    let stream = RedisValue::Stream(vec![
+        ("1-0".to_string(), vec![("a".to_string(), "1".to_string())]),
+        ("2-0".to_string(), vec![("b".to_string(), "2".to_string())]),
+    ]);
+    client.write_value(key, &stream, None).await.unwrap();
+    let RedisValue::Stream(entries) = client.get_value(key).await.unwrap() else {
+        panic!("expected a stream");
+    };
+    assert_eq!(entries[1].0, "2-0");
+    assert_eq!(client.get_ttl(key).await.unwrap(), -1);
+
+    client.delete(key).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_set_ttl_refuses_zero() {
+    let url = redis_url();
+    let mut client = RedisClient::connect(&url, 0, &Default::default())
+        .await
+        .unwrap();
+    let key = "test:ttl:zero";
+    client.set_string(key, "v").await.unwrap();
+
+    // EXPIRE 0 would delete the key without a confirmation
+    assert!(client.set_ttl(key, 0).await.is_err());
+    assert_eq!(client.get_type(key).await.unwrap(), RedisType::String);
+
+    client.delete(key).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_rename_keeps_existing_target() {
+    let url = redis_url();
+    let mut client = RedisClient::connect(&url, 0, &Default::default())
+        .await
+        .unwrap();
+    let (old, new) = ("test:rename:old", "test:rename:new");
+    client.set_string(old, "old").await.unwrap();
+    client.set_string(new, "new").await.unwrap();
+
+    // Without `replace` an existing target is left alone
+    assert!(!client.rename(old, new, false).await.unwrap());
+    assert_eq!(client.get_type(old).await.unwrap(), RedisType::String);
+    assert!(client.rename(old, new, true).await.unwrap());
+    assert!(!client.exists(old).await.unwrap());
+
+    client.delete(new).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires a running Redis server"]
+async fn test_set_bytes_nx_keeps_existing_key() {
+    use redis_nav::redis_client::RedisValue;
+
+    let mut client = RedisClient::connect(&redis_url(), 0, &Default::default())
+        .await
+        .unwrap();
+    let key = "test:create:nx";
+    client.delete(key).await.unwrap();
+
+    assert!(client.set_bytes_nx(key, b"first").await.unwrap());
+    assert!(!client.set_bytes_nx(key, b"second").await.unwrap());
+    match client.get_value(key).await.unwrap() {
+        RedisValue::String(s) => assert_eq!(s, "first"),
+        other => panic!("expected a string, got {:?}", other),
+    }
+    client.delete(key).await.unwrap();
+}
@@ -0,0 +1,42 @@
+use redis_nav::session::{load_session, save_session, session_key, session_path, SessionState};
+use std::path::PathBuf;
+
+#[test]
+fn test_session_path() {
+    assert_eq!(
+        session_path(Some(PathBuf::from("/home/u/.config"))),
+        Some(PathBuf::from("/home/u/.config/redis-nav/session.toml"))
+    );
+    assert_eq!(session_path(Some(PathBuf::new())), None);
+    assert_eq!(session_path(None), None);
+}
+
+#[test]
+fn test_session_key_masks_password() {
+    assert_eq!(
+        session_key("redis://:secret@db.internal:6379", 2),
+        "redis://:****@db.internal:6379 db 2"
+    );
+}
+
+#[test]
+fn test_save_and_load_session() {
+    let dir = tempfile::tempdir().unwrap();
+    // The redis-nav directory is created on first save
+    let path = dir.path().join("redis-nav").join("session.toml");
+    let local = session_key("redis://127.0.0.1:6379", 0);
+    let other = session_key("redis://cache.internal:6379", 0);
+
+    assert_eq!(load_session(&path, &local), None);
+
+    let state = SessionState {
+        expanded_paths: vec!["user:".to_string()],
+        selected_key: Some("user:1".to_string()),
+    };
+    save_session(&path, &local, state.clone()).unwrap();
+    save_session(&path, &other, SessionState::default()).unwrap();
+
+    // Each connection keeps its own session
+    assert_eq!(load_session(&path, &local), Some(state));
+    assert_eq!(load_session(&path, &other), Some(SessionState::default()));
+}
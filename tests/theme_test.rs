@@ -0,0 +1,53 @@
+use ratatui::style::Color;
+use redis_nav::config::ThemeConfig;
+use redis_nav::ui::theme::{config_warnings, parse_color, Theme};
+
+#[test]
+fn test_parse_color() {
+    assert_eq!(parse_color("#ff8000"), Some(Color::Rgb(0xff, 0x80, 0x00)));
+    assert_eq!(parse_color("red"), Some(Color::Red));
+    assert_eq!(parse_color("lightred"), Some(Color::LightRed));
+    assert_eq!(parse_color("darkgray"), Some(Color::DarkGray));
+    assert_eq!(parse_color("208"), Some(Color::Indexed(208)));
+    assert_eq!(parse_color("not-a-color"), None);
+}
+
+#[test]
+fn test_theme_from_config() {
+    let config: ThemeConfig = toml::from_str(
+        r##"
+        border = "#010203"
+        tree_selected = "magenta"
+        title = "bogus"
+        "##,
+    )
+    .unwrap();
+    let theme = Theme::from_config(&config);
+
+    assert_eq!(theme.border.fg, Some(Color::Rgb(1, 2, 3)));
+    assert_eq!(theme.tree_selected.bg, Some(Color::Magenta));
+    // Unknown colors keep the default
+    assert_eq!(theme.title.fg, Theme::default().title.fg);
+}
+
+#[test]
+fn test_named_theme_and_warnings() {
+    let config = ThemeConfig {
+        name: Some("solarized".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        Theme::from_config(&config).border.fg,
+        Theme::solarized().border.fg
+    );
+    assert!(config_warnings(&config).is_empty());
+
+    let config = ThemeConfig {
+        name: Some("neon".to_string()),
+        border: Some("nope".to_string()),
+        ..Default::default()
+    };
+    let warnings = config_warnings(&config);
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings[1].contains("theme.border"));
+}
@@ -1,5 +1,5 @@
 use redis_nav::redis_client::RedisType;
-use redis_nav::tree::TreeBuilder;
+use redis_nav::tree::{path_names, TreeBuilder};
 
 #[test]
 fn test_single_delimiter() {
@@ -30,6 +30,27 @@ fn test_multiple_delimiters() {
     assert_eq!(tree.len(), 2); // "user" and "api"
 }
 
+#[test]
+fn test_per_level_delimiters() {
+    let keys = vec![("service:api/v2:users".to_string(), RedisType::String)];
+
+    // Any delimiter at any level
+    let tree = TreeBuilder::new(vec![':', '/']).build(&keys);
+    assert_eq!(tree[0].children[0].children[0].name, "v2");
+
+    // ':' at the first level, '/' from then on
+    let tree = TreeBuilder::new(vec![':', '/'])
+        .per_level(true)
+        .build(&keys);
+    let service = &tree[0];
+    assert_eq!(service.name, "service");
+    let api = &service.children[0];
+    assert_eq!(api.name, "api");
+    assert_eq!(api.prefix.as_deref(), Some("service:api/"));
+    assert_eq!(api.children[0].name, "v2:users");
+    assert!(!api.children[0].is_folder());
+}
+
 #[test]
 fn test_empty_keys() {
     let builder = TreeBuilder::new(vec![':']);
@@ -39,3 +60,305 @@ fn test_empty_keys() {
 
     assert!(tree.is_empty());
 }
+
+#[test]
+fn test_folder_prefix() {
+    let builder = TreeBuilder::new(vec![':', '/']);
+    let keys = vec![("user:1/name".to_string(), RedisType::String)];
+
+    let tree = builder.build(&keys);
+
+    assert_eq!(tree[0].prefix.as_deref(), Some("user:"));
+    assert_eq!(tree[0].children[0].prefix.as_deref(), Some("user:1/"));
+    assert_eq!(tree[0].children[0].children[0].prefix, None);
+}
+
+#[test]
+fn test_duplicate_scan_keys() {
+    use redis_nav::redis_client::merge_scan_batch;
+    use std::collections::HashSet;
+
+    let mut keys = Vec::new();
+    let mut seen = HashSet::new();
+    merge_scan_batch(&mut keys, &mut seen, vec!["user:1".into(), "user:2".into()]);
+    merge_scan_batch(&mut keys, &mut seen, vec!["user:1".into()]);
+    assert_eq!(keys, vec!["user:1", "user:2"]);
+
+    // Re-inserting a key must not create a second leaf
+    let builder = TreeBuilder::new(vec![':']);
+    let typed = vec![
+        ("user:1".to_string(), RedisType::String),
+        ("user:1".to_string(), RedisType::String),
+    ];
+    let tree = builder.build(&typed);
+    assert_eq!(tree[0].children.len(), 1);
+}
+
+#[test]
+fn test_type_filter() {
+    let builder = TreeBuilder::new(vec![':']);
+    let keys = vec![
+        ("user:1:name".to_string(), RedisType::String),
+        ("user:1:tags".to_string(), RedisType::Set),
+        ("queue:jobs".to_string(), RedisType::List),
+        ("session:abc".to_string(), RedisType::Hash),
+    ];
+
+    let tree = builder.build_filtered(&keys, Some(RedisType::Set));
+
+    assert_eq!(tree.len(), 1);
+    assert_eq!(tree[0].name, "user");
+    let user_1 = &tree[0].children[0];
+    assert_eq!(user_1.children.len(), 1);
+    assert_eq!(user_1.children[0].full_key.as_deref(), Some("user:1:tags"));
+
+    assert_eq!(builder.build_filtered(&keys, None).len(), 3);
+}
+
+#[test]
+fn test_flattened_prefix_for_nested_folders() {
+    use redis_nav::ui::tree_view::TreeViewState;
+
+    let builder = TreeBuilder::new(vec![':']);
+    let keys = vec![("user:1:profile:name".to_string(), RedisType::String)];
+    let mut tree = builder.build(&keys);
+    tree[0].expanded = true;
+    tree[0].children[0].expanded = true;
+    tree[0].children[0].children[0].expanded = true;
+
+    let mut state = TreeViewState::new();
+    state.flatten(&tree);
+
+    let mut paths = Vec::new();
+    for i in 0..state.flattened.len() {
+        state.list_state.select(Some(i));
+        paths.push(state.selected_path().map(|p| p.to_string()));
+    }
+    assert_eq!(
+        paths,
+        vec![
+            Some("user:".to_string()),
+            Some("user:1:".to_string()),
+            Some("user:1:profile:".to_string()),
+            Some("user:1:profile:name".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_incremental_insert_matches_build() {
+    let builder = TreeBuilder::new(vec![':']);
+    let keys = vec![
+        ("user:2:name".to_string(), RedisType::String),
+        ("user:1:name".to_string(), RedisType::String),
+        ("queue".to_string(), RedisType::List),
+    ];
+
+    let mut nodes = Vec::new();
+    for batch in keys.chunks(2) {
+        for (key, redis_type) in batch {
+            builder.insert_key(&mut nodes, key, *redis_type);
+        }
+        builder.sort_nodes(&mut nodes);
+    }
+
+    let built = builder.build(&keys);
+    let names = |nodes: &[redis_nav::tree::TreeNode]| {
+        nodes.iter().map(|n| n.name.clone()).collect::<Vec<_>>()
+    };
+    assert_eq!(names(&nodes), names(&built));
+    assert_eq!(names(&nodes[0].children), vec!["1", "2"]);
+}
+
+#[test]
+fn test_set_expanded_recursive_is_bounded() {
+    let builder = TreeBuilder::new(vec![':']);
+    let keys = vec![("a:b:c:d".to_string(), RedisType::String)];
+    let mut tree = builder.build(&keys);
+
+    tree[0].set_expanded_recursive(true, 1);
+    assert!(tree[0].expanded);
+    assert!(tree[0].children[0].expanded);
+    assert!(!tree[0].children[0].children[0].expanded);
+
+    tree[0].set_expanded_recursive(false, usize::MAX);
+    assert!(!tree[0].children[0].expanded);
+}
+
+#[test]
+fn test_lazy_stubs() {
+    let builder = TreeBuilder::new(vec![':']);
+    assert_eq!(builder.depth_of(""), 0);
+    assert_eq!(builder.depth_of("user:1:"), 2);
+
+    assert_eq!(
+        builder.stub_prefix("user:1:name", 1),
+        Some("user:".to_string())
+    );
+    assert_eq!(
+        builder.stub_prefix("user:1:name", 2),
+        Some("user:1:".to_string())
+    );
+    assert_eq!(builder.stub_prefix("user:1:name", 3), None);
+    assert_eq!(builder.stub_prefix("counter", 1), None);
+    // Zero levels means no limit
+    assert_eq!(builder.stub_prefix("user:1:name", 0), None);
+
+    let mut nodes = Vec::new();
+    assert!(builder.insert_stub(&mut nodes, "user:"));
+    assert!(!builder.insert_stub(&mut nodes, "user:"));
+    assert_eq!(nodes.len(), 1);
+    assert!(nodes[0].is_folder());
+    assert!(!nodes[0].loaded);
+    assert_eq!(nodes[0].prefix.as_deref(), Some("user:"));
+
+    // A stub below a loaded folder keeps the ancestor loaded
+    nodes[0].loaded = true;
+    assert!(builder.insert_stub(&mut nodes, "user:1:"));
+    assert!(nodes[0].loaded);
+    assert!(!nodes[0].children[0].loaded);
+    assert_eq!(nodes[0].children[0].prefix.as_deref(), Some("user:1:"));
+}
+
+#[test]
+fn test_key_that_is_also_a_folder() {
+    use redis_nav::ui::tree_view::TreeViewState;
+
+    let builder = TreeBuilder::new(vec![':']);
+    let leaf_first = vec![
+        ("user".to_string(), RedisType::Hash),
+        ("user:1:name".to_string(), RedisType::String),
+    ];
+    let folder_first: Vec<_> = leaf_first.iter().rev().cloned().collect();
+
+    for keys in [leaf_first, folder_first] {
+        let tree = builder.build(&keys);
+        assert_eq!(tree.len(), 1);
+        let user = &tree[0];
+        assert!(user.is_folder());
+        assert_eq!(user.prefix.as_deref(), Some("user:"));
+        assert_eq!(user.own_key, Some(("user".to_string(), RedisType::Hash)));
+        assert_eq!(user.key(), Some("user"));
+        assert_eq!(user.children[0].children[0].key(), Some("user:1:name"));
+
+        // Selecting the folder row targets its own key
+        let mut state = TreeViewState::new();
+        state.flatten(&tree);
+        assert_eq!(state.selected_key(), Some("user"));
+        assert_eq!(state.selected_path(), Some("user:"));
+        assert!(state.flattened[0].has_own_key);
+    }
+
+    // A lazy stub over an existing key keeps the key too
+    let mut nodes = builder.build(&[("user".to_string(), RedisType::String)]);
+    assert!(builder.insert_stub(&mut nodes, "user:"));
+    assert!(!nodes[0].loaded);
+    assert_eq!(nodes[0].key(), Some("user"));
+}
+
+#[test]
+fn test_folder_stats() {
+    let builder = TreeBuilder::new(vec![':']);
+    let tree = builder.build(&[
+        ("user:1".to_string(), RedisType::Hash),
+        ("user:2".to_string(), RedisType::Hash),
+        ("user:2:tags".to_string(), RedisType::Set),
+        ("user:admin:1".to_string(), RedisType::String),
+    ]);
+    let stats = tree[0].folder_stats();
+    // 1, 2 (also a folder) and admin
+    assert_eq!(stats.children, 3);
+    assert_eq!(stats.keys, 4);
+    assert_eq!(
+        stats.types,
+        vec![
+            (RedisType::Hash, 2),
+            (RedisType::Set, 1),
+            (RedisType::String, 1)
+        ]
+    );
+    assert!(stats.complete);
+
+    let mut nodes = Vec::new();
+    builder.insert_stub(&mut nodes, "lazy:");
+    assert!(!nodes[0].folder_stats().complete);
+}
+
+#[test]
+fn test_expand_to_key() {
+    use redis_nav::tree::expand_to_key;
+    use redis_nav::ui::tree_view::TreeViewState;
+
+    let builder = TreeBuilder::new(vec![':']);
+    let keys = vec![
+        ("user".to_string(), RedisType::Hash),
+        ("user:1:name".to_string(), RedisType::String),
+        ("user:2:name".to_string(), RedisType::String),
+        ("session:abc".to_string(), RedisType::String),
+    ];
+    let mut tree = builder.build(&keys);
+
+    assert!(expand_to_key(&mut tree, "user:2:name"));
+    let mut state = TreeViewState::new();
+    state.flatten(&tree);
+    state.reselect_key("user:2:name", 0);
+    assert_eq!(state.selected_key(), Some("user:2:name"));
+    // Only the folders on the way are expanded
+    let session = tree.iter().find(|n| n.name == "session").unwrap();
+    assert!(!session.expanded);
+    let user = tree.iter().find(|n| n.name == "user").unwrap();
+    assert!(user.expanded);
+    assert!(!user.children[0].expanded);
+
+    // A folder's own key needs nothing expanded
+    let mut tree = builder.build(&keys);
+    assert!(expand_to_key(&mut tree, "user"));
+    assert!(tree.iter().all(|n| !n.expanded));
+
+    assert!(!expand_to_key(&mut tree, "user:3:name"));
+    assert!(!expand_to_key(&mut tree, "user:"));
+}
+
+#[test]
+fn test_path_names_follow_node_index() {
+    let builder = TreeBuilder::new(vec![':']);
+    let keys = vec![
+        ("user:1:sessions:abc".to_string(), RedisType::String),
+        ("user:2:name".to_string(), RedisType::String),
+    ];
+    let tree = builder.build(&keys);
+
+    assert_eq!(
+        path_names(&tree, &[0, 0, 0, 0]),
+        vec!["user", "1", "sessions", "abc"]
+    );
+    assert_eq!(path_names(&tree, &[0, 1]), vec!["user", "2"]);
+    // A stale path stops at the last node that still exists
+    assert_eq!(path_names(&tree, &[0, 5]), vec!["user"]);
+    assert!(path_names(&tree, &[]).is_empty());
+}
+
+#[test]
+fn test_expanding_folders_on_insert() {
+    let expand = ["user:".to_string(), "user:1:".to_string()].into();
+    let builder = TreeBuilder::new(vec![':']).expanding(expand);
+    let mut nodes = Vec::new();
+
+    builder.insert_key(&mut nodes, "user:1:name", RedisType::String);
+    builder.insert_key(&mut nodes, "user:2:name", RedisType::String);
+    let user = &nodes[0];
+    assert!(user.expanded);
+    assert!(user.children[0].expanded);
+    assert!(!user.children[1].expanded);
+
+    // A folder the user collapsed stays collapsed as more keys arrive
+    nodes[0].expanded = false;
+    builder.insert_key(&mut nodes, "user:3:name", RedisType::String);
+    assert!(!nodes[0].expanded);
+
+    // Unloaded stubs stay closed
+    let mut nodes = Vec::new();
+    assert!(builder.insert_stub(&mut nodes, "user:1:"));
+    assert!(nodes[0].expanded);
+    assert!(!nodes[0].children[0].expanded);
+}
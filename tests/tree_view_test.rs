@@ -0,0 +1,231 @@
+use ratatui::layout::Rect;
+use redis_nav::redis_client::RedisType;
+use redis_nav::tree::{fuzzy_match, KeyFilter, TreeBuilder};
+use redis_nav::ui::tree_view::{truncate_to_width, TreeSnapshot, TreeViewState};
+
+#[test]
+fn test_fuzzy_match() {
+    assert!(fuzzy_match("user:1:profile", "profile"));
+    assert!(fuzzy_match("user:1:profile", "u1pf"));
+    assert!(fuzzy_match("User:1", "user"));
+    assert!(!fuzzy_match("user:1:profile", "settings"));
+}
+
+#[test]
+fn test_filtered_flatten_keeps_ancestors() {
+    let builder = TreeBuilder::new(vec![':']);
+    let keys = vec![
+        ("user:1:profile".to_string(), RedisType::String),
+        ("user:1:settings".to_string(), RedisType::String),
+        ("cache:home".to_string(), RedisType::String),
+    ];
+    let tree = builder.build(&keys);
+
+    let mut state = TreeViewState::new();
+    state.filter = Some("settings".to_string());
+    state.flatten(&tree);
+
+    let names: Vec<&str> = state.flattened.iter().map(|n| n.name.as_str()).collect();
+    assert_eq!(names, vec!["user", "1", "settings"]);
+    assert_eq!(state.match_count, 1);
+}
+
+#[test]
+fn test_filtered_flatten_expands_matching_folder() {
+    let builder = TreeBuilder::new(vec![':']);
+    let keys = vec![
+        ("cache:home".to_string(), RedisType::String),
+        ("cache:about".to_string(), RedisType::String),
+        ("user:1".to_string(), RedisType::String),
+    ];
+    let tree = builder.build(&keys);
+
+    let mut state = TreeViewState::new();
+    state.filter = Some("cache:".to_string());
+    state.flatten(&tree);
+
+    let names: Vec<&str> = state.flattened.iter().map(|n| n.name.as_str()).collect();
+    assert_eq!(names, vec!["cache", "about", "home"]);
+}
+
+#[test]
+fn test_index_at_row() {
+    let builder = TreeBuilder::new(vec![':']);
+    let keys = vec![
+        ("a".to_string(), RedisType::String),
+        ("b".to_string(), RedisType::String),
+        ("c".to_string(), RedisType::String),
+    ];
+    let tree = builder.build(&keys);
+
+    let mut state = TreeViewState::new();
+    state.flatten(&tree);
+
+    // Rows 11..=13 are inside the border of a tree drawn at y=10
+    let area = Rect::new(0, 10, 30, 8);
+    assert_eq!(state.index_at(area, 10), None);
+    assert_eq!(state.index_at(area, 11), Some(0));
+    assert_eq!(state.index_at(area, 13), Some(2));
+    assert_eq!(state.index_at(area, 14), None);
+
+    *state.list_state.offset_mut() = 1;
+    assert_eq!(state.index_at(area, 11), Some(1));
+}
+
+#[test]
+fn test_selected_path_prefix() {
+    let builder = TreeBuilder::new(vec![':']);
+    let keys = vec![
+        ("top".to_string(), RedisType::String),
+        ("user".to_string(), RedisType::Hash),
+        ("user:1:name".to_string(), RedisType::String),
+    ];
+    let mut tree = builder.build(&keys);
+    let user = tree.iter_mut().find(|n| n.name == "user").unwrap();
+    user.expanded = true;
+    user.children[0].expanded = true;
+    let mut state = TreeViewState::new();
+    state.flatten(&tree);
+    let names: Vec<_> = state.flattened.iter().map(|n| n.name.as_str()).collect();
+    assert_eq!(names, ["user", "1", "name", "top"]);
+
+    let prefix_at = |state: &mut TreeViewState, row| {
+        state.list_state.select(Some(row));
+        state.selected_path_prefix().map(|s| s.to_string())
+    };
+    // A folder that is also a key still gives its prefix
+    assert_eq!(prefix_at(&mut state, 0).as_deref(), Some("user:"));
+    assert_eq!(prefix_at(&mut state, 1).as_deref(), Some("user:1:"));
+    // A leaf gives its folder's prefix
+    assert_eq!(prefix_at(&mut state, 2).as_deref(), Some("user:1:"));
+    assert_eq!(prefix_at(&mut state, 3), None);
+}
+
+#[test]
+fn test_restore_after_rebuild() {
+    let builder = TreeBuilder::new(vec![':']);
+    let mut keys = vec![
+        ("a:1".to_string(), RedisType::String),
+        ("user:1".to_string(), RedisType::String),
+        ("user:2".to_string(), RedisType::String),
+    ];
+    let mut tree = builder.build(&keys);
+    tree[1].expanded = true;
+    let mut state = TreeViewState::new();
+    state.flatten(&tree);
+    state.list_state.select(Some(3));
+    assert_eq!(state.selected_key(), Some("user:2"));
+
+    // A new key sorts before the selection; the selection follows its key
+    let snapshot = state.snapshot(&tree);
+    keys.push(("user:10".to_string(), RedisType::String));
+    let mut tree = builder.build(&keys);
+    state.restore(&mut tree, &snapshot);
+    assert!(tree[1].expanded);
+    assert_eq!(state.selected_key(), Some("user:2"));
+
+    // A deleted key falls back to its old row, clamped into range
+    let snapshot = state.snapshot(&tree);
+    let mut tree = builder.build(&keys[..1]);
+    state.restore(&mut tree, &snapshot);
+    assert_eq!(state.list_state.selected(), Some(0));
+}
+
+#[test]
+fn test_restore_saved_session() {
+    let builder = TreeBuilder::new(vec![':']);
+    let keys = vec![
+        ("a:1".to_string(), RedisType::String),
+        ("user:1:name".to_string(), RedisType::String),
+        ("user:2".to_string(), RedisType::String),
+    ];
+    let mut tree = builder.build(&keys);
+    let mut state = TreeViewState::new();
+    let session = TreeSnapshot::from_session(
+        vec!["user:".to_string(), "user:1:".to_string()],
+        Some("user:1:name".to_string()),
+    );
+    state.restore(&mut tree, &session);
+    assert_eq!(state.selected_key(), Some("user:1:name"));
+
+    // Saving the restored tree gives the same session back
+    let snapshot = state.snapshot(&tree);
+    assert_eq!(snapshot.expanded(), vec!["user:", "user:1:"]);
+    assert_eq!(snapshot.selected(), Some("user:1:name"));
+}
+
+#[test]
+fn test_types_patched_in_place() {
+    let builder = TreeBuilder::new(vec![':']);
+    let mut tree = builder.build(&[
+        ("user".to_string(), RedisType::Unknown),
+        ("user:1".to_string(), RedisType::Unknown),
+    ]);
+    tree[0].expanded = true;
+    let mut state = TreeViewState::new();
+    state.flatten(&tree);
+    state.list_state.select(Some(1));
+    assert_eq!(state.flattened[1].key_type, Some(RedisType::Unknown));
+
+    // Resolving types keeps the rows and the selection where they were
+    builder.insert_key(&mut tree, "user", RedisType::Hash);
+    builder.insert_key(&mut tree, "user:1", RedisType::Set);
+    state.flatten(&tree);
+    assert_eq!(state.flattened.len(), 2);
+    assert_eq!(state.flattened[0].key_type, Some(RedisType::Hash));
+    assert_eq!(state.flattened[1].key_type, Some(RedisType::Set));
+    assert_eq!(state.selected_key(), Some("user:1"));
+}
+
+#[test]
+fn test_key_filter_parse() {
+    assert_eq!(KeyFilter::parse("  ").unwrap(), None);
+    assert!(KeyFilter::parse("type:blob").is_err());
+
+    let filter = KeyFilter::parse("type:hash *:session:*").unwrap().unwrap();
+    assert!(filter.matches("app:session:1", RedisType::Hash));
+    assert!(!filter.matches("app:session:1", RedisType::String));
+    assert!(!filter.matches("app:cache:1", RedisType::Hash));
+}
+
+#[test]
+fn test_key_filter_flatten() {
+    let builder = TreeBuilder::new(vec![':']);
+    let keys = vec![
+        ("app:session:1".to_string(), RedisType::Hash),
+        ("app:session:2".to_string(), RedisType::String),
+        ("app:cache:1".to_string(), RedisType::Hash),
+    ];
+    let tree = builder.build(&keys);
+
+    let mut state = TreeViewState::new();
+    state.key_filter = KeyFilter::parse("type:hash *:session:*").unwrap();
+    state.flatten(&tree);
+    let names: Vec<&str> = state.flattened.iter().map(|n| n.name.as_str()).collect();
+    assert_eq!(names, vec!["app", "session", "1"]);
+    assert_eq!(state.match_count, 1);
+
+    // Combined with the fuzzy search, both must match
+    state.filter = Some("cache".to_string());
+    state.flatten(&tree);
+    assert!(state.flattened.is_empty());
+
+    // Clearing restores the full, collapsed tree
+    state.filter = None;
+    state.key_filter = None;
+    state.flatten(&tree);
+    let names: Vec<&str> = state.flattened.iter().map(|n| n.name.as_str()).collect();
+    assert_eq!(names, vec!["app"]);
+}
+
+#[test]
+fn test_truncate_to_width() {
+    assert_eq!(truncate_to_width("session", 7), "session");
+    assert_eq!(truncate_to_width("session", 5), "sess…");
+    assert_eq!(truncate_to_width("session", 1), "…");
+    assert_eq!(truncate_to_width("session", 0), "");
+    // CJK characters take two columns each; never overshoot the width
+    assert_eq!(truncate_to_width("日本語キー", 5), "日本…");
+    assert_eq!(truncate_to_width("日本語キー", 4), "日…");
+    assert_eq!(truncate_to_width("日本語キー", 10), "日本語キー");
+}
@@ -0,0 +1,201 @@
+use redis_nav::redis_client::RedisValue;
+use redis_nav::ui::theme::Theme;
+use redis_nav::ui::value_view::{format_score, ValueView};
+
+#[test]
+fn test_text_is_rendered_value() {
+    let theme = Theme::default();
+    let value = RedisValue::String(r#"{"a":1}"#.to_string());
+    let view = ValueView::new(Some(&value), Some("k"), &theme, 0, false, false, None);
+    assert_eq!(view.text().unwrap(), "{\n  \"a\": 1\n}");
+}
+
+#[test]
+fn test_xml_is_rendered_indented() {
+    let theme = Theme::default();
+    let value = RedisValue::String("<a><b>x</b></a>".to_string());
+    let view = ValueView::new(Some(&value), Some("k"), &theme, 0, false, false, None);
+    assert_eq!(view.text().unwrap(), "<a>\n  <b>x</b>\n</a>");
+
+    // Malformed markup is shown as it is
+    let value = RedisValue::String("<a><b>x</a>".to_string());
+    let view = ValueView::new(Some(&value), Some("k"), &theme, 0, false, false, None);
+    assert_eq!(view.text().unwrap(), "<a><b>x</a>");
+}
+
+#[test]
+fn test_text_of_paged_list_uses_absolute_indexes() {
+    let theme = Theme::default();
+    let value = RedisValue::List(vec!["x".to_string(), "y".to_string()]);
+    let view = ValueView::new(Some(&value), Some("k"), &theme, 0, false, false, None).offset(200);
+    assert_eq!(view.text().unwrap(), "[200] x\n[201] y");
+}
+
+#[test]
+fn test_text_without_value() {
+    let theme = Theme::default();
+    let view = ValueView::new(None, None, &theme, 0, false, false, None);
+    assert!(view.text().is_none());
+}
+
+#[test]
+fn test_stream_entries_render_id_then_fields() {
+    let theme = Theme::default();
+    let value = RedisValue::Stream(vec![
+        (
+            "1700000000001-0".to_string(),
+            vec![
+                ("event".to_string(), "login".to_string()),
+                ("user".to_string(), "42".to_string()),
+            ],
+        ),
+        (
+            "1700000000000-0".to_string(),
+            vec![("event".to_string(), "signup".to_string())],
+        ),
+    ]);
+    let view = ValueView::new(Some(&value), Some("k"), &theme, 0, false, false, None);
+    let (lines, label) = view.lines();
+
+    assert_eq!(label, "STREAM");
+    assert_eq!(lines[0].style, theme.title);
+    assert_eq!(
+        view.text().unwrap(),
+        "1700000000001-0\n    event: login\n    user: 42\n1700000000000-0\n    event: signup"
+    );
+}
+
+#[test]
+fn test_value_search_matches() {
+    use redis_nav::ui::value_view::ValueSearch;
+
+    let mut search = ValueSearch {
+        query: "name".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(search.find_in("Name: name"), vec![0..4, 6..10]);
+    search.case_sensitive = true;
+    assert_eq!(search.find_in("Name: name"), vec![6..10]);
+
+    let theme = Theme::default();
+    let value = RedisValue::String(r#"{"name":"a","id":1,"nickname":"b"}"#.to_string());
+    let view = ValueView::new(Some(&value), Some("k"), &theme, 0, false, false, None);
+    let (lines, _) = view.lines();
+    // Pretty-printed with sorted keys: `{`, "id", "name", "nickname", `}`
+    assert_eq!(search.matching_lines(&lines), vec![2, 3]);
+}
+
+#[test]
+fn test_highlight_ranges_splits_spans() {
+    use ratatui::style::{Color, Style};
+    use ratatui::text::{Line, Span};
+    use redis_nav::ui::value_view::highlight_ranges;
+
+    let red = Style::default().fg(Color::Red);
+    let hl = Style::default().bg(Color::Yellow);
+    let line = Line::from(vec![Span::styled("\"key\"", red), Span::raw(": value")]);
+    let line = highlight_ranges(line, &[3..5, 7..9], hl);
+
+    let pieces: Vec<(&str, Style)> = line
+        .spans
+        .iter()
+        .map(|s| (s.content.as_ref(), s.style))
+        .collect();
+    assert_eq!(
+        pieces,
+        vec![
+            ("\"ke", red),
+            ("y\"", red.patch(hl)),
+            (": ", Style::default()),
+            ("va", hl),
+            ("lue", Style::default()),
+        ]
+    );
+}
+
+#[test]
+fn test_line_numbers_are_right_aligned() {
+    use ratatui::style::Style;
+    use ratatui::text::Line;
+    use redis_nav::ui::value_view::add_line_numbers;
+
+    let mut lines: Vec<Line<'static>> = (0..10).map(|i| Line::raw(format!("l{}", i))).collect();
+    add_line_numbers(&mut lines, Style::default());
+    assert_eq!(lines[0].to_string(), " 1 l0");
+    assert_eq!(lines[9].to_string(), "10 l9");
+}
+
+#[test]
+fn test_msgpack_renders_as_json() {
+    let theme = Theme::default();
+    // {"id": 7}
+    let value = RedisValue::Bytes(vec![0x81, 0xa2, b'i', b'd', 0x07]);
+    let view = ValueView::new(Some(&value), Some("k"), &theme, 0, false, false, None);
+
+    assert_eq!(view.lines().1, "MSGPACK");
+    assert_eq!(view.text().unwrap(), "{\n  \"id\": 7\n}");
+}
+
+#[test]
+fn test_line_count_counts_wrapped_rows() {
+    let theme = Theme::default();
+    let value = RedisValue::String(format!("short\n{}", "x".repeat(25)));
+    let view = ValueView::new(Some(&value), Some("k"), &theme, 0, false, false, None);
+
+    assert_eq!(view.line_count(80), 2);
+    // The long line wraps onto three rows of 10
+    assert_eq!(view.line_count(10), 4);
+    // The gutter widens every line
+    assert_eq!(view.line_numbers(true).line_count(80), 2);
+}
+
+#[test]
+fn test_row_of_line_skips_wrapped_rows() {
+    let theme = Theme::default();
+    let value = RedisValue::String(format!("{}\nneedle", "x".repeat(25)));
+    let view = ValueView::new(Some(&value), Some("k"), &theme, 0, false, false, None);
+
+    // Line 1 starts below the three rows of line 0
+    assert_eq!(view.row_of_line(1, 10), 3);
+    // Unwrapped, rows and lines coincide
+    assert_eq!(view.wrap(false, 0).row_of_line(1, 10), 1);
+}
+
+#[test]
+fn test_truncated_value_shows_notice() {
+    let theme = Theme::default();
+    let value = RedisValue::String("abcd".to_string());
+    let view = ValueView::new(Some(&value), Some("k"), &theme, 0, false, false, None)
+        .truncated(Some(1000));
+
+    // The notice is drawn, but not part of the value's text
+    assert_eq!(view.line_count(80), 2);
+    assert_eq!(view.text().unwrap(), "abcd");
+}
+
+#[test]
+fn test_unwrapped_lines_take_one_row_each() {
+    let theme = Theme::default();
+    let value = RedisValue::String(format!("short\n{}", "x".repeat(25)));
+    let view =
+        ValueView::new(Some(&value), Some("k"), &theme, 0, false, false, None).wrap(false, 8);
+
+    assert_eq!(view.line_count(10), 2);
+    assert_eq!(view.max_width(), 25);
+}
+
+#[test]
+fn test_zset_scores_keep_full_precision() {
+    assert_eq!(format_score(3.0, None), "3");
+    assert_eq!(format_score(0.001, None), "0.001");
+    assert_eq!(format_score(1700000000.5, None), "1700000000.5");
+    assert_eq!(format_score(-2.25, None), "-2.25");
+    assert_eq!(format_score(0.001, Some(2)), "0.00");
+
+    let theme = Theme::default();
+    let value = RedisValue::ZSet(vec![("a".to_string(), 0.001), ("b".to_string(), 2.0)]);
+    let view = ValueView::new(Some(&value), Some("k"), &theme, 0, false, false, None);
+    assert_eq!(view.text().unwrap(), "0.001: a\n2: b");
+    let view = view.score_precision(Some(1));
+    assert_eq!(view.text().unwrap(), "0.0: a\n2.0: b");
+}